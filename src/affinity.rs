@@ -0,0 +1,201 @@
+//! Node selectors, node affinity, inter-pod affinity and topology spread constraints of the k8s pod
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// Operator of the node selector requirement
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NodeSelectorOperator {
+    In,
+    NotIn,
+    Exists,
+    DoesNotExist,
+    Gt,
+    Lt,
+}
+
+/// Requirement to the value of a single node label
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeSelectorRequirement {
+    pub key: String,
+    pub operator: NodeSelectorOperator,
+    /// Values for In/NotIn, a single integer for Gt/Lt, empty for Exists/DoesNotExist.
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+impl NodeSelectorRequirement {
+    pub fn new(key: &str, operator: NodeSelectorOperator, values: Vec<&str>) -> Self {
+        Self {
+            key: key.to_string(),
+            operator,
+            values: values.into_iter().map(|value| value.to_string()).collect(),
+        }
+    }
+
+    pub fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        let label = labels.get(&self.key);
+        match self.operator {
+            NodeSelectorOperator::In => label.is_some_and(|value| self.values.contains(value)),
+            NodeSelectorOperator::NotIn => label.is_none_or(|value| !self.values.contains(value)),
+            NodeSelectorOperator::Exists => label.is_some(),
+            NodeSelectorOperator::DoesNotExist => label.is_none(),
+            NodeSelectorOperator::Gt | NodeSelectorOperator::Lt => {
+                let label_value = label.and_then(|value| value.parse::<i64>().ok());
+                let bound = self.values.first().and_then(|value| value.parse::<i64>().ok());
+                match (label_value, bound) {
+                    (Some(label_value), Some(bound)) => {
+                        if self.operator == NodeSelectorOperator::Gt {
+                            label_value > bound
+                        } else {
+                            label_value < bound
+                        }
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+/// Conjunction of the node selector requirements (an empty term matches no nodes)
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeSelectorTerm {
+    #[serde(default)]
+    pub match_expressions: Vec<NodeSelectorRequirement>,
+}
+
+impl NodeSelectorTerm {
+    pub fn new(match_expressions: Vec<NodeSelectorRequirement>) -> Self {
+        Self { match_expressions }
+    }
+
+    pub fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        !self.match_expressions.is_empty() &&
+            self.match_expressions.iter().all(|requirement| requirement.matches(labels))
+    }
+}
+
+/// Node selector term with the weight (for preferred node affinity)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PreferredSchedulingTerm {
+    pub weight: u32,
+    pub preference: NodeSelectorTerm,
+}
+
+/// Node affinity of the pod
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeAffinity {
+    /// Terms of requiredDuringSchedulingIgnoredDuringExecution, node must match one of them
+    /// (no requirements if the list is empty).
+    #[serde(default)]
+    pub required: Vec<NodeSelectorTerm>,
+    /// Terms of preferredDuringSchedulingIgnoredDuringExecution.
+    #[serde(default)]
+    pub preferred: Vec<PreferredSchedulingTerm>,
+}
+
+impl NodeAffinity {
+    pub fn required_matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        self.required.is_empty() || self.required.iter().any(|term| term.matches(labels))
+    }
+
+    /// Returns sum of weights of the matched preferred terms.
+    pub fn preferred_score(&self, labels: &BTreeMap<String, String>) -> u32 {
+        self.preferred.iter()
+            .filter(|term| term.preference.matches(labels))
+            .map(|term| term.weight)
+            .sum()
+    }
+}
+
+/// Selector of pods by their labels (an empty selector matches all pods)
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LabelSelector {
+    #[serde(default)]
+    pub match_labels: BTreeMap<String, String>,
+    /// Requirements to pod labels (only In, NotIn, Exists and DoesNotExist operators make sense).
+    #[serde(default)]
+    pub match_expressions: Vec<NodeSelectorRequirement>,
+}
+
+impl LabelSelector {
+    pub fn from_labels(match_labels: BTreeMap<String, String>) -> Self {
+        Self {
+            match_labels,
+            match_expressions: Vec::default(),
+        }
+    }
+
+    pub fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        self.match_labels.iter().all(|(key, value)| labels.get(key) == Some(value)) &&
+            self.match_expressions.iter().all(|requirement| requirement.matches(labels))
+    }
+}
+
+/// Pods matched by the selector, which run in the same topology domain (nodes with the same
+/// value of the topology key label, e.g. kubernetes.io/hostname or topology.kubernetes.io/zone)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PodAffinityTerm {
+    pub label_selector: LabelSelector,
+    pub topology_key: String,
+}
+
+impl PodAffinityTerm {
+    pub fn new(label_selector: LabelSelector, topology_key: &str) -> Self {
+        Self {
+            label_selector,
+            topology_key: topology_key.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeightedPodAffinityTerm {
+    pub weight: u32,
+    pub pod_affinity_term: PodAffinityTerm,
+}
+
+/// Pod affinity or anti-affinity of the pod
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PodAffinity {
+    /// Terms of requiredDuringSchedulingIgnoredDuringExecution, all of them must be satisfied.
+    #[serde(default)]
+    pub required: Vec<PodAffinityTerm>,
+    /// Terms of preferredDuringSchedulingIgnoredDuringExecution.
+    #[serde(default)]
+    pub preferred: Vec<WeightedPodAffinityTerm>,
+}
+
+/// What to do with the pod if it can't satisfy the topology spread constraint
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum UnsatisfiableConstraintAction {
+    /// Pod isn't placed to nodes which increase the skew above max_skew.
+    #[default]
+    DoNotSchedule,
+    /// Nodes which reduce the skew are preferred.
+    ScheduleAnyway,
+}
+
+/// Spreading of pods matched by the selector across topology domains
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TopologySpreadConstraint {
+    /// Max allowed difference between numbers of matched pods in any two topology domains.
+    pub max_skew: u64,
+    pub topology_key: String,
+    #[serde(default)]
+    pub when_unsatisfiable: UnsatisfiableConstraintAction,
+    pub label_selector: LabelSelector,
+}
+
+impl TopologySpreadConstraint {
+    pub fn new(max_skew: u64, topology_key: &str, when_unsatisfiable: UnsatisfiableConstraintAction,
+               label_selector: LabelSelector) -> Self {
+        Self {
+            max_skew,
+            topology_key: topology_key.to_string(),
+            when_unsatisfiable,
+            label_selector,
+        }
+    }
+}
@@ -1,28 +1,35 @@
 //! Representation of the k8s API server
 
 use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap};
-use std::hash::Hash;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::rc::Rc;
 use dslab_core::cast;
-use crate::pod::Pod;
+use crate::pod::{Pod, PodStatus, QoSClass};
 use dslab_core::context::SimulationContext;
 use dslab_core::event::Event;
 use dslab_core::handler::EventHandler;
-use rand::distributions::uniform::SampleBorrow;
-use crate::node::{Node, NodeState};
+use crate::node::{LABEL_HOSTNAME, Node, NodeState};
 use crate::simulation_config::SimulationConfig;
-use sugars::{rc, refcell};
-use crate::deployment::Deployment;
-use crate::events::node::{AllocateNewDefaultNodes, NodeStatusChanged, RemoveNode};
-use crate::events::assigning::{PodAssigningRequest, PodAssigningSucceeded, PodAssigningFailed, PodPlacementRequest, PodPlacementSucceeded, PodPlacementFailed, PodMigrationRequest};
-use crate::events::api_server::PodRemoveRequest;
-use crate::events::deployment::{DeploymentCreateRequest, DeploymentHorizontalAutoscaling};
-use crate::events::logger::MetricsSnapshot;
+use crate::deployment::{Deployment, DeploymentRollout, DeploymentStrategy, PodTemplate};
+use crate::events::node::{NodeDrainRequest, NodeHeartbeat, NodeStatusChanged, NodeTaintAdded, NodeTaintRemoved, NoExecuteTaintEviction,
+                          NodeRemovalFailed, RemoveNode};
+use crate::events::assigning::{PodAssigningRequest, PodAssigningSucceeded, PodPlacementRequest, PodPlacementSucceeded, PodPlacementFailed, PodMigrationRequest};
+use crate::disruption_budget::{DisruptionBudgetTarget, PodDisruptionBudget};
+use crate::events::api_server::{DisruptionBudgetCreateRequest, DisruptionBudgetRemoveRequest, PodPreemptionRequest,
+                                PodRemoveRequest};
+use crate::events::deployment::{DeploymentCreateRequest, DeploymentHorizontalAutoscaling, DeploymentUpdateRequest};
+use crate::events::logger::{CostAccounting, MetricsSnapshot};
+use crate::events::pod::{PodOOMKilled, PodRestarted, PodStarted, PodSucceeded, PodTerminated};
 use crate::events::scheduler::MoveRequest;
+use crate::fault_injector::FaultInjector;
+use crate::job_controller::JobController;
 use crate::metrics_server::MetricsServer;
+use crate::network::NetworkModel;
 use crate::scheduler::Scheduler;
-use crate::simulation_metrics::{Metrics, MetricsLogger};
+use crate::cluster_autoscaler::ClusterAutoscaler;
+use crate::simulation_metrics::{CapacityStats, CostReport, FaultInjectionStats, Metrics, MetricsLogger, NodeOutage,
+                                OOMKill, Preemption, Rollout};
+use crate::taints::{Taint, TAINT_UNSCHEDULABLE, TaintEffect, toleration_time};
 
 pub struct APIServer {
     pub id: u32,
@@ -32,26 +39,58 @@ pub struct APIServer {
     pub deployment_to_replicas: HashMap<u64, Vec<u64>>,
     pub deployments_start_time: HashMap<u64, f64>,
     pub deployments: HashMap<u64, Deployment>,
+    /// The last rollout of every updated deployment.
+    pub deployment_rollouts: BTreeMap<u64, DeploymentRollout>,
+    pub disruption_budgets: BTreeMap<u64, PodDisruptionBudget>,
+    /// Time of the last heartbeat of every node.
+    pub node_heartbeats: BTreeMap<u32, f64>,
+    /// Time of the failure of failed nodes (known only to the simulation, not to controllers).
+    pub node_failure_times: BTreeMap<u32, f64>,
+    /// Time since which every node is in the cluster (working or failed).
+    node_start_times: BTreeMap<u32, f64>,
+    /// Total time in the cluster of nodes which were removed from it (nodes can be added again).
+    node_past_uptimes: BTreeMap<u32, f64>,
+    /// Pods removed before their placement (they are dropped when the scheduler or the node returns them).
+    removed_pod_ids: HashSet<u64>,
 
     scheduler: Option<Rc<RefCell<Scheduler>>>,
+    cluster_autoscaler: Option<Rc<RefCell<ClusterAutoscaler>>>,
     metrics_server: Option<Rc<RefCell<MetricsServer>>>,
+    job_controller: Option<Rc<RefCell<JobController>>>,
+    fault_injector: Option<Rc<RefCell<FaultInjector>>>,
+    network: Rc<RefCell<NetworkModel>>,
 
     ctx: SimulationContext,
     sim_config: Rc<SimulationConfig>,
 
     metrics_logger: Box<dyn MetricsLogger>,
     pod_migration_count: u64,
+    preemption_count: u64,
+    preemptions: Vec<Preemption>,
+    oom_kill_count: u64,
+    oom_kills: Vec<OOMKill>,
+    restart_count: u64,
+    rollouts: Vec<Rollout>,
+    blocked_eviction_count: u64,
+    /// Pods which evictions are blocked, they are counted once until the eviction is allowed.
+    blocked_pod_ids: BTreeSet<u64>,
+    node_outages: Vec<NodeOutage>,
+    cost: CostReport,
+    /// Time up to which costs of nodes are accounted.
+    last_cost_accounting_time: f64,
 
     pod_counter: u64,
     deployment_counter: u64,
+    disruption_budget_counter: u64,
 }
 
 impl APIServer {
-    pub fn new(ctx: SimulationContext, sim_config: Rc<SimulationConfig>,
+    pub fn new(network: Rc<RefCell<NetworkModel>>, ctx: SimulationContext, sim_config: Rc<SimulationConfig>,
                metrics_logger: Box<dyn MetricsLogger>) -> Self {
         if metrics_logger.snapshot_period() > 0.0 {
             ctx.emit(MetricsSnapshot {}, ctx.id(), metrics_logger.snapshot_period());
         }
+        ctx.emit(CostAccounting {}, ctx.id(), sim_config.cost_accounting_interval);
 
         Self {
             id: ctx.id(),
@@ -61,14 +100,37 @@ impl APIServer {
             deployment_to_replicas: HashMap::default(),
             deployments_start_time: HashMap::default(),
             deployments: HashMap::default(),
+            deployment_rollouts: BTreeMap::default(),
+            disruption_budgets: BTreeMap::default(),
+            node_heartbeats: BTreeMap::default(),
+            node_failure_times: BTreeMap::default(),
+            node_start_times: BTreeMap::default(),
+            node_past_uptimes: BTreeMap::default(),
+            removed_pod_ids: HashSet::default(),
             scheduler: None,
+            cluster_autoscaler: None,
             metrics_server: None,
+            job_controller: None,
+            fault_injector: None,
+            network,
             ctx,
             sim_config,
             metrics_logger,
             pod_migration_count: 0,
+            preemption_count: 0,
+            preemptions: Vec::default(),
+            oom_kill_count: 0,
+            oom_kills: Vec::default(),
+            restart_count: 0,
+            rollouts: Vec::default(),
+            blocked_eviction_count: 0,
+            blocked_pod_ids: BTreeSet::default(),
+            node_outages: Vec::default(),
+            cost: CostReport::default(),
+            last_cost_accounting_time: 0.0,
             pod_counter: 0,
             deployment_counter: 0,
+            disruption_budget_counter: 0,
         }
     }
 
@@ -76,43 +138,121 @@ impl APIServer {
         self.scheduler = Some(scheduler);
     }
 
+    pub fn set_cluster_autoscaler(&mut self, cluster_autoscaler: Rc<RefCell<ClusterAutoscaler>>) {
+        self.cluster_autoscaler = Some(cluster_autoscaler);
+    }
+
     pub fn set_metrics_server(&mut self, metrics_server: Rc<RefCell<MetricsServer>>) {
         self.metrics_server = Some(metrics_server);
     }
 
+    pub fn set_job_controller(&mut self, job_controller: Rc<RefCell<JobController>>) {
+        self.job_controller = Some(job_controller);
+    }
+
+    pub fn set_fault_injector(&mut self, fault_injector: Rc<RefCell<FaultInjector>>) {
+        self.fault_injector = Some(fault_injector);
+    }
+
 
     /// Add new node to the working nodes
     pub fn add_new_node(&mut self, node: Rc<RefCell<Node>>) {
+        self.account_costs();
         node.borrow_mut().state = NodeState::Working;
+        self.node_heartbeats.insert(node.borrow().id, self.ctx.time());
+        self.node_start_times.insert(node.borrow().id, self.ctx.time());
         self.working_nodes.insert(node.borrow().id, node.clone());
-        self.ctx.emit(MoveRequest {}, self.scheduler.clone().unwrap().borrow().id,
-                      self.sim_config.control_plane_message_delay);
+        if self.fault_injector.is_some() {
+            let node = node.borrow();
+            self.fault_injector.as_ref().unwrap().borrow_mut().on_node_added(node.id, &node.labels);
+        }
+        self.network.borrow_mut().send(&self.ctx, MoveRequest {}, self.scheduler.clone().unwrap().borrow().id,
+                                       self.sim_config.control_plane_message_delay);
     }
 
     /// Recover node from the failed nodes
     pub fn recover_node(&mut self, node_id: u32) {
-        let mut node = self.failed_nodes.remove(&node_id).unwrap();
+        self.account_costs();
+        let node = self.failed_nodes.remove(&node_id).unwrap();
         node.borrow_mut().state = NodeState::Working;
         self.working_nodes.insert(node_id, node);
     }
 
-    /// Remove node from cluster (from working nodes)
-    pub fn remove_node(&mut self, node_id: u32) {
+    /// Remove node from cluster (from working nodes), the node isn't removed
+    /// if evictions of its pods are blocked by disruption budgets, returns whether the node is removed
+    pub fn remove_node(&mut self, node_id: u32) -> bool {
+        if !self.working_nodes.contains_key(&node_id) {
+            return true;
+        }
+        let pod_ids: Vec<u64> = self.working_nodes.get(&node_id).unwrap().borrow().pods.keys().cloned().collect();
+        if !self.check_evictions(&pod_ids) {
+            return false;
+        }
+        self.detach_node(node_id);
+        true
+    }
+
+    /// Remove the working or failed node from the cluster regardless of disruption budgets
+    /// (e.g. it's reclaimed by the cloud), returns count of its pods which are rescheduled
+    pub fn reclaim_node(&mut self, node_id: u32) -> u64 {
+        let node = self.get_node(node_id);
+        if node.is_none() {
+            return 0;
+        }
+        let pod_count = node.unwrap().borrow().pods.len() as u64;
+        if self.failed_nodes.contains_key(&node_id) {
+            self.account_costs();
+            let node = self.failed_nodes.remove(&node_id).unwrap();
+            self.working_nodes.insert(node_id, node);
+        }
+        self.detach_node(node_id);
+        pod_count
+    }
+
+    /// Total time of the node in the cluster (in seconds)
+    pub fn node_uptime(&self, node_id: u32) -> f64 {
+        let past_uptime = *self.node_past_uptimes.get(&node_id).unwrap_or(&0.0);
+        let current_uptime = self.node_start_times.get(&node_id).map_or(0.0, |time| self.ctx.time() - time);
+        past_uptime + current_uptime
+    }
+
+    /// Remove the working node, its pods are returned to the scheduler
+    fn detach_node(&mut self, node_id: u32) {
+        self.account_costs();
+        let start_time = self.node_start_times.remove(&node_id);
+        if start_time.is_some() {
+            *self.node_past_uptimes.entry(node_id).or_default() += self.ctx.time() - start_time.unwrap();
+        }
         let node = self.working_nodes.remove(&node_id).unwrap();
-        let node = node.borrow_mut();
+        let mut node = node.borrow_mut();
         for (_, pod) in node.pods.clone().into_iter() {
             self.ctx.emit(PodAssigningRequest { pod }, self.id, 0.0);
         }
+        // the node can be added to the cluster again by the cluster autoscaler
+        node.pods.clear();
+        node.terminating_pods.clear();
+        node.cpu_allocated = 0.0;
+        node.memory_allocated = 0.0;
+        node.cpu_used = 0.0;
+        node.memory_used = 0.0;
     }
 
     /// Crash node (from working nodes)
     pub fn crash_node(&mut self, node_id: u32) {
+        self.account_costs();
         let node = self.working_nodes.remove(&node_id).unwrap();
+        if self.fault_injector.is_some() {
+            let failure_time = self.node_failure_times.get(&node_id).cloned().unwrap_or(self.ctx.time());
+            let pods: Vec<Pod> = node.borrow().pods.values().cloned().collect();
+            self.fault_injector.as_ref().unwrap().borrow_mut().on_node_crashed(node_id, failure_time, &pods);
+        }
         let mut mut_node = node.borrow_mut();
-        for (pod_id, pod) in mut_node.pods.clone().into_iter() {
+        for (_, mut pod) in mut_node.pods.clone().into_iter() {
+            pod.status = PodStatus::Pending;
             self.ctx.emit(PodAssigningRequest { pod }, self.id, 0.0);
         }
         mut_node.pods.clear();
+        mut_node.terminating_pods.clear();
         mut_node.state = NodeState::Failed;
         mut_node.cpu_allocated = 0.0;
         mut_node.memory_allocated = 0.0;
@@ -122,6 +262,117 @@ impl APIServer {
         self.failed_nodes.insert(node_id, node);
     }
 
+    /// Change the state of the node without the knowledge of the control plane,
+    /// the failure is detected by the node lifecycle controller when heartbeats are missed
+    fn set_node_state(&mut self, node_id: u32, new_status: NodeState) {
+        let node = self.get_node(node_id);
+        if node.is_none() {
+            return;
+        }
+        self.account_costs();
+        if new_status == NodeState::Failed {
+            self.node_failure_times.insert(node_id, self.ctx.time());
+        } else {
+            self.node_failure_times.remove(&node_id);
+        }
+        node.unwrap().borrow_mut().state = new_status;
+    }
+
+    pub fn add_node_outage(&mut self, outage: NodeOutage) {
+        self.node_outages.push(outage);
+    }
+
+    /// Working or failed node of the cluster
+    pub fn get_node(&self, node_id: u32) -> Option<Rc<RefCell<Node>>> {
+        self.working_nodes.get(&node_id).or_else(|| self.failed_nodes.get(&node_id)).cloned()
+    }
+
+    /// Add taint to the node (replaces the taint with the same key and effect),
+    /// pods which don't tolerate NoExecute taint are evicted
+    pub fn add_node_taint(&mut self, node_id: u32, taint: Taint) {
+        self.add_node_taint_with_default_toleration(node_id, taint, 0.0);
+    }
+
+    /// Add taint to the node, pods without tolerations of NoExecute taint tolerate it
+    /// for the default time (like the DefaultTolerationSeconds admission of k8s)
+    pub fn add_node_taint_with_default_toleration(&mut self, node_id: u32, taint: Taint, default_toleration_time: f64) {
+        let node = self.get_node(node_id);
+        if node.is_none() {
+            return;
+        }
+        let node = node.unwrap();
+
+        if taint.effect == TaintEffect::NoExecute {
+            for (pod_id, pod) in node.borrow().pods.iter() {
+                let tolerated_time = toleration_time(&pod.constraints.tolerations, &taint);
+                if tolerated_time.is_some_and(|time| time.is_infinite()) {
+                    continue;
+                }
+                // the node evicts the pod, unless the taint is removed before
+                self.network.borrow_mut().send_reliable(&self.ctx, NoExecuteTaintEviction { pod_id: *pod_id }, node_id,
+                    self.sim_config.message_delay + tolerated_time.unwrap_or(default_toleration_time));
+            }
+        }
+
+        let mut node = node.borrow_mut();
+        node.taints.retain(|node_taint| node_taint.key != taint.key || node_taint.effect != taint.effect);
+        node.taints.push(taint);
+    }
+
+    /// Remove taint with the key and effect from the node
+    pub fn remove_node_taint(&mut self, node_id: u32, key: &str, effect: TaintEffect) {
+        let node = self.get_node(node_id);
+        if node.is_none() {
+            return;
+        }
+        node.unwrap().borrow_mut().taints.retain(|taint| taint.key != key || taint.effect != effect);
+        self.network.borrow_mut().send(&self.ctx, MoveRequest {}, self.scheduler.clone().unwrap().borrow().id,
+                                       self.sim_config.control_plane_message_delay);
+    }
+
+    /// Mark the node unschedulable (with the NoSchedule taint), its pods keep working
+    pub fn cordon_node(&mut self, node_id: u32) {
+        self.add_node_taint(node_id, Taint::new(TAINT_UNSCHEDULABLE, "", TaintEffect::NoSchedule));
+    }
+
+    pub fn uncordon_node(&mut self, node_id: u32) {
+        self.remove_node_taint(node_id, TAINT_UNSCHEDULABLE, TaintEffect::NoSchedule);
+    }
+
+    /// Cordon the node and evict its pods which evictions are allowed by disruption budgets
+    /// (they are terminated gracefully), returns whether the node has neither pods nor terminating pods
+    pub fn drain_node(&mut self, node_id: u32) -> bool {
+        let node = self.working_nodes.get(&node_id);
+        if node.is_none() {
+            return true;
+        }
+        let node = node.unwrap().clone();
+        self.cordon_node(node_id);
+
+        let pod_ids: Vec<u64> = node.borrow().pods.keys().cloned().collect();
+        for pod_id in pod_ids {
+            if self.check_evictions(&[pod_id]) {
+                self.evict_pod(&node, pod_id);
+            }
+        }
+        let node = node.borrow();
+        node.pods.is_empty() && node.terminating_pods.is_empty()
+    }
+
+    /// Remove the pod from the node (it's terminated gracefully) and return it to the scheduler
+    fn evict_pod(&mut self, node: &Rc<RefCell<Node>>, pod_id: u64) -> bool {
+        let pod = node.borrow_mut().remove_pod(pod_id);
+        if pod.is_none() {
+            return false;
+        }
+        if self.metrics_server.is_some() {
+            self.metrics_server.clone().unwrap().borrow_mut().clear_pod_statistics(pod_id);
+        }
+        self.pod_to_node_map.remove(&pod_id);
+        self.scheduler.clone().unwrap().borrow_mut().add_pod(pod.unwrap());
+        true
+    }
+
     pub fn remove_pod(&mut self, pod_id: u64) {
         if self.metrics_server.is_some() {
             self.metrics_server.clone().unwrap().borrow_mut().clear_pod_statistics(pod_id);
@@ -136,6 +387,45 @@ impl APIServer {
         self.pod_to_node_map.remove(&pod_id);
     }
 
+    /// Remove the pod from the cluster, also if it isn't placed yet
+    /// (from the scheduler's queues or when it's returned by the scheduler or the node)
+    pub fn delete_pod(&mut self, pod_id: u64) {
+        if self.pod_to_node_map.contains_key(&pod_id) {
+            self.remove_pod(pod_id);
+        } else if !self.scheduler.clone().unwrap().borrow_mut().remove_pod(pod_id) {
+            self.removed_pod_ids.insert(pod_id);
+        }
+    }
+
+    /// Evict victims of the preemption from the node and return them to the scheduler
+    pub fn preempt_pods(&mut self, preemptor_id: u64, node_id: u32, victim_ids: Vec<u64>) {
+        let node = self.working_nodes.get(&node_id);
+        if node.is_none() {
+            return;
+        }
+        let node = node.unwrap().clone();
+
+        let mut evicted_ids = Vec::default();
+        for victim_id in victim_ids {
+            if self.evict_pod(&node, victim_id) {
+                evicted_ids.push(victim_id);
+            }
+        }
+        if evicted_ids.is_empty() {
+            return;
+        }
+
+        self.preemption_count += 1;
+        self.preemptions.push(Preemption {
+            timestamp: self.ctx.time(),
+            preemptor_id,
+            node_id,
+            victim_ids: evicted_ids,
+        });
+        self.network.borrow_mut().send(&self.ctx, MoveRequest {}, self.scheduler.clone().unwrap().borrow().id,
+                                       self.sim_config.control_plane_message_delay);
+    }
+
     /// Get list of working nodes
     pub fn get_working_nodes(&self) -> &BTreeMap<u32, Rc<RefCell<Node>>> {
         &self.working_nodes
@@ -150,7 +440,7 @@ impl APIServer {
 
         let mut replica_cnt: u64 = 0;
         for pod_id in replica_ids {
-            if self.pod_to_node_map.get(pod_id).is_none() {
+            if !self.pod_to_node_map.contains_key(pod_id) {
                 continue;
             }
             replica_cnt += 1;
@@ -166,11 +456,214 @@ impl APIServer {
         *start_time.unwrap()
     }
 
+    /// Check whether the pod is placed and its container is running
+    fn is_pod_available(&self, pod_id: u64) -> bool {
+        let node_id = self.pod_to_node_map.get(&pod_id);
+        if node_id.is_none() {
+            return false;
+        }
+        let node = self.working_nodes.get(node_id.unwrap());
+        if node.is_none() {
+            return false;
+        }
+        node.unwrap().borrow().pods.get(&pod_id)
+            .is_some_and(|pod| pod.status == PodStatus::Running && pod.container_start_time.is_none())
+    }
+
+    /// Returns ids of pods covered by the disruption budget and the expected count of them
+    fn disruption_budget_pods(&self, budget: &PodDisruptionBudget) -> (Vec<u64>, u64) {
+        match &budget.target {
+            DisruptionBudgetTarget::Deployment { deployment_id } => {
+                let replica_ids = self.deployment_to_replicas.get(deployment_id).cloned().unwrap_or_default();
+                let expected_count = self.deployments.get(deployment_id)
+                    .map_or(replica_ids.len() as u64, |deployment| deployment.cnt_replicas);
+                (replica_ids, expected_count)
+            }
+            DisruptionBudgetTarget::Selector { selector } => {
+                let mut pod_ids = Vec::<u64>::default();
+                for (_, node) in self.working_nodes.iter() {
+                    for (pod_id, pod) in node.borrow().pods.iter() {
+                        if selector.matches(&pod.labels) {
+                            pod_ids.push(*pod_id);
+                        }
+                    }
+                }
+                let expected_count = pod_ids.len() as u64;
+                (pod_ids, expected_count)
+            }
+        }
+    }
+
+    /// Returns pods (of the ones evicted together, in their order) which evictions
+    /// would leave less available pods than some disruption budget desires
+    pub fn pods_blocked_by_budgets(&self, pod_ids: &[u64]) -> BTreeSet<u64> {
+        let mut blocked_pod_ids = BTreeSet::<u64>::default();
+        for (_, budget) in self.disruption_budgets.iter() {
+            let (budget_pod_ids, expected_count) = self.disruption_budget_pods(budget);
+            let available_count = self.available_pods_count(&budget_pod_ids);
+            let allowed_count = available_count.saturating_sub(budget.desired_available(expected_count));
+            // evictions of unavailable pods don't decrease the availability
+            let evicted_ids = pod_ids.iter()
+                .filter(|pod_id| budget_pod_ids.contains(*pod_id) && self.is_pod_available(**pod_id));
+            blocked_pod_ids.extend(evicted_ids.skip(allowed_count as usize));
+        }
+        blocked_pod_ids
+    }
+
+    /// Check disruption budgets before voluntary evictions of the pods, blocked evictions are counted
+    /// once per pod until its eviction is allowed (retries of drains and removals aren't counted)
+    pub fn check_evictions(&mut self, pod_ids: &[u64]) -> bool {
+        let blocked_pod_ids = self.pods_blocked_by_budgets(pod_ids);
+        if blocked_pod_ids.is_empty() {
+            for pod_id in pod_ids {
+                self.blocked_pod_ids.remove(pod_id);
+            }
+            return true;
+        }
+        self.add_blocked_evictions(blocked_pod_ids);
+        false
+    }
+
+    /// Check disruption budgets before the voluntary eviction of the pod in addition to the pods
+    /// evicted already, the blocked eviction is counted like in `check_evictions`
+    pub fn check_additional_eviction(&mut self, pod_id: u64, evicted_pod_ids: &[u64]) -> bool {
+        let mut pod_ids = evicted_pod_ids.to_vec();
+        pod_ids.push(pod_id);
+        if !self.pods_blocked_by_budgets(&pod_ids).contains(&pod_id) {
+            self.blocked_pod_ids.remove(&pod_id);
+            return true;
+        }
+        self.add_blocked_evictions(BTreeSet::from([pod_id]));
+        false
+    }
+
+    fn add_blocked_evictions(&mut self, pod_ids: BTreeSet<u64>) {
+        for pod_id in pod_ids {
+            if self.blocked_pod_ids.insert(pod_id) {
+                self.blocked_eviction_count += 1;
+            }
+        }
+    }
+
+    pub fn blocked_eviction_count(&self) -> u64 {
+        self.blocked_eviction_count
+    }
+
+    fn available_pods_count(&self, pod_ids: &[u64]) -> u64 {
+        pod_ids.iter().filter(|pod_id| self.is_pod_available(**pod_id)).count() as u64
+    }
+
+    /// Replace the pod template of the deployment, its replicas are replaced according to the strategy
+    pub fn update_deployment(&mut self, id: u64, pod_template: PodTemplate, strategy: DeploymentStrategy) {
+        let deployment = self.deployments.get_mut(&id);
+        if deployment.is_none() {
+            return;
+        }
+        let deployment = deployment.unwrap();
+        deployment.pod_template = pod_template;
+        deployment.strategy = strategy;
+        deployment.revision += 1;
+        let revision = deployment.revision;
+
+        let replica_ids = self.deployment_to_replicas.get(&id).cloned().unwrap_or_default();
+        let rollout = DeploymentRollout::new(revision, self.ctx.time(), replica_ids.iter().cloned().collect(),
+                                             self.available_pods_count(&replica_ids));
+        self.deployment_rollouts.insert(id, rollout);
+        self.sync_rollout(id);
+    }
+
+    /// Create new replicas and remove old ones as far as the strategy allows,
+    /// the rollout is finished when all replicas are new and available
+    fn sync_rollout(&mut self, id: u64) {
+        let rollout = self.deployment_rollouts.get(&id);
+        if rollout.is_none() || rollout.unwrap().finish_time.is_some() {
+            return;
+        }
+        let mut old_replica_ids = rollout.unwrap().old_replica_ids.clone();
+        let deployment = self.deployments.get(&id).unwrap().clone();
+        let mut replicas = self.deployment_to_replicas.remove(&id).unwrap_or_default();
+        // replicas could be removed by the horizontal autoscaler
+        old_replica_ids.retain(|pod_id| replicas.contains(pod_id));
+
+        let mut to_remove = Vec::<u64>::default();
+        let mut new_replicas_count = deployment.cnt_replicas;
+        match deployment.strategy {
+            DeploymentStrategy::RollingUpdate { max_surge, max_unavailable } => {
+                let min_available = deployment.cnt_replicas.saturating_sub(max_unavailable);
+                let mut available_count = self.available_pods_count(&replicas);
+                // unavailable old replicas are removed first, they don't decrease the availability
+                let mut old_ids: Vec<u64> = old_replica_ids.iter().cloned().collect();
+                old_ids.sort_by_key(|pod_id| self.is_pod_available(*pod_id));
+                for pod_id in old_ids {
+                    let available = self.is_pod_available(pod_id);
+                    if available {
+                        if available_count <= min_available {
+                            break;
+                        }
+                        available_count -= 1;
+                    }
+                    to_remove.push(pod_id);
+                }
+
+                let replicas_count = (replicas.len() - to_remove.len()) as u64;
+                let old_count = (old_replica_ids.len() - to_remove.len()) as u64;
+                new_replicas_count = (replicas_count - old_count + (deployment.cnt_replicas + max_surge)
+                    .saturating_sub(replicas_count)).min(deployment.cnt_replicas);
+            }
+            DeploymentStrategy::Recreate => {
+                to_remove = old_replica_ids.iter().cloned().collect();
+            }
+        }
+
+        for pod_id in to_remove {
+            self.delete_pod(pod_id);
+            replicas.retain(|replica_id| *replica_id != pod_id);
+            old_replica_ids.remove(&pod_id);
+        }
+
+        let old_replicas_terminating = self.working_nodes.values()
+            .any(|node| node.borrow().terminating_pods.iter().any(|pod| pod.deployment_id == Some(id)));
+        if deployment.strategy != DeploymentStrategy::Recreate || !old_replicas_terminating {
+            let scheduler = self.scheduler.clone().unwrap();
+            while ((replicas.len() - old_replica_ids.len()) as u64) < new_replicas_count {
+                let pod_id = self.generate_pod_id();
+                scheduler.borrow_mut().add_pod(deployment.create_new_replica(pod_id));
+                replicas.push(pod_id);
+            }
+        }
+
+        let available_count = self.available_pods_count(&replicas);
+        let finished = old_replica_ids.is_empty() && available_count == deployment.cnt_replicas &&
+            replicas.len() as u64 == deployment.cnt_replicas;
+        self.deployment_to_replicas.insert(id, replicas);
+
+        let rollout = self.deployment_rollouts.get_mut(&id).unwrap();
+        rollout.old_replica_ids = old_replica_ids;
+        rollout.min_available_replicas = rollout.min_available_replicas.min(available_count);
+        if finished {
+            rollout.finish_time = Some(self.ctx.time());
+            self.rollouts.push(Rollout {
+                deployment_id: id,
+                revision: rollout.revision,
+                start_time: rollout.start_time,
+                duration: self.ctx.time() - rollout.start_time,
+                min_available_replicas: rollout.min_available_replicas,
+            });
+        }
+    }
+
+    fn sync_rollouts(&mut self) {
+        let deployment_ids: Vec<u64> = self.deployment_rollouts.keys().cloned().collect();
+        for id in deployment_ids {
+            self.sync_rollout(id);
+        }
+    }
+
     /// Returns the average allocated CPU across all working nodes.
     pub fn average_cpu_allocated(&self) -> f64 {
         let mut sum_cpu_load: f64 = 0.0;
-        for (node_id, node) in self.working_nodes.iter() {
-            sum_cpu_load += (node.borrow().cpu_allocated as f64);
+        for node in self.working_nodes.values() {
+            sum_cpu_load += node.borrow().cpu_allocated as f64;
         }
         sum_cpu_load / (self.working_nodes.len() as f64)
     }
@@ -178,7 +671,7 @@ impl APIServer {
     /// Returns the average allocated memory across all working nodes.
     pub fn average_memory_allocated(&self) -> f64 {
         let mut sum_memory_load: f64 = 0.0;
-        for (node_id, node) in self.working_nodes.iter() {
+        for node in self.working_nodes.values() {
             sum_memory_load += node.borrow().memory_allocated;
         }
         sum_memory_load / (self.working_nodes.len() as f64)
@@ -188,7 +681,7 @@ impl APIServer {
     pub fn cpu_allocated_load_rate(&self) -> f64 {
         let mut sum_cpu_load: f64 = 0.0;
         let mut sum_cpu_total: f64 = 0.0;
-        for (node_id, node) in self.working_nodes.iter() {
+        for node in self.working_nodes.values() {
             sum_cpu_load += node.borrow().cpu_allocated as f64;
             sum_cpu_total += node.borrow().cpu_total as f64;
         }
@@ -209,8 +702,8 @@ impl APIServer {
     /// Returns the average used CPU across all working nodes.
     pub fn average_cpu_used(&self) -> f64 {
         let mut sum_cpu_load: f64 = 0.0;
-        for (node_id, node) in self.working_nodes.iter() {
-            sum_cpu_load += (node.borrow().cpu_used as f64);
+        for node in self.working_nodes.values() {
+            sum_cpu_load += node.borrow().cpu_used as f64;
         }
         sum_cpu_load / (self.working_nodes.len() as f64)
     }
@@ -218,7 +711,7 @@ impl APIServer {
     /// Returns the average used memory across all working nodes.
     pub fn average_memory_used(&self) -> f64 {
         let mut sum_memory_load: f64 = 0.0;
-        for (node_id, node) in self.working_nodes.iter() {
+        for node in self.working_nodes.values() {
             sum_memory_load += node.borrow().memory_used;
         }
         sum_memory_load / (self.working_nodes.len() as f64)
@@ -228,7 +721,7 @@ impl APIServer {
     pub fn cpu_used_load_rate(&self) -> f64 {
         let mut sum_cpu_load: f64 = 0.0;
         let mut sum_cpu_total: f64 = 0.0;
-        for (node_id, node) in self.working_nodes.iter() {
+        for node in self.working_nodes.values() {
             sum_cpu_load += node.borrow().cpu_used as f64;
             sum_cpu_total += node.borrow().cpu_total as f64;
         }
@@ -254,6 +747,27 @@ impl APIServer {
         memory_overuse_count
     }
 
+    /// Cpu wanted by pods, but not granted to them (summary for all pods on working nodes)
+    pub fn throttled_cpu(&self) -> f64 {
+        let mut throttled_cpu: f64 = 0.0;
+        for (_, node) in self.working_nodes.iter() {
+            for pod in node.borrow().pods.values() {
+                throttled_cpu += pod.throttled_cpu as f64;
+            }
+        }
+        throttled_cpu
+    }
+
+    pub fn evicted_pods_per_qos(&self) -> BTreeMap<QoSClass, u64> {
+        let mut evicted_pods_per_qos = BTreeMap::<QoSClass, u64>::default();
+        for (_, node) in self.working_nodes.iter() {
+            for (qos_class, count) in node.borrow().evictions_per_qos.iter() {
+                *evicted_pods_per_qos.entry(*qos_class).or_insert(0) += count;
+            }
+        }
+        evicted_pods_per_qos
+    }
+
     pub fn deployments_cpu_utilization(&self) -> f64 {
         let mut sum_cpu_utilization: f64 = 0.0;
         for (_, deployment) in self.deployments.iter() {
@@ -328,7 +842,125 @@ impl APIServer {
         }
     }
 
+    pub fn preemption_count(&self) -> u64 {
+        self.preemption_count
+    }
+
+    pub fn oom_kill_count(&self) -> u64 {
+        self.oom_kill_count
+    }
+
+    pub fn restart_count(&self) -> u64 {
+        self.restart_count
+    }
+
+    /// Returns the skew (difference between max and min numbers of placed replicas in topology domains)
+    /// of every deployment: max over topology keys of its spread constraints, across nodes if there are none.
+    pub fn deployments_skew(&self) -> BTreeMap<u64, u64> {
+        let mut deployments_skew = BTreeMap::<u64, u64>::default();
+        for (_, deployment) in self.deployments.iter() {
+            let replica_ids = self.deployment_to_replicas.get(&deployment.id);
+            if replica_ids.is_none() {
+                continue
+            }
+            let replica_ids = replica_ids.unwrap();
+
+            let constraints = &deployment.pod_template.constraints;
+            let mut topology_keys: Vec<&str> = constraints.topology_spread_constraints.iter()
+                .map(|constraint| constraint.topology_key.as_str())
+                .collect();
+            if topology_keys.is_empty() {
+                topology_keys.push(LABEL_HOSTNAME);
+            }
+
+            let mut skew: u64 = 0;
+            for topology_key in topology_keys {
+                let mut counts = HashMap::<String, u64>::default();
+                for (_, node) in self.working_nodes.iter() {
+                    let node = node.borrow();
+                    if !constraints.matches_node_labels(&node.labels) {
+                        continue;
+                    }
+                    if let Some(value) = node.labels.get(topology_key) {
+                        let replicas_count = replica_ids.iter()
+                            .filter(|pod_id| node.pods.contains_key(*pod_id))
+                            .count() as u64;
+                        *counts.entry(value.clone()).or_insert(0) += replicas_count;
+                    }
+                }
+                let max_count = counts.values().cloned().max().unwrap_or(0);
+                let min_count = counts.values().cloned().min().unwrap_or(0);
+                skew = skew.max(max_count - min_count);
+            }
+            deployments_skew.insert(deployment.id, skew);
+        }
+        deployments_skew
+    }
+
+    /// Add costs of nodes since the last accounting (it's called before every change of the set or the state
+    /// of nodes). The cost of every working node is split between deployments by requests of its pods, resources
+    /// allocated to pods but not used are wasted, not requested ones are idle (shares are integrated over time
+    /// by nodes). Failed nodes are billed too, their cost is accounted separately.
+    pub fn account_costs(&mut self) {
+        let seconds = self.ctx.time() - self.last_cost_accounting_time;
+        self.last_cost_accounting_time = self.ctx.time();
+        if seconds <= 0.0 {
+            return;
+        }
+        let hours = seconds / 3600.0;
+        for node in self.failed_nodes.values() {
+            let cost = node.borrow().price * hours;
+            self.cost.node_hours += hours;
+            self.cost.total_cost += cost;
+            self.cost.failed_cost += cost;
+        }
+        for node in self.working_nodes.values() {
+            let mut node = node.borrow_mut();
+            let shares = node.take_share_integrals();
+            let cost = node.price * hours;
+            self.cost.node_hours += hours;
+            self.cost.total_cost += cost;
+            // with node_lifecycle failed nodes stay working in the control plane until their pods are evicted
+            if node.state == NodeState::Failed {
+                self.cost.failed_cost += cost;
+                continue;
+            }
+            if cost == 0.0 {
+                continue;
+            }
+
+            // cost of the share-second of the node
+            let unit_cost = cost / seconds;
+            for (deployment_id, share) in shares.deployments {
+                *self.cost.deployment_costs.entry(deployment_id).or_default() += unit_cost * share;
+            }
+            self.cost.wasted_cost += unit_cost * shares.wasted;
+            self.cost.idle_cost += unit_cost * (seconds - shares.requested).max(0.0);
+        }
+    }
+
+    pub fn cost_report(&self) -> CostReport {
+        self.cost.clone()
+    }
+
     pub fn log_metrics(&mut self) {
+        self.account_costs();
+        let mut job_completions = Vec::default();
+        let mut jobs_makespan = 0.0;
+        if self.job_controller.is_some() {
+            let mut job_controller = self.job_controller.as_ref().unwrap().borrow_mut();
+            job_completions = job_controller.take_job_completions();
+            jobs_makespan = job_controller.makespan();
+        }
+        let mut fault_injection = FaultInjectionStats::default();
+        if self.fault_injector.is_some() {
+            fault_injection = self.fault_injector.as_ref().unwrap().borrow().stats.clone();
+        }
+        let (mut spot, mut on_demand) = (CapacityStats::default(), CapacityStats::default());
+        if self.cluster_autoscaler.is_some() {
+            (spot, on_demand) = self.cluster_autoscaler.as_ref().unwrap().borrow().capacity_stats(self);
+        }
+
         let metrics = Metrics::new(
             self.ctx.time(),
             self.average_cpu_allocated(),
@@ -345,6 +977,24 @@ impl APIServer {
             self.deployments_cpu_utilization(),
             self.deployments_memory_utilization(),
             self.pod_to_node_map.len() as u64,
+            self.deployments_skew(),
+            self.preemption_count,
+            std::mem::take(&mut self.preemptions),
+            self.evicted_pods_per_qos(),
+            self.oom_kill_count,
+            self.restart_count,
+            std::mem::take(&mut self.oom_kills),
+            self.throttled_cpu(),
+            job_completions,
+            jobs_makespan,
+            std::mem::take(&mut self.rollouts),
+            self.blocked_eviction_count,
+            fault_injection,
+            std::mem::take(&mut self.node_outages),
+            self.network.borrow().lost_message_count,
+            spot,
+            on_demand,
+            self.cost.clone(),
         );
         self.metrics_logger.log_metrics(metrics);
     }
@@ -363,46 +1013,137 @@ impl APIServer {
         self.deployment_counter += 1;
         self.deployment_counter
     }
+
+    pub fn generate_disruption_budget_id(&mut self) -> u64 {
+        self.disruption_budget_counter += 1;
+        self.disruption_budget_counter
+    }
 }
 
 impl EventHandler for APIServer {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
             NodeStatusChanged { node_id, new_status } => {
+                if self.sim_config.node_lifecycle.is_some() {
+                    self.set_node_state(node_id, new_status);
+                    return;
+                }
                 if new_status == NodeState::Working {
+                    if !self.failed_nodes.contains_key(&node_id) {
+                        return;
+                    }
                     self.recover_node(node_id);
                 } else {
+                    if !self.working_nodes.contains_key(&node_id) {
+                        return;
+                    }
                     self.crash_node(node_id);
                 }
-                self.ctx.emit(MoveRequest {}, self.scheduler.clone().unwrap().borrow().id,
-                              self.sim_config.control_plane_message_delay);
+                self.network.borrow_mut().send(&self.ctx, MoveRequest {}, self.scheduler.clone().unwrap().borrow().id,
+                                               self.sim_config.control_plane_message_delay);
+            }
+            NodeHeartbeat { node_id } => {
+                if self.get_node(node_id).is_none() {
+                    return;
+                }
+                self.node_heartbeats.insert(node_id, self.ctx.time());
+                // the node with evicted pods is back
+                if self.failed_nodes.contains_key(&node_id) {
+                    self.recover_node(node_id);
+                    self.network.borrow_mut().send(&self.ctx, MoveRequest {},
+                                                   self.scheduler.clone().unwrap().borrow().id,
+                                                   self.sim_config.control_plane_message_delay);
+                }
             }
             PodAssigningRequest { pod } => {
+                if self.removed_pod_ids.remove(&pod.id) {
+                    return;
+                }
                 self.scheduler.clone().unwrap().borrow_mut().add_pod(pod);
             }
             PodAssigningSucceeded { pod, node_id } => {
-                if self.working_nodes.get(&node_id).is_none() {
+                if self.removed_pod_ids.remove(&pod.id) {
+                    self.scheduler.clone().unwrap().borrow_mut().remove_pod(pod.id);
+                } else if !self.working_nodes.contains_key(&node_id) {
                     self.scheduler.clone().unwrap().borrow_mut().add_pod(pod);
                 } else {
-                    self.ctx.emit(PodPlacementRequest { pod, node_id },
+                    self.network.borrow_mut().send_reliable(&self.ctx, PodPlacementRequest { pod, node_id },
                         self.working_nodes.get(&node_id).unwrap().borrow().id,
                         self.sim_config.message_delay);
                 }
             }
             PodPlacementSucceeded { pod_id, node_id } => {
                 self.pod_to_node_map.insert(pod_id, node_id);
+                if self.removed_pod_ids.remove(&pod_id) {
+                    self.remove_pod(pod_id);
+                }
             }
-            PodPlacementFailed { pod, node_id } => {
+            PodPlacementFailed { pod, .. } => {
+                if self.removed_pod_ids.remove(&pod.id) {
+                    return;
+                }
                 self.scheduler.clone().unwrap().borrow_mut().add_pod(pod);
             }
             PodRemoveRequest { pod_id } => {
-                self.remove_pod(pod_id);
+                self.delete_pod(pod_id);
+            }
+            PodPreemptionRequest { preemptor_id, node_id, victim_ids } => {
+                self.preempt_pods(preemptor_id, node_id, victim_ids);
+            }
+            PodOOMKilled { pod_id, node_id } => {
+                self.oom_kill_count += 1;
+                self.oom_kills.push(OOMKill { timestamp: self.ctx.time(), pod_id, node_id });
+                if self.job_controller.is_some() {
+                    self.network.borrow_mut().send_reliable(&self.ctx, PodOOMKilled { pod_id, node_id },
+                                                            self.job_controller.clone().unwrap().borrow().id,
+                                                            self.sim_config.control_plane_message_delay);
+                }
+            }
+            PodRestarted { .. } => {
+                self.restart_count += 1;
+            }
+            PodTerminated { .. } => {
+                // requests of the pod are released
+                self.network.borrow_mut().send(&self.ctx, MoveRequest {}, self.scheduler.clone().unwrap().borrow().id,
+                                               self.sim_config.control_plane_message_delay);
+                self.sync_rollouts();
+            }
+            PodStarted { .. } => {
+                self.sync_rollouts();
+            }
+            PodSucceeded { pod_id, node_id } => {
+                if self.metrics_server.is_some() {
+                    self.metrics_server.clone().unwrap().borrow_mut().clear_pod_statistics(pod_id);
+                }
+                self.pod_to_node_map.remove(&pod_id);
+                if self.job_controller.is_some() {
+                    self.network.borrow_mut().send_reliable(&self.ctx, PodSucceeded { pod_id, node_id },
+                                                            self.job_controller.clone().unwrap().borrow().id,
+                                                            self.sim_config.control_plane_message_delay);
+                }
+                self.network.borrow_mut().send(&self.ctx, MoveRequest {}, self.scheduler.clone().unwrap().borrow().id,
+                                               self.sim_config.control_plane_message_delay);
+            }
+            NodeDrainRequest { node_id } => {
+                if !self.drain_node(node_id) {
+                    self.ctx.emit(NodeDrainRequest { node_id }, self.id, self.sim_config.drain_retry_interval);
+                }
             }
             RemoveNode { node_id } => {
-                self.remove_node(node_id);
+                if !self.remove_node(node_id) && self.cluster_autoscaler.is_some() {
+                    self.network.borrow_mut().send(&self.ctx, NodeRemovalFailed { node_id },
+                                                   self.cluster_autoscaler.clone().unwrap().borrow().id,
+                                                   self.sim_config.control_plane_message_delay);
+                }
+            }
+            NodeTaintAdded { node_id, taint } => {
+                self.add_node_taint(node_id, taint);
+            }
+            NodeTaintRemoved { node_id, key, effect } => {
+                self.remove_node_taint(node_id, &key, effect);
             }
             DeploymentCreateRequest { deployment } => {
-                let mut scheduler = self.scheduler.clone().unwrap();
+                let scheduler = self.scheduler.clone().unwrap();
                 let mut replicas = Vec::default();
                 for _ in 0..deployment.cnt_replicas {
                     let id = self.generate_pod_id();
@@ -412,6 +1153,15 @@ impl EventHandler for APIServer {
                 self.deployments.insert(deployment.id, deployment.clone());
                 self.deployment_to_replicas.insert(deployment.id, replicas);
             }
+            DisruptionBudgetCreateRequest { id, budget } => {
+                self.disruption_budgets.insert(id, budget);
+            }
+            DisruptionBudgetRemoveRequest { id } => {
+                self.disruption_budgets.remove(&id);
+            }
+            DeploymentUpdateRequest { id, pod_template, strategy } => {
+                self.update_deployment(id, pod_template, strategy);
+            }
             DeploymentHorizontalAutoscaling { id, new_cnt_replicas } => {
                 let deployment = self.deployments.remove(&id);
                 if deployment.is_none() {
@@ -420,6 +1170,12 @@ impl EventHandler for APIServer {
                 let mut deployment = deployment.unwrap();
                 let mut replicas = self.deployment_to_replicas.remove(&id).unwrap();
                 if new_cnt_replicas < deployment.cnt_replicas {
+                    // replicas of the old revision are removed first during the rollout
+                    let old_replica_ids = self.deployment_rollouts.get(&id)
+                        .filter(|rollout| rollout.finish_time.is_none())
+                        .map(|rollout| rollout.old_replica_ids.clone())
+                        .unwrap_or_default();
+                    replicas.sort_by_key(|pod_id| old_replica_ids.contains(pod_id));
                     for _ in 0..(deployment.cnt_replicas - new_cnt_replicas) {
                         let pod_id = replicas.pop().unwrap();
                         self.ctx.emit(PodRemoveRequest { pod_id }, self.id, self.sim_config.message_delay);
@@ -436,6 +1192,10 @@ impl EventHandler for APIServer {
                 self.deployments.insert(deployment.id, deployment.clone());
                 self.deployment_to_replicas.insert(id, replicas);
             }
+            CostAccounting {} => {
+                self.account_costs();
+                self.ctx.emit(CostAccounting {}, self.id, self.sim_config.cost_accounting_interval);
+            }
             MetricsSnapshot {} => {
                 self.log_metrics();
 
@@ -443,7 +1203,7 @@ impl EventHandler for APIServer {
                     self.ctx.emit(MetricsSnapshot {}, self.id, self.metrics_logger.snapshot_period());
                 }
             }
-            PodMigrationRequest { pod, source_node_id } => {
+            PodMigrationRequest { pod, .. } => {
                 self.pod_migration_count += 1;
                 self.remove_pod(pod.id);
                 self.scheduler.clone().unwrap().borrow_mut().add_pod(pod);
@@ -0,0 +1,123 @@
+//! Cloud provider which provisions nodes of node groups: quotas of instance types, stockouts,
+//! random provisioning failures, boot times of nodes and reclamations of spot nodes
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use dslab_core::SimulationContext;
+use serde::Serialize;
+use crate::simulation_config::{BootTimeDistribution, CloudProviderConfig, NodeGroupConfig, ReclamationModel,
+                               SimulationConfig, StockoutWindow};
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+pub enum ProvisioningError {
+    QuotaExceeded,
+    Stockout,
+    /// The node was provisioned, but it didn't boot.
+    BootFailure,
+}
+
+/// Result of the request of a new node
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Provisioning {
+    /// The node is added to the cluster after the boot time
+    Booted { boot_time: f64 },
+    /// The error is known after the delay (0 for rejected requests)
+    Failed { error: ProvisioningError, delay: f64 },
+}
+
+pub trait CloudProvider {
+    /// Request a node of the group, used_count nodes of its instance type are already
+    /// in the cluster or being provisioned
+    fn provision_node(&mut self, group: &NodeGroupConfig, used_count: u64, now_time: f64) -> Provisioning;
+
+    /// Time from the start of the spot node of the group to its reclamation (None if it isn't reclaimed)
+    fn reclamation_time(&mut self, _group: &NodeGroupConfig) -> Option<f64> {
+        None
+    }
+}
+
+/// Cloud provider of the simulation config, random failures and boot times are drawn
+/// with the simulation's random generator
+pub struct SimulatedCloudProvider {
+    config: CloudProviderConfig,
+    /// Positions in traces of reclamations by names of node groups.
+    trace_indices: BTreeMap<String, usize>,
+
+    ctx: SimulationContext,
+}
+
+impl SimulatedCloudProvider {
+    pub fn new(ctx: SimulationContext, sim_config: Rc<SimulationConfig>) -> Self {
+        for group in sim_config.node_groups.iter() {
+            group.validate();
+        }
+        let mut config = sim_config.cloud_provider.clone();
+        if config.stockouts_file.is_some() {
+            let file_name = config.stockouts_file.clone().unwrap();
+            let stockouts: Vec<StockoutWindow> = serde_yaml::from_str(
+                &std::fs::read_to_string(&file_name).unwrap_or_else(|_| panic!("Can't read file {}", file_name)),
+            ).unwrap_or_else(|_| panic!("Can't parse YAML from file {}", file_name));
+            config.stockouts.extend(stockouts);
+        }
+        Self {
+            config,
+            trace_indices: BTreeMap::default(),
+            ctx,
+        }
+    }
+
+    fn is_stockout(&self, instance_type: &str, now_time: f64) -> bool {
+        self.config.stockouts.iter().any(|window| window.instance_type == instance_type &&
+            window.start_time <= now_time && now_time < window.start_time + window.duration)
+    }
+
+    fn sample_boot_time(&mut self, group: &NodeGroupConfig) -> f64 {
+        match self.config.boot_time.clone() {
+            None => group.provisioning_time,
+            Some(BootTimeDistribution::Constant { value }) => value,
+            Some(BootTimeDistribution::Uniform { min, max }) => min + (max - min) * self.ctx.rand(),
+            Some(BootTimeDistribution::Normal { mean, std_dev }) => {
+                // Box-Muller transform
+                let u1 = 1.0 - self.ctx.rand();
+                let u2 = self.ctx.rand();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                (mean + std_dev * z).max(0.0)
+            }
+        }
+    }
+}
+
+impl CloudProvider for SimulatedCloudProvider {
+    fn provision_node(&mut self, group: &NodeGroupConfig, used_count: u64, now_time: f64) -> Provisioning {
+        let instance_type = group.instance_type();
+        let quota = self.config.quotas.get(instance_type);
+        if quota.is_some() && used_count >= *quota.unwrap() {
+            return Provisioning::Failed { error: ProvisioningError::QuotaExceeded, delay: 0.0 };
+        }
+        if self.is_stockout(instance_type, now_time) {
+            return Provisioning::Failed { error: ProvisioningError::Stockout, delay: 0.0 };
+        }
+
+        let boot_time = self.sample_boot_time(group);
+        if self.config.provisioning_failure_probability > 0.0 &&
+            self.ctx.rand() < self.config.provisioning_failure_probability {
+            return Provisioning::Failed { error: ProvisioningError::BootFailure, delay: boot_time };
+        }
+        Provisioning::Booted { boot_time }
+    }
+
+    fn reclamation_time(&mut self, group: &NodeGroupConfig) -> Option<f64> {
+        let spot = group.spot.as_ref()?;
+        match &spot.reclamation {
+            ReclamationModel::HazardRate { rate } => {
+                Some(-3600.0 / rate * (1.0 - self.ctx.rand()).ln())
+            }
+            ReclamationModel::Trace { values } => {
+                let index = self.trace_indices.entry(group.name.clone()).or_default();
+                let value = values[*index % values.len()];
+                *index += 1;
+                Some(value)
+            }
+        }
+    }
+}
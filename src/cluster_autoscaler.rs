@@ -1,88 +1,422 @@
 use std::cell::RefCell;
-use std::collections::BinaryHeap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
-use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
-use sugars::{rc, refcell};
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
 use crate::api_server::APIServer;
-use crate::cluster_autoscaler_algorithm::ClusterAutoscalerAlgorithm;
+use crate::cloud_provider::{CloudProvider, Provisioning, ProvisioningError};
+use crate::cluster_autoscaler_algorithm::{ClusterAutoscalerAlgorithm, ExpansionOption, NodeGroup, PodMoveSimulator};
+use crate::control_plane_outage::OutageState;
 use crate::events::autoscaler::ClusterAutoscalerScan;
-use crate::events::node::{AllocateNewDefaultNodes, RemoveNode};
-use crate::node::{Node, NodeState};
+use crate::events::node::{AllocateNewNodes, NodeDrainRequest, NodeProvisioningFailed, NodeRemovalFailed, RemoveNode,
+                          SpotReclamation, SpotTerminationNotice};
+use crate::events::outage::{ComponentOutageEnd, ComponentOutageStart};
+use crate::logger::Logger;
+use crate::network::NetworkModel;
+use crate::node::Node;
+use crate::pod::Pod;
 use crate::scheduler::Scheduler;
-use crate::simulation_config::SimulationConfig;
+use crate::simulation_config::{NodeGroupConfig, SimulationConfig};
+use crate::simulation_metrics::CapacityStats;
+
+/// Node group of the cluster autoscaler with all its nodes
+pub struct NodeGroupState {
+    pub config: NodeGroupConfig,
+    /// Nodes of the group (in the cluster or not).
+    pub nodes: Vec<Rc<RefCell<Node>>>,
+    /// Nodes which are being provisioned.
+    pub provisioning_node_ids: BTreeSet<u32>,
+    /// The group isn't scaled up until this time after failed provisioning.
+    pub backoff_until: f64,
+    /// Failures since the last successful provisioning (the backoff is doubled after every one).
+    pub consecutive_failure_count: u32,
+    pub failed_provisioning_count: u64,
+}
+
+impl NodeGroupState {
+    pub fn new(config: NodeGroupConfig, nodes: Vec<Rc<RefCell<Node>>>) -> Self {
+        Self {
+            config,
+            nodes,
+            provisioning_node_ids: BTreeSet::default(),
+            backoff_until: 0.0,
+            consecutive_failure_count: 0,
+            failed_provisioning_count: 0,
+        }
+    }
+}
 
 pub struct ClusterAutoscaler {
     pub id: u32,
-    cloud_nodes_pool: Vec<Rc<RefCell<Node>>>,
+    pub node_groups: Vec<NodeGroupState>,
+    /// Nodes requested to be removed with the time by which they are expected to be removed.
+    removing_node_ids: BTreeMap<u32, f64>,
+    /// Working spot nodes with ids of their starts (events of previous starts are ignored).
+    spot_node_starts: BTreeMap<u32, u64>,
+    spot_start_counter: u64,
+    /// Reclamations of spot nodes (node hours and cost are calculated by capacity_stats).
+    pub spot_stats: CapacityStats,
     api_server: Rc<RefCell<APIServer>>,
     scheduler: Rc<RefCell<Scheduler>>,
     cluster_autoscaler_algorithm: Box<dyn ClusterAutoscalerAlgorithm>,
+    cloud_provider: Box<dyn CloudProvider>,
+    network: Rc<RefCell<NetworkModel>>,
+    logger: Rc<RefCell<Box<dyn Logger>>>,
+    pub outage: OutageState,
 
     ctx: SimulationContext,
     sim_config: Rc<SimulationConfig>,
 }
 
 impl ClusterAutoscaler {
-    pub fn new(cloud_nodes_pool: Vec<Rc<RefCell<Node>>>, api_server: Rc<RefCell<APIServer>>,
+    pub fn new(node_groups: Vec<NodeGroupState>, api_server: Rc<RefCell<APIServer>>,
                scheduler: Rc<RefCell<Scheduler>>,
                cluster_autoscaler_algorithm: Box<dyn ClusterAutoscalerAlgorithm>,
-               ctx: SimulationContext, sim_config: Rc<SimulationConfig>) -> Self {
+               cloud_provider: Box<dyn CloudProvider>, network: Rc<RefCell<NetworkModel>>,
+               logger: Rc<RefCell<Box<dyn Logger>>>, ctx: SimulationContext,
+               sim_config: Rc<SimulationConfig>) -> Self {
         Self {
             id: ctx.id(),
-            cloud_nodes_pool,
+            node_groups,
+            removing_node_ids: BTreeMap::default(),
+            spot_node_starts: BTreeMap::default(),
+            spot_start_counter: 0,
+            spot_stats: CapacityStats::default(),
             api_server,
             scheduler,
             cluster_autoscaler_algorithm,
+            cloud_provider,
+            network,
+            logger,
+            outage: OutageState::default(),
             ctx,
             sim_config
         }
     }
 
+    /// Count of nodes of the group in the cluster (including nodes being provisioned)
+    pub fn node_group_size(&self, group_id: usize) -> u64 {
+        let group = &self.node_groups[group_id];
+        let api_server = self.api_server.borrow();
+        let cluster_count = group.nodes.iter()
+            .filter(|node| api_server.get_node(node.borrow().id).is_some())
+            .count();
+        (cluster_count + group.provisioning_node_ids.len()) as u64
+    }
+
+    /// Node groups as they are seen by the algorithm
+    fn node_groups_snapshot(&self) -> Vec<NodeGroup> {
+        (0..self.node_groups.len())
+            .map(|group_id| NodeGroup {
+                config: self.node_groups[group_id].config.clone(),
+                size: self.node_group_size(group_id),
+                provisioning_count: self.node_groups[group_id].provisioning_node_ids.len() as u64,
+                is_backed_off: self.node_groups[group_id].backoff_until > self.ctx.time(),
+            })
+            .collect()
+    }
+
+    fn node_group_id(&self, node_id: u32) -> Option<usize> {
+        self.node_groups.iter()
+            .position(|group| group.nodes.iter().any(|node| node.borrow().id == node_id))
+    }
+
+    /// Add min_size nodes of every node group to the cluster at once
+    pub fn add_min_size_nodes(&mut self) {
+        for group_id in 0..self.node_groups.len() {
+            let size = self.node_group_size(group_id);
+            let min_size = self.node_groups[group_id].config.min_size;
+            for node_id in self.reserve_nodes(group_id, min_size.saturating_sub(size) as u32) {
+                self.allocate_new_node(group_id, node_id);
+            }
+        }
+    }
+
+    /// Choose nodes of the group which aren't in the cluster and mark them as provisioning
+    fn reserve_nodes(&mut self, group_id: usize, cnt_nodes: u32) -> Vec<u32> {
+        let api_server = self.api_server.borrow();
+        let group = &mut self.node_groups[group_id];
+        let node_ids: Vec<u32> = group.nodes.iter()
+            .map(|node| node.borrow().id)
+            .filter(|node_id| api_server.get_node(*node_id).is_none() &&
+                !group.provisioning_node_ids.contains(node_id))
+            .take(cnt_nodes as usize)
+            .collect();
+        group.provisioning_node_ids.extend(node_ids.iter());
+        node_ids
+    }
+
+    /// Count of nodes of the instance type in the cluster (including nodes being provisioned)
+    fn instance_type_usage(&self, instance_type: &str) -> u64 {
+        (0..self.node_groups.len())
+            .filter(|group_id| self.node_groups[*group_id].config.instance_type() == instance_type)
+            .map(|group_id| self.node_group_size(group_id))
+            .sum()
+    }
+
+    pub fn set_cloud_provider(&mut self, cloud_provider: Box<dyn CloudProvider>) {
+        self.cloud_provider = cloud_provider;
+    }
+
+    /// Unschedulable pods which don't fit working nodes, other ones (e.g. waiting for the node added just now)
+    /// are placed by the scheduler without new nodes
+    fn pods_to_scale_up(&self) -> Vec<Pod> {
+        let mut scheduler = self.scheduler.borrow_mut();
+        let mut moved_requests = BTreeMap::<u32, (f32, f64)>::default();
+        let pending_pods = scheduler.unschedulable_queue.clone();
+        pending_pods.into_iter()
+            .filter(|pod| !scheduler.can_move_pods(&vec![pod.clone()], &BTreeSet::default(), &mut moved_requests))
+            .collect()
+    }
+
     pub fn try_to_scale_up(&mut self) -> bool {
-        let pending_pods = &self.scheduler.borrow().unschedulable_queue;
-        let cnt_new_nodes = self.cluster_autoscaler_algorithm.try_to_scale_up(pending_pods,
-                                                                              self.ctx.time(),
-                                                                              &self.sim_config.default_node);
-        if cnt_new_nodes > 0 {
-            self.ctx.emit(AllocateNewDefaultNodes { cnt_nodes: cnt_new_nodes },
-                          self.id, self.sim_config.default_node_allocation_time);
-            true
-        } else {
-            false
+        let node_groups = self.node_groups_snapshot();
+        let pending_pods = self.pods_to_scale_up();
+        let plan = self.cluster_autoscaler_algorithm.try_to_scale_up(&pending_pods, self.ctx.time(), &node_groups);
+        self.log_expansion_decisions();
+        let mut scaled_up = false;
+        for (group_id, cnt_nodes) in plan {
+            for _ in 0..cnt_nodes {
+                let used_count = self.instance_type_usage(self.node_groups[group_id].config.instance_type());
+                let node_ids = self.reserve_nodes(group_id, 1);
+                if node_ids.is_empty() {
+                    break;
+                }
+                let node_id = node_ids[0];
+                match self.cloud_provider.provision_node(&self.node_groups[group_id].config, used_count,
+                                                         self.ctx.time()) {
+                    Provisioning::Booted { boot_time } => {
+                        scaled_up = true;
+                        self.ctx.emit(AllocateNewNodes { group_id, node_ids }, self.id, boot_time);
+                    }
+                    Provisioning::Failed { error, delay } if delay > 0.0 => {
+                        scaled_up = true;
+                        self.ctx.emit(NodeProvisioningFailed { group_id, node_id, error }, self.id, delay);
+                    }
+                    Provisioning::Failed { error, .. } => {
+                        // the request is rejected, other nodes of the group aren't requested
+                        self.on_provisioning_failure(group_id, node_id, error);
+                        break;
+                    }
+                }
+            }
+        }
+        scaled_up
+    }
+
+    /// Forget the node which wasn't provisioned and back off from its group
+    fn on_provisioning_failure(&mut self, group_id: usize, node_id: u32, error: ProvisioningError) {
+        let now = self.ctx.time();
+        let group = &mut self.node_groups[group_id];
+        group.provisioning_node_ids.remove(&node_id);
+        group.failed_provisioning_count += 1;
+        let backoff = (self.sim_config.node_group_initial_backoff *
+            2.0_f64.powi(group.consecutive_failure_count as i32)).min(self.sim_config.node_group_max_backoff);
+        group.consecutive_failure_count += 1;
+        group.backoff_until = group.backoff_until.max(now + backoff);
+        let message = format!("provisioning of a node of group {} failed ({:?}), the group is backed off for {}",
+                              group.config.name, error, group.backoff_until - now);
+        self.logger.borrow_mut().log_info(&self.ctx, message);
+    }
+
+    fn describe_option(&self, option: &ExpansionOption) -> String {
+        format!("{} ({} nodes for {} pods)", self.node_groups[option.group_id].config.name,
+                option.node_count, option.pod_count)
+    }
+
+    /// Log choices of node groups by the expander with rejected options
+    fn log_expansion_decisions(&mut self) {
+        for decision in self.cluster_autoscaler_algorithm.take_expansion_decisions() {
+            let rejected: Vec<String> = decision.rejected.iter()
+                .map(|option| self.describe_option(option))
+                .collect();
+            self.logger.borrow_mut().log_info(&self.ctx,
+                format!("expander {} chose node group {}, rejected: [{}]", decision.expander,
+                        self.describe_option(&decision.chosen), rejected.join(", ")));
         }
     }
 
     pub fn try_to_scale_down(&mut self) {
-        let working_nodes = &self.api_server.borrow().working_nodes;
         let nodes_be_removed = self.cluster_autoscaler_algorithm.try_to_scale_down(
-            working_nodes, self.ctx.time());
+            &self.api_server.borrow().working_nodes, self.ctx.time(), &mut *self.scheduler.borrow_mut());
+        let mut group_sizes: Vec<u64> = (0..self.node_groups.len())
+            .map(|group_id| self.node_group_size(group_id))
+            .collect();
         for node_id in nodes_be_removed {
-            self.ctx.emit(RemoveNode { node_id }, self.api_server.borrow().id,
-                          self.sim_config.node_stop_duration);
+            // node groups don't shrink below their min_size
+            if let Some(group_id) = self.node_group_id(node_id) {
+                if group_sizes[group_id] <= self.node_groups[group_id].config.min_size {
+                    continue;
+                }
+                group_sizes[group_id] -= 1;
+            }
+            self.removing_node_ids.insert(node_id, self.ctx.time() + self.sim_config.node_stop_duration);
+            self.network.borrow_mut().send(&self.ctx, RemoveNode { node_id }, self.api_server.borrow().id,
+                                           self.sim_config.node_stop_duration);
+        }
+    }
+
+    /// Report nodes which are still working after the expected removal time to the algorithm
+    fn check_removals(&mut self) {
+        let now = self.ctx.time();
+        let expired_node_ids: Vec<u32> = self.removing_node_ids.iter()
+            .filter(|(_, removal_time)| **removal_time < now)
+            .map(|(node_id, _)| *node_id)
+            .collect();
+        for node_id in expired_node_ids {
+            if self.api_server.borrow().working_nodes.contains_key(&node_id) {
+                self.on_removal_failure(node_id);
+            } else {
+                self.removing_node_ids.remove(&node_id);
+            }
+        }
+    }
+
+    /// The node wasn't removed (the API server reported it or it's still working after the removal time)
+    fn on_removal_failure(&mut self, node_id: u32) {
+        if self.removing_node_ids.remove(&node_id).is_none() {
+            return;
         }
+        self.logger.borrow_mut().log_info(&self.ctx,
+            format!("node {} isn't removed, evictions of its pods are blocked", node_id));
+        self.cluster_autoscaler_algorithm.on_scale_down_failure(node_id, self.ctx.time());
     }
 
-    /// Allocate new node for scale up (from cloud pool)
-    pub fn allocate_new_node(&mut self) {
-        let node = self.cloud_nodes_pool.pop().unwrap();
+    /// Add the provisioned node of the group to the cluster, the reclamation of the spot node is planned
+    pub fn allocate_new_node(&mut self, group_id: usize, node_id: u32) {
+        let group = &mut self.node_groups[group_id];
+        group.provisioning_node_ids.remove(&node_id);
+        group.consecutive_failure_count = 0;
+        let node = group.nodes.iter().find(|node| node.borrow().id == node_id).unwrap().clone();
+        // taints of the previous run of the node (e.g. of its drain) are dropped
+        node.borrow_mut().taints = group.config.taints.clone();
         self.api_server.borrow_mut().add_new_node(node);
+
+        let reclamation_time = self.cloud_provider.reclamation_time(&self.node_groups[group_id].config);
+        if reclamation_time.is_none() {
+            return;
+        }
+        let reclamation_time = reclamation_time.unwrap();
+        let termination_notice = self.node_groups[group_id].config.spot.as_ref().unwrap().termination_notice;
+        self.spot_start_counter += 1;
+        let start_id = self.spot_start_counter;
+        self.spot_node_starts.insert(node_id, start_id);
+        self.ctx.emit(SpotTerminationNotice { group_id, node_id, start_id }, self.id,
+                      (reclamation_time - termination_notice).max(0.0));
+        self.ctx.emit(SpotReclamation { group_id, node_id, start_id }, self.id, reclamation_time);
+    }
+
+    /// The spot node is drained before the reclamation
+    fn on_termination_notice(&mut self, group_id: usize, node_id: u32) {
+        let node = self.api_server.borrow().get_node(node_id);
+        if node.is_none() {
+            return;
+        }
+        self.spot_stats.drained_pod_count += node.unwrap().borrow().pods.len() as u64;
+        self.logger.borrow_mut().log_info(&self.ctx, format!("termination notice for spot node {} of group {}",
+                                                             node_id, self.node_groups[group_id].config.name));
+        self.network.borrow_mut().send(&self.ctx, NodeDrainRequest { node_id }, self.api_server.borrow().id,
+                                       self.sim_config.control_plane_message_delay);
+    }
+
+    /// The spot node is removed by the cloud, its remaining pods are killed and rescheduled
+    fn reclaim_node(&mut self, group_id: usize, node_id: u32) {
+        self.spot_node_starts.remove(&node_id);
+        self.removing_node_ids.remove(&node_id);
+        if self.api_server.borrow().get_node(node_id).is_none() {
+            return;
+        }
+        let killed_pod_count = self.api_server.borrow_mut().reclaim_node(node_id);
+        self.spot_stats.reclaimed_node_count += 1;
+        self.spot_stats.killed_pod_count += killed_pod_count;
+        self.logger.borrow_mut().log_info(&self.ctx,
+            format!("spot node {} of group {} is reclaimed, {} pods are killed", node_id,
+                    self.node_groups[group_id].config.name, killed_pod_count));
+    }
+
+    /// Whether the event is about the current start of the spot node (it isn't removed or added again since)
+    fn is_current_start(&self, node_id: u32, start_id: u64) -> bool {
+        self.spot_node_starts.get(&node_id) == Some(&start_id)
+    }
+
+    /// Node hours, cost and disruptions of spot and on-demand nodes of node groups
+    pub fn capacity_stats(&self, api_server: &APIServer) -> (CapacityStats, CapacityStats) {
+        let mut spot = self.spot_stats.clone();
+        let mut on_demand = CapacityStats::default();
+        for group in self.node_groups.iter() {
+            let node_hours: f64 = group.nodes.iter()
+                .map(|node| api_server.node_uptime(node.borrow().id) / 3600.0)
+                .sum();
+            let stats = if group.config.spot.is_some() { &mut spot } else { &mut on_demand };
+            stats.node_hours += node_hours;
+            stats.cost += node_hours * group.config.price;
+        }
+        (spot, on_demand)
+    }
+
+    /// Restart after the outage: unneeded times of nodes, times of last scalings, backoffs and removals
+    /// are lost, nodes being provisioned are taken from the cloud (requests which nodes aren't in the cluster)
+    fn restart(&mut self) {
+        self.cluster_autoscaler_algorithm.reset();
+        self.removing_node_ids.clear();
+        let api_server = self.api_server.borrow();
+        for group in self.node_groups.iter_mut() {
+            group.backoff_until = 0.0;
+            group.consecutive_failure_count = 0;
+            group.provisioning_node_ids.retain(|node_id| api_server.get_node(*node_id).is_none());
+        }
+        drop(api_server);
+        for event in self.outage.finish() {
+            self.on(event);
+        }
     }
 }
 
 impl EventHandler for ClusterAutoscaler {
     fn on(&mut self, event: Event) {
+        // events of the cloud (completions of provisioning and reclamations) aren't affected by the outage
+        let is_cloud_event = event.data.is::<AllocateNewNodes>() || event.data.is::<NodeProvisioningFailed>() ||
+            event.data.is::<SpotTerminationNotice>() || event.data.is::<SpotReclamation>();
+        let event = if is_cloud_event { Some(event) } else { self.outage.intercept::<ClusterAutoscalerScan>(event) };
+        if event.is_none() {
+            return;
+        }
+        let event = event.unwrap();
         cast!(match event.data {
             ClusterAutoscalerScan {} => {
+                self.check_removals();
                 if !self.try_to_scale_up() {
                     self.try_to_scale_down();
                 }
                 self.ctx.emit(ClusterAutoscalerScan{}, self.id,
                     self.sim_config.cluster_autoscaler_scan_interval);
             }
-            AllocateNewDefaultNodes { cnt_nodes } => {
-                for _ in 0..cnt_nodes.min(self.cloud_nodes_pool.len() as u32) {
-                    self.allocate_new_node();
+            AllocateNewNodes { group_id, node_ids } => {
+                for node_id in node_ids {
+                    self.allocate_new_node(group_id, node_id);
                 }
+                self.cluster_autoscaler_algorithm.on_nodes_added(self.ctx.time());
+            }
+            NodeProvisioningFailed { group_id, node_id, error } => {
+                self.on_provisioning_failure(group_id, node_id, error);
+            }
+            NodeRemovalFailed { node_id } => {
+                self.on_removal_failure(node_id);
+            }
+            SpotTerminationNotice { group_id, node_id, start_id } => {
+                if self.is_current_start(node_id, start_id) {
+                    self.on_termination_notice(group_id, node_id);
+                }
+            }
+            SpotReclamation { group_id, node_id, start_id } => {
+                if self.is_current_start(node_id, start_id) {
+                    self.reclaim_node(group_id, node_id);
+                }
+            }
+            ComponentOutageStart { queue_events } => {
+                self.outage.start(queue_events);
+            }
+            ComponentOutageEnd {} => {
+                self.restart();
             }
         })
     }
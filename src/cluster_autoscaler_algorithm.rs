@@ -1,15 +1,106 @@
 use std::cell::RefCell;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
 use crate::node::Node;
 use crate::pod::Pod;
-use crate::simulation_config::NodeConfig;
+use crate::simulation_config::NodeGroupConfig;
+use crate::taints::{tolerates_taints, TaintEffect};
+
+/// Node group as it's seen by the algorithm
+#[derive(Clone)]
+pub struct NodeGroup {
+    pub config: NodeGroupConfig,
+    /// Count of nodes of the group in the cluster (including nodes being provisioned).
+    pub size: u64,
+    /// Count of nodes being provisioned (pending pods are expected to be placed on them).
+    pub provisioning_count: u64,
+    /// The group isn't scaled up after failed provisioning of its nodes.
+    pub is_backed_off: bool,
+}
+
+impl NodeGroup {
+    /// Count of nodes which can be added to the group (backoff isn't taken into account)
+    pub fn free_capacity(&self) -> u64 {
+        self.config.max_size.saturating_sub(self.size)
+    }
+
+    /// Whether the pod can be placed on an empty node of the group
+    /// (resources, node selector and affinity, NoSchedule and NoExecute taints)
+    pub fn fits(&self, pod: &Pod) -> bool {
+        let labels = self.config.node_labels();
+        let tolerations = &pod.constraints.tolerations;
+        pod.requested_cpu <= self.config.cpu && pod.requested_memory <= self.config.memory &&
+            pod.constraints.matches_node_labels(&labels) &&
+            tolerates_taints(tolerations, &self.config.taints, TaintEffect::NoSchedule) &&
+            tolerates_taints(tolerations, &self.config.taints, TaintEffect::NoExecute)
+    }
+}
+
+/// Count of new nodes by indices of node groups
+pub type ScaleUpPlan = BTreeMap<usize, u32>;
+
+/// Option of the scale-up: new nodes of the node group for some of pending pods
+#[derive(Clone, Debug)]
+pub struct ExpansionOption {
+    pub group_id: usize,
+    pub node_count: u32,
+    pub pod_count: usize,
+    /// Total requests of pods placed on new nodes.
+    pub requested_cpu: f32,
+    pub requested_memory: f64,
+}
+
+/// Option chosen by the expander among others
+#[derive(Clone, Debug)]
+pub struct ExpansionDecision {
+    pub expander: String,
+    pub chosen: ExpansionOption,
+    pub rejected: Vec<ExpansionOption>,
+}
+
+/// Strategy of choice of the node group when several groups can host pending pods
+/// (like expanders of the k8s cluster autoscaler)
+pub trait Expander {
+    fn name(&self) -> &str;
+
+    /// Returns index of the best option (options aren't empty)
+    fn best_option(&mut self, options: &Vec<ExpansionOption>, node_groups: &Vec<NodeGroup>) -> usize;
+}
+
+/// Dry run of the scheduler's filter for pods of nodes to be removed
+pub trait PodMoveSimulator {
+    /// Whether every pod can be placed on some working node except excluded ones. Requests of moved pods
+    /// are added to moved_requests (node_id -> (cpu, memory)), which are reserved on nodes by next checks
+    fn can_move_pods(&mut self, pods: &Vec<Pod>, excluded_node_ids: &BTreeSet<u32>,
+                     moved_requests: &mut BTreeMap<u32, (f32, f64)>) -> bool;
+}
 
 pub trait ClusterAutoscalerAlgorithm {
-    /// Checks the need for scaling up, returns the number of new nodes
-    fn try_to_scale_up(&mut self, pending_pods: &Vec<Pod>, now_time: f64, default_node: &NodeConfig) -> u32;
+    /// Checks the need for scaling up, returns the number of new nodes for every node group
+    fn try_to_scale_up(&mut self, pending_pods: &Vec<Pod>, now_time: f64, node_groups: &Vec<NodeGroup>) -> ScaleUpPlan;
 
     /// Checks the need for scaling down, returns ids of nodes to be deleted
-    fn try_to_scale_down(&mut self, working_nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
-                         now_time: f64) -> Vec<u32>;
-}
\ No newline at end of file
+    fn try_to_scale_down(&mut self, working_nodes: &BTreeMap<u32, Rc<RefCell<Node>>>, now_time: f64,
+                         move_simulator: &mut dyn PodMoveSimulator) -> Vec<u32>;
+
+    /// Called when the node wasn't removed after the request (e.g. evictions are blocked by disruption budgets)
+    fn on_scale_down_failure(&mut self, _node_id: u32, _now_time: f64) {}
+
+    /// Sets the utilization threshold of scale-down and its delays (from the simulation config)
+    fn set_scale_down_policy(&mut self, _utilization_threshold: f64, _delay_after_add: f64,
+                             _delay_after_failure: f64) {}
+
+    /// Called when new nodes arrive to the cluster
+    fn on_nodes_added(&mut self, _now_time: f64) {}
+
+    /// Replaces the expander of the algorithm (with the one of the simulation config)
+    fn set_expander(&mut self, _expander: Box<dyn Expander>) {}
+
+    /// Returns decisions of the expander made since the last call (to be logged by the autoscaler)
+    fn take_expansion_decisions(&mut self) -> Vec<ExpansionDecision> {
+        Vec::default()
+    }
+
+    /// Forgets the in-memory state (on restart of the autoscaler)
+    fn reset(&mut self) {}
+}
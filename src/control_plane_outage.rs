@@ -0,0 +1,66 @@
+//! State of the outage of the control plane component (events received while it doesn't work)
+
+use dslab_core::Event;
+use dslab_core::event::EventData;
+use crate::events::outage::ComponentOutageEnd;
+
+#[derive(Default)]
+pub struct OutageState {
+    pub is_down: bool,
+    queue_events: bool,
+    /// Events received during the outage, they're handled after the restart.
+    queued_events: Vec<Event>,
+    /// Events of periodic loops of the component, loops are continued after the restart.
+    periodic_events: Vec<Event>,
+    pub dropped_event_count: u64,
+    pub restart_count: u64,
+}
+
+impl OutageState {
+    pub fn start(&mut self, queue_events: bool) {
+        self.is_down = true;
+        self.queue_events = queue_events;
+    }
+
+    pub fn queue_events(&self) -> bool {
+        self.queue_events
+    }
+
+    /// Queue the event received during the outage (or drop it if events aren't queued)
+    pub fn hold(&mut self, event: Event) {
+        if self.queue_events {
+            self.queued_events.push(event);
+        } else {
+            self.dropped_event_count += 1;
+        }
+    }
+
+    /// Keep the event of the periodic loop until the restart
+    pub fn hold_periodic(&mut self, event: Event) {
+        self.periodic_events.push(event);
+    }
+
+    /// Hold the event received during the outage (events P of the periodic loop until the restart,
+    /// others are queued or dropped), returns the event if the component works and handles it
+    pub fn intercept<P: EventData>(&mut self, event: Event) -> Option<Event> {
+        if !self.is_down || event.data.is::<ComponentOutageEnd>() {
+            return Some(event);
+        }
+        if event.data.is::<P>() {
+            self.hold_periodic(event);
+        } else {
+            self.hold(event);
+        }
+        None
+    }
+
+    /// Finish the outage, returns events to be handled by the restarted component
+    /// (events of periodic loops first, then queued ones)
+    pub fn finish(&mut self) -> Vec<Event> {
+        self.is_down = false;
+        self.restart_count += 1;
+        let mut events = std::mem::take(&mut self.periodic_events);
+        events.append(&mut self.queued_events);
+        events
+    }
+}
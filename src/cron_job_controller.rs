@@ -0,0 +1,71 @@
+//! Controller of k8s cron jobs: creates jobs of cron jobs on their schedules
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
+use crate::events::job::{CronJobCreateRequest, CronJobSchedule, JobCreateRequest};
+use crate::job::CronJob;
+use crate::job_controller::JobController;
+use crate::network::NetworkModel;
+use crate::simulation_config::SimulationConfig;
+
+pub struct CronJobController {
+    pub id: u32,
+    pub cron_jobs: BTreeMap<u64, CronJob>,
+    job_controller: Rc<RefCell<JobController>>,
+    network: Rc<RefCell<NetworkModel>>,
+
+    ctx: SimulationContext,
+    sim_config: Rc<SimulationConfig>,
+
+    cron_job_counter: u64,
+}
+
+impl CronJobController {
+    pub fn new(job_controller: Rc<RefCell<JobController>>, network: Rc<RefCell<NetworkModel>>, ctx: SimulationContext,
+               sim_config: Rc<SimulationConfig>) -> Self {
+        Self {
+            id: ctx.id(),
+            cron_jobs: BTreeMap::default(),
+            job_controller,
+            network,
+            ctx,
+            sim_config,
+            cron_job_counter: 0,
+        }
+    }
+
+    pub fn generate_cron_job_id(&mut self) -> u64 {
+        self.cron_job_counter += 1;
+        self.cron_job_counter
+    }
+
+    /// Create the next job of the cron job and plan the following one (if the limit isn't reached)
+    fn schedule_job(&mut self, cron_job_id: u64) {
+        let cron_job = self.cron_jobs.get_mut(&cron_job_id).unwrap();
+        let job_id = self.job_controller.borrow_mut().generate_job_id();
+        self.network.borrow_mut().send_reliable(&self.ctx, JobCreateRequest { job: cron_job.create_new_job(job_id) },
+                                                self.job_controller.borrow().id, self.sim_config.control_plane_message_delay);
+        cron_job.job_ids.push(job_id);
+
+        if cron_job.jobs_limit.is_none_or(|limit| (cron_job.job_ids.len() as u64) < limit) {
+            self.ctx.emit(CronJobSchedule { cron_job_id }, self.id, cron_job.schedule_period);
+        }
+    }
+}
+
+impl EventHandler for CronJobController {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            CronJobCreateRequest { cron_job } => {
+                let cron_job_id = cron_job.id;
+                self.cron_jobs.insert(cron_job_id, cron_job);
+                self.schedule_job(cron_job_id);
+            }
+            CronJobSchedule { cron_job_id } => {
+                self.schedule_job(cron_job_id);
+            }
+        })
+    }
+}
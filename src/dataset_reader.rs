@@ -1,10 +1,19 @@
+use std::collections::BTreeMap;
 use std::fs::File;
+use crate::affinity::TopologySpreadConstraint;
+use crate::job::JobSpec;
 use crate::load_model::{ConstantLoadModel, LoadModel, ResourceSnapshot, TraceLoadModel};
+use crate::pod::SchedulingConstraints;
+use crate::priority_class::PreemptionPolicy;
+use crate::taints::{Taint, Toleration};
 
 #[derive(Clone)]
 pub struct NodeRequest {
     pub cpu: f32,
     pub memory: f64,
+    pub labels: BTreeMap<String, String>,
+    pub taints: Vec<Taint>,
+    pub price: f64,
 }
 
 #[derive(Clone)]
@@ -19,6 +28,11 @@ pub struct PodRequest {
     pub limit_cpu: f32,
     pub limit_memory: f64,
     pub priority_weight: u64,
+    /// Name of the priority class from the config (overrides priority weight and preemption policy).
+    pub priority_class_name: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub constraints: SchedulingConstraints,
+    pub image: Option<String>,
 }
 
 #[derive(Clone)]
@@ -33,14 +47,45 @@ pub struct DeploymentRequest {
     pub limit_cpu: f32,
     pub limit_memory: f64,
     pub priority_weight: u64,
+    /// Name of the priority class from the config (overrides priority weight and preemption policy).
+    pub priority_class_name: Option<String>,
     pub cnt_replicas: u64,
+    pub labels: BTreeMap<String, String>,
+    pub constraints: SchedulingConstraints,
+    pub image: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct JobRequest {
+    pub timestamp: f64,
+
+    pub cpu_load_model: Box<dyn LoadModel>,
+    pub memory_load_model: Box<dyn LoadModel>,
+
+    pub requested_cpu: f32,
+    pub requested_memory: f64,
+    pub limit_cpu: f32,
+    pub limit_memory: f64,
+    pub priority_weight: u64,
+    /// Name of the priority class from the config (overrides priority weight and preemption policy).
+    pub priority_class_name: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub constraints: SchedulingConstraints,
+    pub image: Option<String>,
+    /// Time of the work of job's pods.
+    pub duration: f64,
+    pub spec: JobSpec,
+    /// Period of the cron job (for SUBMIT_CRON_JOB events).
+    pub schedule_period: Option<f64>,
+    pub jobs_limit: Option<u64>,
 }
 
 #[derive(Default)]
 pub struct DatasetReader {
     pub node_requests: Vec<NodeRequest>,
     pub pod_requests: Vec<PodRequest>,
-    pub deployment_requests: Vec<DeploymentRequest>
+    pub deployment_requests: Vec<DeploymentRequest>,
+    pub job_requests: Vec<JobRequest>,
 }
 
 impl DatasetReader {
@@ -56,12 +101,20 @@ impl DatasetReader {
                 self.node_requests.push(NodeRequest {
                     cpu: event["cpu"].as_f64().unwrap() as f32,
                     memory: event["memory"].as_f64().unwrap(),
+                    labels: self.parse_labels(&event["labels"]),
+                    taints: serde_json::from_value::<Option<Vec<Taint>>>(event["taints"].clone())
+                        .unwrap()
+                        .unwrap_or_default(),
+                    price: event["price"].as_f64().unwrap_or(0.0),
                 })
-            } else if event["type"] == "SUBMIT_POD" || event["type"] == "SUBMIT_DEPLOYMENT" {
+            } else if event["type"] == "SUBMIT_POD" || event["type"] == "SUBMIT_DEPLOYMENT" ||
+                event["type"] == "SUBMIT_JOB" || event["type"] == "SUBMIT_CRON_JOB" {
                 let requested_cpu = event["requested_cpu"].as_f64().unwrap();
                 let requested_memory = event["requested_memory"].as_f64().unwrap();
                 let cpu_load_model = self.parse_load_model(&event["cpu_load_model"], requested_cpu);
                 let memory_load_model = self.parse_load_model(&event["memory_load_model"], requested_memory);
+                let constraints = self.parse_constraints(event);
+                let image = event["image"].as_str().map(|image| image.to_string());
 
                 if event["type"] == "SUBMIT_POD" {
                     self.pod_requests.push(PodRequest {
@@ -72,9 +125,13 @@ impl DatasetReader {
                         requested_memory,
                         limit_cpu: event["limit_cpu"].as_f64().unwrap() as f32,
                         limit_memory: event["limit_memory"].as_f64().unwrap(),
-                        priority_weight: event["priority_weight"].as_u64().unwrap(),
+                        priority_weight: event["priority_weight"].as_u64().unwrap_or(0),
+                        priority_class_name: event["priority_class_name"].as_str().map(|name| name.to_string()),
+                        labels: self.parse_labels(&event["labels"]),
+                        constraints,
+                        image,
                     })
-                } else {
+                } else if event["type"] == "SUBMIT_DEPLOYMENT" {
                     self.deployment_requests.push(DeploymentRequest {
                         timestamp: event["timestamp"].as_f64().unwrap(),
                         cpu_load_model,
@@ -83,14 +140,74 @@ impl DatasetReader {
                         requested_memory,
                         limit_cpu: event["limit_cpu"].as_f64().unwrap() as f32,
                         limit_memory: event["limit_memory"].as_f64().unwrap(),
-                        priority_weight: event["priority_weight"].as_u64().unwrap(),
+                        priority_weight: event["priority_weight"].as_u64().unwrap_or(0),
+                        priority_class_name: event["priority_class_name"].as_str().map(|name| name.to_string()),
                         cnt_replicas: event["cnt_replicas"].as_u64().unwrap(),
+                        labels: self.parse_labels(&event["labels"]),
+                        constraints,
+                        image,
+                    })
+                } else {
+                    let default_spec = JobSpec::default();
+                    self.job_requests.push(JobRequest {
+                        timestamp: event["timestamp"].as_f64().unwrap(),
+                        cpu_load_model,
+                        memory_load_model,
+                        requested_cpu: requested_cpu as f32,
+                        requested_memory,
+                        limit_cpu: event["limit_cpu"].as_f64().unwrap() as f32,
+                        limit_memory: event["limit_memory"].as_f64().unwrap(),
+                        priority_weight: event["priority_weight"].as_u64().unwrap_or(0),
+                        priority_class_name: event["priority_class_name"].as_str().map(|name| name.to_string()),
+                        labels: self.parse_labels(&event["labels"]),
+                        constraints,
+                        image,
+                        duration: event["duration"].as_f64().unwrap(),
+                        spec: JobSpec::new(
+                            event["completions"].as_u64().unwrap_or(default_spec.completions),
+                            event["parallelism"].as_u64().unwrap_or(default_spec.parallelism),
+                            event["backoff_limit"].as_u64().unwrap_or(default_spec.backoff_limit),
+                            event["active_deadline_seconds"].as_f64(),
+                        ),
+                        schedule_period: if event["type"] == "SUBMIT_CRON_JOB" {
+                            Some(event["schedule_period"].as_f64().unwrap())
+                        } else {
+                            None
+                        },
+                        jobs_limit: event["jobs_limit"].as_u64(),
                     })
                 }
             }
         }
     }
 
+    fn parse_labels(&self, labels_json: &serde_json::Value) -> BTreeMap<String, String> {
+        serde_json::from_value::<Option<BTreeMap<String, String>>>(labels_json.clone())
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    /// Parses "node_selector" (label map), "node_affinity" ("required" node selector terms
+    /// and "preferred" weighted terms), "tolerations", "pod_affinity" and "pod_anti_affinity"
+    /// ("required" terms and "preferred" weighted terms), "topology_spread_constraints"
+    /// and "preemption_policy" of the pod or deployment event.
+    fn parse_constraints(&self, event: &serde_json::Value) -> SchedulingConstraints {
+        SchedulingConstraints {
+            node_selector: self.parse_labels(&event["node_selector"]),
+            node_affinity: serde_json::from_value(event["node_affinity"].clone()).unwrap(),
+            tolerations: serde_json::from_value::<Option<Vec<Toleration>>>(event["tolerations"].clone())
+                .unwrap()
+                .unwrap_or_default(),
+            pod_affinity: serde_json::from_value(event["pod_affinity"].clone()).unwrap(),
+            pod_anti_affinity: serde_json::from_value(event["pod_anti_affinity"].clone()).unwrap(),
+            topology_spread_constraints: serde_json::from_value::<Option<Vec<TopologySpreadConstraint>>>(
+                event["topology_spread_constraints"].clone()).unwrap().unwrap_or_default(),
+            preemption_policy: serde_json::from_value::<Option<PreemptionPolicy>>(event["preemption_policy"].clone())
+                .unwrap()
+                .unwrap_or_default(),
+        }
+    }
+
     fn parse_load_model(&mut self, load_model_json: &serde_json::Value,
                         default_value: f64) -> Box<dyn LoadModel> {
         if load_model_json["type"] == "CONST" {
@@ -1,14 +1,12 @@
 use std::cell::RefCell;
-use std::cmp::max;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::rc::Rc;
-use serde::{Deserialize, Serialize};
-use crate::cluster_autoscaler_algorithm::ClusterAutoscalerAlgorithm;
+use crate::cluster_autoscaler_algorithm::{ClusterAutoscalerAlgorithm, ExpansionDecision, ExpansionOption, Expander,
+                                          NodeGroup, PodMoveSimulator, ScaleUpPlan};
+use crate::default_cluster_autoscaler_algorithms::expanders::MostPodsExpander;
 use crate::node::Node;
 use crate::pod::Pod;
-use crate::simulation_config::NodeConfig;
 
-#[derive(Default)]
 pub struct SimpleClusterAutoscalerAlgorithm {
     node_unneeded_time: HashMap<u32, f64>,
     scale_down_unneeded_time: f64,
@@ -16,6 +14,15 @@ pub struct SimpleClusterAutoscalerAlgorithm {
     last_scale_up_time: f64,
     last_scale_down_time: f64,
     scale_up_delay: f64,
+    /// Nodes with request utilization (max of CPU and memory) below the threshold are scaled down.
+    scale_down_utilization_threshold: f64,
+    /// Time after the arrival of new nodes (and after the failed scale-down) when nodes aren't scaled down.
+    scale_down_delay_after_add: f64,
+    scale_down_delay_after_failure: f64,
+    last_node_added_time: Option<f64>,
+    last_scale_down_failure_time: Option<f64>,
+    expander: Box<dyn Expander>,
+    expansion_decisions: Vec<ExpansionDecision>,
 }
 
 impl SimpleClusterAutoscalerAlgorithm {
@@ -26,9 +33,22 @@ impl SimpleClusterAutoscalerAlgorithm {
             max_empty_bulk_delete,
             last_scale_up_time: 0.0,
             last_scale_down_time: 0.0,
-            scale_up_delay
+            scale_up_delay,
+            scale_down_utilization_threshold: 0.5,
+            scale_down_delay_after_add: 600.0,
+            scale_down_delay_after_failure: 180.0,
+            last_node_added_time: None,
+            last_scale_down_failure_time: None,
+            expander: Box::new(MostPodsExpander::new()),
+            expansion_decisions: Vec::default(),
         }
     }
+
+    /// Use the expander to choose node groups for scale-up (most-pods by default)
+    pub fn with_expander(mut self, expander: Box<dyn Expander>) -> Self {
+        self.expander = expander;
+        self
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -45,30 +65,21 @@ struct SimplePod {
     pub requested_memory: f64,
 }
 
-impl ClusterAutoscalerAlgorithm for SimpleClusterAutoscalerAlgorithm {
-    fn try_to_scale_up(&mut self, pending_pods: &Vec<Pod>, now_time: f64,
-                       default_node: &NodeConfig) -> u32 {
-        if pending_pods.is_empty() {
-            return 0;
-        }
-        if self.last_scale_up_time + self.scale_up_delay > now_time {
-            return 0;
-        }
-
-        let mut nodes = Vec::<SimpleNode>::default();
-        nodes.push(SimpleNode {
+impl SimpleClusterAutoscalerAlgorithm {
+    /// Packs pods fitting the node group into empty_count empty nodes and at most max_nodes
+    /// new nodes of the group, returns count of new nodes and indices of packed pods
+    fn pack_pods(pods: &Vec<&Pod>, group: &NodeGroup, empty_count: u64, max_nodes: u64) -> (u32, Vec<usize>) {
+        let empty_node = SimpleNode {
             cpu_allocated: 0., memory_allocated: 0.,
-            cpu_total: default_node.cpu, memory_total: default_node.memory,
-        });
-
-        let mut pods: Vec<SimplePod> = pending_pods.iter()
-            .map(
-                |pod| SimplePod {
-                    requested_cpu: pod.requested_cpu, requested_memory: pod.requested_memory
-                })
-            .collect();
-
-        for pod in pods {
+            cpu_total: group.config.cpu, memory_total: group.config.memory,
+        };
+        let mut nodes = vec![empty_node; empty_count as usize];
+        let mut packed_pods = Vec::<usize>::default();
+        for (pod_ind, pod) in pods.iter().enumerate() {
+            if !group.fits(pod) {
+                continue;
+            }
+            let pod = SimplePod { requested_cpu: pod.requested_cpu, requested_memory: pod.requested_memory };
             let mut filtered_nodes: Vec<&mut SimpleNode> = nodes.iter_mut()
                 .filter(
                     |node| node.cpu_allocated + pod.requested_cpu <= node.cpu_total &&
@@ -78,15 +89,17 @@ impl ClusterAutoscalerAlgorithm for SimpleClusterAutoscalerAlgorithm {
 
             if filtered_nodes.is_empty() {
                 drop(filtered_nodes);
+                if nodes.len() as u64 >= empty_count + max_nodes {
+                    continue;
+                }
                 nodes.push(SimpleNode {
                     cpu_allocated: pod.requested_cpu, memory_allocated: pod.requested_memory,
-                    cpu_total: default_node.cpu, memory_total: default_node.memory,
+                    cpu_total: group.config.cpu, memory_total: group.config.memory,
                 });
             } else {
                 let mut max_prior_ind = 0;
                 let mut max_prior = -1.0;
-                for i in 0..filtered_nodes.len() {
-                    let node = &filtered_nodes[i];
+                for (i, node) in filtered_nodes.iter().enumerate() {
                     let cpu_utilization = (node.cpu_allocated + pod.requested_cpu) / node.cpu_total;
                     let memory_utilization = (node.memory_allocated + pod.requested_memory) / node.memory_total;
                     let prior = (1.0 - cpu_utilization as f64) + (1.0 - memory_utilization);
@@ -98,47 +111,175 @@ impl ClusterAutoscalerAlgorithm for SimpleClusterAutoscalerAlgorithm {
                 filtered_nodes[max_prior_ind].cpu_allocated += pod.requested_cpu;
                 filtered_nodes[max_prior_ind].memory_allocated += pod.requested_memory;
             }
+            packed_pods.push(pod_ind);
         }
+        ((nodes.len() as u64 - empty_count) as u32, packed_pods)
+    }
 
-        return nodes.len() as u32;
+    /// Max of shares of CPU and memory of the node requested by its pods
+    fn request_utilization(&self, node: &Node) -> f64 {
+        let requested_cpu: f32 = node.pods.values().map(|pod| pod.requested_cpu).sum();
+        let requested_memory: f64 = node.pods.values().map(|pod| pod.requested_memory).sum();
+        (requested_cpu as f64 / node.cpu_total as f64).max(requested_memory / node.memory_total)
     }
 
-    fn try_to_scale_down(&mut self, working_nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
-                         now_time: f64) -> Vec<u32> {
-        let mut now_is_needed_nodes = Vec::<u32>::default();
-        for (node_id, _) in &self.node_unneeded_time {
-            let node = working_nodes.get(&node_id);
-            if node.is_none() {
-                now_is_needed_nodes.push(*node_id);
-            } else if node.unwrap().borrow().cpu_allocated != 0.0 ||
-                node.unwrap().borrow().memory_allocated != 0.0 {
-                now_is_needed_nodes.push(*node_id);
-            }
+    fn is_scale_down_delayed(&self, now_time: f64) -> bool {
+        self.last_node_added_time.is_some_and(|time| now_time - time < self.scale_down_delay_after_add) ||
+            self.last_scale_down_failure_time
+                .is_some_and(|time| now_time - time < self.scale_down_delay_after_failure)
+    }
+
+    fn remove_pods<'a>(pods: Vec<&'a Pod>, pod_indices: &[usize]) -> Vec<&'a Pod> {
+        pods.into_iter().enumerate()
+            .filter(|(pod_ind, _)| !pod_indices.contains(pod_ind))
+            .map(|(_, pod)| pod)
+            .collect()
+    }
+}
+
+impl ClusterAutoscalerAlgorithm for SimpleClusterAutoscalerAlgorithm {
+    fn try_to_scale_up(&mut self, pending_pods: &Vec<Pod>, now_time: f64,
+                       node_groups: &Vec<NodeGroup>) -> ScaleUpPlan {
+        let mut plan = ScaleUpPlan::default();
+        if pending_pods.is_empty() {
+            return plan;
         }
-        for node_id in now_is_needed_nodes {
-            self.node_unneeded_time.remove(&node_id);
+        if self.last_scale_up_time + self.scale_up_delay > now_time {
+            return plan;
         }
 
-        let mut nodes_to_scale_down = Vec::<u32>::default();
-        for (node_id, node) in working_nodes {
-            if node.borrow().cpu_allocated == 0.0 && node.borrow().memory_allocated == 0.0 {
-                let node_in_unneeded = self.node_unneeded_time.get(node_id);
-                if node_in_unneeded.is_none() {
-                    self.node_unneeded_time.insert(*node_id, now_time);
-                } else if now_time - node_in_unneeded.unwrap() >= self.scale_down_unneeded_time {
-                    nodes_to_scale_down.push(*node_id);
-                    if nodes_to_scale_down.len() == (self.max_empty_bulk_delete as usize) {
-                        break;
-                    }
+        // pods which can be placed on nodes being provisioned don't need new nodes
+        let mut pods: Vec<&Pod> = pending_pods.iter().collect();
+        for group in node_groups.iter().filter(|group| group.provisioning_count > 0) {
+            let (_, packed_pods) = Self::pack_pods(&pods, group, group.provisioning_count, 0);
+            pods = Self::remove_pods(pods, &packed_pods);
+        }
+
+        // groups are chosen one after another by the expander among groups which can host remaining pods
+        loop {
+            let mut options = Vec::<ExpansionOption>::default();
+            let mut options_pods = Vec::<Vec<usize>>::default();
+            for (group_id, group) in node_groups.iter().enumerate() {
+                let planned_count = *plan.get(&group_id).unwrap_or(&0) as u64;
+                let max_nodes = group.free_capacity().saturating_sub(planned_count);
+                if group.is_backed_off || max_nodes == 0 {
+                    continue;
+                }
+                let (cnt_nodes, packed_pods) = Self::pack_pods(&pods, group, 0, max_nodes);
+                if packed_pods.is_empty() {
+                    continue;
                 }
+                options.push(ExpansionOption {
+                    group_id,
+                    node_count: cnt_nodes,
+                    pod_count: packed_pods.len(),
+                    requested_cpu: packed_pods.iter().map(|pod_ind| pods[*pod_ind].requested_cpu).sum(),
+                    requested_memory: packed_pods.iter().map(|pod_ind| pods[*pod_ind].requested_memory).sum(),
+                });
+                options_pods.push(packed_pods);
+            }
+            if options.is_empty() {
+                break;
             }
+
+            let best_ind = self.expander.best_option(&options, node_groups);
+            let chosen = options.remove(best_ind);
+            *plan.entry(chosen.group_id).or_default() += chosen.node_count;
+            pods = Self::remove_pods(pods, &options_pods[best_ind]);
+            self.expansion_decisions.push(ExpansionDecision {
+                expander: self.expander.name().to_string(),
+                chosen,
+                rejected: options,
+            });
+        }
+
+        if !plan.is_empty() {
+            self.last_scale_up_time = now_time;
         }
-        if !nodes_to_scale_down.is_empty() {
-            for node_id in &nodes_to_scale_down {
-                self.node_unneeded_time.remove(node_id);
+        plan
+    }
+
+    fn try_to_scale_down(&mut self, working_nodes: &BTreeMap<u32, Rc<RefCell<Node>>>, now_time: f64,
+                         move_simulator: &mut dyn PodMoveSimulator) -> Vec<u32> {
+        // underutilized nodes are unneeded if their pods can be moved to other nodes
+        // (except nodes which are unneeded too), nodes receiving moved pods are kept
+        let mut unneeded_node_ids = BTreeSet::<u32>::default();
+        let mut moved_requests = BTreeMap::<u32, (f32, f64)>::default();
+        for (node_id, node) in working_nodes {
+            let node = node.borrow();
+            let mut excluded_node_ids = unneeded_node_ids.clone();
+            excluded_node_ids.insert(*node_id);
+            let pods: Vec<Pod> = node.pods.values().cloned().collect();
+            if !moved_requests.contains_key(node_id) &&
+                self.request_utilization(&node) < self.scale_down_utilization_threshold &&
+                (pods.is_empty() || move_simulator.can_move_pods(&pods, &excluded_node_ids, &mut moved_requests)) {
+                unneeded_node_ids.insert(*node_id);
             }
         }
+        self.node_unneeded_time.retain(|node_id, _| unneeded_node_ids.contains(node_id));
+        for node_id in unneeded_node_ids.iter() {
+            self.node_unneeded_time.entry(*node_id).or_insert(now_time);
+        }
         self.last_scale_down_time = now_time;
+        if self.is_scale_down_delayed(now_time) {
+            return Vec::default();
+        }
+
+        // nodes unneeded for scale_down_unneeded_time are removed: empty ones in bulk, at most one non-empty
+        let mut nodes_to_scale_down = Vec::<u32>::default();
+        let mut empty_count = 0;
+        let mut non_empty_count = 0;
+        for node_id in unneeded_node_ids {
+            if now_time - self.node_unneeded_time.get(&node_id).unwrap() < self.scale_down_unneeded_time {
+                continue;
+            }
+            if working_nodes.get(&node_id).unwrap().borrow().pods.is_empty() {
+                if empty_count == self.max_empty_bulk_delete {
+                    continue;
+                }
+                empty_count += 1;
+            } else {
+                if non_empty_count == 1 {
+                    continue;
+                }
+                non_empty_count += 1;
+            }
+            nodes_to_scale_down.push(node_id);
+        }
+        for node_id in &nodes_to_scale_down {
+            self.node_unneeded_time.remove(node_id);
+        }
         nodes_to_scale_down
     }
+
+    fn set_scale_down_policy(&mut self, utilization_threshold: f64, delay_after_add: f64,
+                             delay_after_failure: f64) {
+        self.scale_down_utilization_threshold = utilization_threshold;
+        self.scale_down_delay_after_add = delay_after_add;
+        self.scale_down_delay_after_failure = delay_after_failure;
+    }
+
+    fn on_nodes_added(&mut self, now_time: f64) {
+        self.last_node_added_time = Some(now_time);
+    }
+
+    fn on_scale_down_failure(&mut self, _node_id: u32, now_time: f64) {
+        self.last_scale_down_failure_time = Some(now_time);
+    }
+
+    fn set_expander(&mut self, expander: Box<dyn Expander>) {
+        self.expander = expander;
+    }
+
+    fn take_expansion_decisions(&mut self) -> Vec<ExpansionDecision> {
+        std::mem::take(&mut self.expansion_decisions)
+    }
+
+    fn reset(&mut self) {
+        self.node_unneeded_time.clear();
+        self.last_scale_up_time = 0.0;
+        self.last_scale_down_time = 0.0;
+        self.last_node_added_time = None;
+        self.last_scale_down_failure_time = None;
+    }
 }
\ No newline at end of file
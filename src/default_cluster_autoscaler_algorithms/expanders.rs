@@ -0,0 +1,146 @@
+//! Default expanders of the cluster autoscaler: random, most-pods, least-waste, price and priority
+
+use std::collections::BTreeMap;
+use dslab_core::context::SimulationContext;
+use crate::cluster_autoscaler_algorithm::{ExpansionOption, Expander, NodeGroup};
+use crate::simulation_config::ExpanderConfig;
+
+/// Expander of the config (the random one draws with the given context)
+pub fn expander_from_config(config: &ExpanderConfig, ctx: SimulationContext) -> Box<dyn Expander> {
+    match config {
+        ExpanderConfig::Random => Box::new(RandomExpander::new(ctx)),
+        ExpanderConfig::MostPods => Box::new(MostPodsExpander::new()),
+        ExpanderConfig::LeastWaste => Box::new(LeastWasteExpander::new()),
+        ExpanderConfig::Price => Box::new(PriceExpander::new()),
+        ExpanderConfig::Priority { priorities } => Box::new(PriorityExpander::new(priorities.clone())),
+    }
+}
+
+/// Index of the option with the min key (the first one on ties)
+fn min_option_by_key<F: Fn(&ExpansionOption) -> f64>(options: &[ExpansionOption], key: F) -> usize {
+    let mut best_ind = 0;
+    for i in 1..options.len() {
+        if key(&options[i]) < key(&options[best_ind]) {
+            best_ind = i;
+        }
+    }
+    best_ind
+}
+
+/// Chooses a random option with the simulation's random generator
+pub struct RandomExpander {
+    ctx: SimulationContext,
+}
+
+impl RandomExpander {
+    pub fn new(ctx: SimulationContext) -> Self {
+        Self {
+            ctx,
+        }
+    }
+}
+
+impl Expander for RandomExpander {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn best_option(&mut self, options: &Vec<ExpansionOption>, _node_groups: &Vec<NodeGroup>) -> usize {
+        (self.ctx.rand() * options.len() as f64) as usize
+    }
+}
+
+/// Chooses the option which hosts most of pending pods
+#[derive(Default)]
+pub struct MostPodsExpander {}
+
+impl MostPodsExpander {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl Expander for MostPodsExpander {
+    fn name(&self) -> &str {
+        "most-pods"
+    }
+
+    fn best_option(&mut self, options: &Vec<ExpansionOption>, _node_groups: &Vec<NodeGroup>) -> usize {
+        min_option_by_key(options, |option| -(option.pod_count as f64))
+    }
+}
+
+/// Chooses the option with the least share of CPU and memory of new nodes not requested by pods
+#[derive(Default)]
+pub struct LeastWasteExpander {}
+
+impl LeastWasteExpander {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl Expander for LeastWasteExpander {
+    fn name(&self) -> &str {
+        "least-waste"
+    }
+
+    fn best_option(&mut self, options: &Vec<ExpansionOption>, node_groups: &Vec<NodeGroup>) -> usize {
+        min_option_by_key(options, |option| {
+            let config = &node_groups[option.group_id].config;
+            let cpu_total = config.cpu as f64 * option.node_count as f64;
+            let memory_total = config.memory * option.node_count as f64;
+            (cpu_total - option.requested_cpu as f64) / cpu_total +
+                (memory_total - option.requested_memory) / memory_total
+        })
+    }
+}
+
+/// Chooses the option with the lowest price of new nodes per hosted pod
+#[derive(Default)]
+pub struct PriceExpander {}
+
+impl PriceExpander {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl Expander for PriceExpander {
+    fn name(&self) -> &str {
+        "price"
+    }
+
+    fn best_option(&mut self, options: &Vec<ExpansionOption>, node_groups: &Vec<NodeGroup>) -> usize {
+        min_option_by_key(options, |option| {
+            node_groups[option.group_id].config.price * option.node_count as f64 / option.pod_count as f64
+        })
+    }
+}
+
+/// Chooses the option of the node group with the highest priority (0 for groups without priority,
+/// the first option on ties)
+pub struct PriorityExpander {
+    /// Priorities by names of node groups.
+    priorities: BTreeMap<String, i64>,
+}
+
+impl PriorityExpander {
+    pub fn new(priorities: BTreeMap<String, i64>) -> Self {
+        Self {
+            priorities,
+        }
+    }
+}
+
+impl Expander for PriorityExpander {
+    fn name(&self) -> &str {
+        "priority"
+    }
+
+    fn best_option(&mut self, options: &Vec<ExpansionOption>, node_groups: &Vec<NodeGroup>) -> usize {
+        min_option_by_key(options, |option| {
+            -(*self.priorities.get(&node_groups[option.group_id].config.name).unwrap_or(&0) as f64)
+        })
+    }
+}
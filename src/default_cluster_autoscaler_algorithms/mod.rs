@@ -1 +1,2 @@
-pub mod default_simple_algorithm;
\ No newline at end of file
+pub mod default_simple_algorithm;
+pub mod expanders;
\ No newline at end of file
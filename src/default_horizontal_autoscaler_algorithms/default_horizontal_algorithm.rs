@@ -54,30 +54,27 @@ impl HorizontalAutoscalerAlgorithm for ResourcesHorizontalAutoscalerAlgorithm {
         average_cpu /= deployment.cnt_replicas as f32;
         average_memory /= deployment.cnt_replicas as f64;
 
-        let mut new_cnt_replicas = deployment.cnt_replicas;
-
-        match self.controlled_resources {
+        let new_cnt_replicas = match self.controlled_resources {
             ControlledResources::CPUOnly { cpu_utilization } => {
                 let target_cpu_utilization = cpu_utilization.unwrap_or(1.);
                 let target_cpu = deployment.pod_template.requested_cpu * target_cpu_utilization;
-                new_cnt_replicas = (average_cpu / target_cpu * deployment.cnt_replicas as f32).ceil() as u64;
+                (average_cpu / target_cpu * deployment.cnt_replicas as f32).ceil() as u64
             },
             ControlledResources::MemoryOnly { memory_utilization } => {
                 let target_memory_utilization = memory_utilization.unwrap_or(1.);
                 let target_memory = deployment.pod_template.requested_memory * target_memory_utilization;
-                new_cnt_replicas = (average_memory / target_memory * deployment.cnt_replicas as f64).ceil() as u64;
+                (average_memory / target_memory * deployment.cnt_replicas as f64).ceil() as u64
             },
             ControlledResources::CPUAndMemory { cpu_utilization, memory_utilization } => {
                 let target_cpu_utilization = cpu_utilization.unwrap_or(1.);
                 let target_cpu = deployment.pod_template.requested_cpu * target_cpu_utilization;
-                new_cnt_replicas = (average_cpu / target_cpu * deployment.cnt_replicas as f32).ceil() as u64;
+                let cpu_cnt_replicas = (average_cpu / target_cpu * deployment.cnt_replicas as f32).ceil() as u64;
 
                 let target_memory_utilization = memory_utilization.unwrap_or(1.);
                 let target_memory = deployment.pod_template.requested_memory * target_memory_utilization;
-                new_cnt_replicas = new_cnt_replicas
-                    .max((average_memory / target_memory * deployment.cnt_replicas as f64).ceil() as u64);
+                cpu_cnt_replicas.max((average_memory / target_memory * deployment.cnt_replicas as f64).ceil() as u64)
             }
-        };
+        }.clamp(self.min_replicas, self.max_replicas);
 
         if new_cnt_replicas < deployment.cnt_replicas {
             self.last_downscale_time.insert(deployment.id, now_time);
@@ -85,4 +82,8 @@ impl HorizontalAutoscalerAlgorithm for ResourcesHorizontalAutoscalerAlgorithm {
 
         new_cnt_replicas
     }
+
+    fn reset(&mut self) {
+        self.last_downscale_time.clear();
+    }
 }
\ No newline at end of file
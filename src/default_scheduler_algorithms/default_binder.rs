@@ -0,0 +1,25 @@
+use crate::pod::Pod;
+use crate::scheduler_algorithm::{BindPlugin, CycleState, PluginStatus, SchedulerPlugin};
+
+/// Accepts every pod, the binding itself is the PodAssigningSucceeded request
+/// which the scheduler sends to the API server after bind plugins.
+#[derive(Default)]
+pub struct DefaultBinder;
+
+impl DefaultBinder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SchedulerPlugin for DefaultBinder {
+    fn name(&self) -> &str {
+        "DefaultBinder"
+    }
+}
+
+impl BindPlugin for DefaultBinder {
+    fn bind(&mut self, _state: &mut CycleState, _pod: &Pod, _node_id: u32) -> PluginStatus {
+        PluginStatus::Success
+    }
+}
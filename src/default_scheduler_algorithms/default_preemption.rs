@@ -0,0 +1,129 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use crate::node::Node;
+use crate::pod::Pod;
+use crate::priority_class::PreemptionPolicy;
+use crate::scheduler_algorithm::{CycleState, NOMINATED_PODS_KEY, NominatedPods, PluginStatus, PostFilterPlugin,
+                                 PostFilterResult, SchedulerPlugin};
+
+/// Chooses victims on the node: all pods with lower priority are removed, then pods with the highest priority
+/// are returned while the preemptor still fits. Returns None if the preemptor doesn't fit even without them.
+fn select_victims(state: &CycleState, pod: &Pod, node: &Node) -> Option<Vec<u64>> {
+    let mut reserved_cpu = 0.0;
+    let mut reserved_memory = 0.0;
+    if let Some(nominated_pods) = state.read::<NominatedPods>(NOMINATED_PODS_KEY) {
+        for (node_id, nominated_pod) in nominated_pods.borrow().values() {
+            if *node_id == node.id && nominated_pod.id != pod.id &&
+                nominated_pod.priority_weight >= pod.priority_weight {
+                reserved_cpu += nominated_pod.requested_cpu;
+                reserved_memory += nominated_pod.requested_memory;
+            }
+        }
+    }
+    // released resources are subtracted from requests to check the node as if victims were removed
+    let fits = |released_cpu: f32, released_memory: f64| {
+        node.can_place_pod(pod.requested_cpu + reserved_cpu - released_cpu,
+                           pod.requested_memory + reserved_memory - released_memory)
+    };
+
+    let mut candidates: Vec<&Pod> = node.pods.values()
+        .filter(|candidate| candidate.priority_weight < pod.priority_weight)
+        .collect();
+    let mut released_cpu: f32 = candidates.iter().map(|candidate| candidate.cpu.max(candidate.requested_cpu)).sum();
+    let mut released_memory: f64 = candidates.iter()
+        .map(|candidate| candidate.memory.max(candidate.requested_memory))
+        .sum();
+    if !fits(released_cpu, released_memory) {
+        return None;
+    }
+
+    candidates.sort_by(|first, second| second.priority_weight.cmp(&first.priority_weight)
+        .then(first.id.cmp(&second.id)));
+    let mut victim_ids = Vec::default();
+    for candidate in candidates {
+        let candidate_cpu = candidate.cpu.max(candidate.requested_cpu);
+        let candidate_memory = candidate.memory.max(candidate.requested_memory);
+        if fits(released_cpu - candidate_cpu, released_memory - candidate_memory) {
+            released_cpu -= candidate_cpu;
+            released_memory -= candidate_memory;
+        } else {
+            victim_ids.push(candidate.id);
+        }
+    }
+    Some(victim_ids)
+}
+
+/// Preempts pods with lower priority when the pod doesn't fit any node. The node with the lowest max priority
+/// of victims (then with the fewest victims) is chosen, the pod is nominated to it.
+#[derive(Default)]
+pub struct DefaultPreemption;
+
+impl DefaultPreemption {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SchedulerPlugin for DefaultPreemption {
+    fn name(&self) -> &str {
+        "DefaultPreemption"
+    }
+}
+
+impl PostFilterPlugin for DefaultPreemption {
+    fn post_filter(&mut self, state: &CycleState, pod: &Pod,
+                   nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
+                   node_statuses: &BTreeMap<u32, PluginStatus>) -> Option<PostFilterResult> {
+        if pod.constraints.preemption_policy == PreemptionPolicy::Never {
+            return None;
+        }
+        // victims of the previous preemption are still terminating, the pod keeps waiting for them
+        if let Some(nominated_node_id) = pod.nominated_node_id {
+            let victims_terminating = nodes.get(&nominated_node_id).is_some_and(|node| {
+                node.borrow().terminating_pods.iter()
+                    .any(|terminating_pod| terminating_pod.priority_weight < pod.priority_weight)
+            });
+            if victims_terminating {
+                return Some(PostFilterResult {
+                    nominated_node_id: Some(nominated_node_id),
+                    victim_ids: Vec::default(),
+                });
+            }
+        }
+
+        let mut best_candidate: Option<(u32, Vec<u64>, u64)> = None;
+        for (node_id, status) in node_statuses.iter() {
+            // preemption can't help if the node was rejected not because of resources
+            if !matches!(status, PluginStatus::Unschedulable(_)) {
+                continue;
+            }
+            let node = match nodes.get(node_id) {
+                Some(node) => node.borrow(),
+                None => continue,
+            };
+            let victim_ids = match select_victims(state, pod, &node) {
+                Some(victim_ids) => victim_ids,
+                None => continue,
+            };
+            let max_victim_priority = victim_ids.iter()
+                .map(|victim_id| node.pods.get(victim_id).unwrap().priority_weight)
+                .max()
+                .unwrap_or(0);
+            let is_better = match &best_candidate {
+                None => true,
+                Some((_, best_victim_ids, best_max_priority)) => {
+                    (max_victim_priority, victim_ids.len()) < (*best_max_priority, best_victim_ids.len())
+                }
+            };
+            if is_better {
+                best_candidate = Some((*node_id, victim_ids, max_victim_priority));
+            }
+        }
+
+        best_candidate.map(|(node_id, victim_ids, _)| PostFilterResult {
+            nominated_node_id: Some(node_id),
+            victim_ids,
+        })
+    }
+}
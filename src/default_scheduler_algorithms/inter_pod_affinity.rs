@@ -0,0 +1,211 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+use crate::affinity::{PodAffinity, PodAffinityTerm, WeightedPodAffinityTerm};
+use crate::node::Node;
+use crate::pod::Pod;
+use crate::scheduler_algorithm::{CycleState, FilterPlugin, for_each_existing_pod, MAX_NODE_SCORE, PluginStatus,
+                                 PreFilterPlugin, PreScorePlugin, SchedulerPlugin, ScorePlugin};
+
+const PRE_FILTER_STATE_KEY: &str = "PreFilterInterPodAffinity";
+const PRE_SCORE_STATE_KEY: &str = "PreScoreInterPodAffinity";
+
+#[derive(Default)]
+struct PreFilterState {
+    /// For every required affinity term of the pod: topology value -> count of matched pods.
+    affinity_counts: Vec<HashMap<String, u64>>,
+    /// For every required anti-affinity term of the pod: topology value -> count of matched pods.
+    anti_affinity_counts: Vec<HashMap<String, u64>>,
+    /// Topology domains (key, value) of existing pods which required anti-affinity matches the pod.
+    existing_anti_affinity_domains: HashSet<(String, String)>,
+}
+
+#[derive(Default)]
+struct PreScoreState {
+    topology_scores: HashMap<(String, String), f64>,
+}
+
+fn required_terms(affinity: &Option<PodAffinity>) -> &[PodAffinityTerm] {
+    match affinity {
+        Some(affinity) => &affinity.required,
+        None => &[],
+    }
+}
+
+fn preferred_terms(affinity: &Option<PodAffinity>) -> &[WeightedPodAffinityTerm] {
+    match affinity {
+        Some(affinity) => &affinity.preferred,
+        None => &[],
+    }
+}
+
+/// Filters nodes by required pod affinity and anti-affinity (of the pod and of already running pods),
+/// scores nodes by preferred pod affinity and anti-affinity.
+#[derive(Default)]
+pub struct InterPodAffinity;
+
+impl InterPodAffinity {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SchedulerPlugin for InterPodAffinity {
+    fn name(&self) -> &str {
+        "InterPodAffinity"
+    }
+}
+
+impl PreFilterPlugin for InterPodAffinity {
+    fn pre_filter(&mut self, state: &mut CycleState, pod: &Pod,
+                  nodes: &BTreeMap<u32, Rc<RefCell<Node>>>) -> PluginStatus {
+        let affinity_terms = required_terms(&pod.constraints.pod_affinity);
+        let anti_affinity_terms = required_terms(&pod.constraints.pod_anti_affinity);
+        let mut pre_filter_state = PreFilterState {
+            affinity_counts: vec![HashMap::default(); affinity_terms.len()],
+            anti_affinity_counts: vec![HashMap::default(); anti_affinity_terms.len()],
+            existing_anti_affinity_domains: HashSet::default(),
+        };
+
+        for_each_existing_pod(state, pod, nodes, |node_labels, existing_pod| {
+            for (term, counts) in affinity_terms.iter().zip(pre_filter_state.affinity_counts.iter_mut())
+                .chain(anti_affinity_terms.iter().zip(pre_filter_state.anti_affinity_counts.iter_mut())) {
+                if let Some(value) = node_labels.get(&term.topology_key) {
+                    if term.label_selector.matches(&existing_pod.labels) {
+                        *counts.entry(value.clone()).or_insert(0) += 1;
+                    }
+                }
+            }
+            for term in required_terms(&existing_pod.constraints.pod_anti_affinity) {
+                if let Some(value) = node_labels.get(&term.topology_key) {
+                    if term.label_selector.matches(&pod.labels) {
+                        pre_filter_state.existing_anti_affinity_domains
+                            .insert((term.topology_key.clone(), value.clone()));
+                    }
+                }
+            }
+        });
+
+        state.write(PRE_FILTER_STATE_KEY, pre_filter_state);
+        PluginStatus::Success
+    }
+}
+
+impl FilterPlugin for InterPodAffinity {
+    fn filter(&mut self, state: &CycleState, pod: &Pod, node: &Node) -> PluginStatus {
+        let pre_filter_state = match state.read::<PreFilterState>(PRE_FILTER_STATE_KEY) {
+            Some(pre_filter_state) => pre_filter_state,
+            None => return PluginStatus::Success,
+        };
+
+        let in_existing_anti_affinity_domain = pre_filter_state.existing_anti_affinity_domains.iter()
+            .any(|(key, value)| node.labels.get(key) == Some(value));
+        if in_existing_anti_affinity_domain {
+            return PluginStatus::UnschedulableAndUnresolvable(
+                "node didn't satisfy existing pods anti-affinity rules".to_string());
+        }
+
+        let anti_affinity_terms = required_terms(&pod.constraints.pod_anti_affinity);
+        for (term, counts) in anti_affinity_terms.iter().zip(pre_filter_state.anti_affinity_counts.iter()) {
+            if let Some(value) = node.labels.get(&term.topology_key) {
+                if counts.get(value).is_some_and(|count| *count > 0) {
+                    return PluginStatus::UnschedulableAndUnresolvable(
+                        "node didn't match pod anti-affinity rules".to_string());
+                }
+            }
+        }
+
+        let affinity_terms = required_terms(&pod.constraints.pod_affinity);
+        let mut pods_exist = true;
+        for (term, counts) in affinity_terms.iter().zip(pre_filter_state.affinity_counts.iter()) {
+            match node.labels.get(&term.topology_key) {
+                Some(value) => {
+                    if counts.get(value).is_none_or(|count| *count == 0) {
+                        pods_exist = false;
+                    }
+                }
+                None => {
+                    return PluginStatus::UnschedulableAndUnresolvable(
+                        "node didn't match pod affinity rules".to_string());
+                }
+            }
+        }
+        // the first pod of the group with affinity to itself can be placed anywhere
+        if !pods_exist {
+            let no_matched_pods = pre_filter_state.affinity_counts.iter().all(|counts| counts.is_empty());
+            let matches_itself = affinity_terms.iter()
+                .all(|term| term.label_selector.matches(&pod.labels));
+            if !(no_matched_pods && matches_itself) {
+                return PluginStatus::UnschedulableAndUnresolvable(
+                    "node didn't match pod affinity rules".to_string());
+            }
+        }
+        PluginStatus::Success
+    }
+}
+
+impl PreScorePlugin for InterPodAffinity {
+    fn pre_score(&mut self, state: &mut CycleState, pod: &Pod,
+                 nodes: &BTreeMap<u32, Rc<RefCell<Node>>>, _filtered_node_ids: &[u32]) -> PluginStatus {
+        let mut pre_score_state = PreScoreState::default();
+        let mut add_score = |term: &PodAffinityTerm, node_labels: &BTreeMap<String, String>, score: f64| {
+            if let Some(value) = node_labels.get(&term.topology_key) {
+                *pre_score_state.topology_scores
+                    .entry((term.topology_key.clone(), value.clone()))
+                    .or_insert(0.0) += score;
+            }
+        };
+
+        for_each_existing_pod(state, pod, nodes, |node_labels, existing_pod| {
+            // preferred terms of the pod matched by the existing pod
+            for term in preferred_terms(&pod.constraints.pod_affinity) {
+                if term.pod_affinity_term.label_selector.matches(&existing_pod.labels) {
+                    add_score(&term.pod_affinity_term, node_labels, term.weight as f64);
+                }
+            }
+            for term in preferred_terms(&pod.constraints.pod_anti_affinity) {
+                if term.pod_affinity_term.label_selector.matches(&existing_pod.labels) {
+                    add_score(&term.pod_affinity_term, node_labels, -(term.weight as f64));
+                }
+            }
+            // preferred terms of the existing pod matched by the pod
+            for term in preferred_terms(&existing_pod.constraints.pod_affinity) {
+                if term.pod_affinity_term.label_selector.matches(&pod.labels) {
+                    add_score(&term.pod_affinity_term, node_labels, term.weight as f64);
+                }
+            }
+            for term in preferred_terms(&existing_pod.constraints.pod_anti_affinity) {
+                if term.pod_affinity_term.label_selector.matches(&pod.labels) {
+                    add_score(&term.pod_affinity_term, node_labels, -(term.weight as f64));
+                }
+            }
+        });
+
+        state.write(PRE_SCORE_STATE_KEY, pre_score_state);
+        PluginStatus::Success
+    }
+}
+
+impl ScorePlugin for InterPodAffinity {
+    fn score(&mut self, state: &CycleState, _pod: &Pod, node: &Node) -> f64 {
+        match state.read::<PreScoreState>(PRE_SCORE_STATE_KEY) {
+            Some(pre_score_state) => pre_score_state.topology_scores.iter()
+                .filter(|((key, value), _)| node.labels.get(key) == Some(value))
+                .map(|(_, score)| *score)
+                .sum(),
+            None => 0.0,
+        }
+    }
+
+    fn normalize_score(&mut self, _state: &CycleState, _pod: &Pod, scores: &mut [f64]) {
+        let max_score = scores.iter().cloned().fold(f64::MIN, f64::max);
+        let min_score = scores.iter().cloned().fold(f64::MAX, f64::min);
+        for score in scores.iter_mut() {
+            *score = if max_score > min_score {
+                MAX_NODE_SCORE * (*score - min_score) / (max_score - min_score)
+            } else {
+                0.0
+            };
+        }
+    }
+}
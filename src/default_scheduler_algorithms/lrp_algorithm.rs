@@ -1,10 +1,8 @@
-use std::cell::RefCell;
-use std::collections::BTreeMap;
-use std::rc::Rc;
 use crate::node::Node;
 use crate::pod::Pod;
-use crate::scheduler_algorithm::SchedulerAlgorithm;
+use crate::scheduler_algorithm::{CycleState, SchedulerPlugin, ScorePlugin};
 
+/// Least requested priority: prefers nodes with the lowest allocation after placing the pod.
 #[derive(Default)]
 pub struct LRPAlgorithm;
 
@@ -14,26 +12,16 @@ impl LRPAlgorithm {
     }
 }
 
-impl SchedulerAlgorithm for LRPAlgorithm {
-    fn filter(&self, pod: &Pod, nodes: &BTreeMap<u32, Rc<RefCell<Node>>>) -> Vec<u32> {
-        let mut filtered_nodes = Vec::<u32>::default();
-        for (node_id, node) in nodes.into_iter() {
-            if node.borrow().can_place_pod(pod.requested_cpu, pod.requested_memory) {
-                filtered_nodes.push(*node_id);
-            }
-        }
-        filtered_nodes
+impl SchedulerPlugin for LRPAlgorithm {
+    fn name(&self) -> &str {
+        "LeastRequestedPriority"
     }
+}
 
-    fn score(&self, pod: &Pod, nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
-             filtered_node_ids: &Vec<u32>) -> Vec<f64> {
-        let mut scores = Vec::<f64>::default();
-        for node_id in filtered_node_ids {
-            let node = nodes.get(node_id).unwrap().borrow();
-            let cpu_utilization = ((node.cpu_allocated + pod.requested_cpu) as f64) / (node.cpu_total as f64);
-            let memory_utilization = (node.memory_allocated + pod.requested_memory) / node.memory_total;
-            scores.push((10.0 * (1.0 - cpu_utilization) + 10.0 * (1.0 - memory_utilization)) / 2.0);
-        }
-        scores
+impl ScorePlugin for LRPAlgorithm {
+    fn score(&mut self, _state: &CycleState, pod: &Pod, node: &Node) -> f64 {
+        let cpu_utilization = ((node.cpu_allocated + pod.requested_cpu) as f64) / (node.cpu_total as f64);
+        let memory_utilization = (node.memory_allocated + pod.requested_memory) / node.memory_total;
+        (10.0 * (1.0 - cpu_utilization) + 10.0 * (1.0 - memory_utilization)) / 2.0
     }
-}
\ No newline at end of file
+}
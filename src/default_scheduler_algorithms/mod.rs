@@ -1,2 +1,10 @@
 pub mod mrp_algorithm;
-pub mod lrp_algorithm;
\ No newline at end of file
+pub mod lrp_algorithm;
+pub mod priority_sort;
+pub mod node_resources_fit;
+pub mod default_binder;
+pub mod node_affinity;
+pub mod taint_toleration;
+pub mod inter_pod_affinity;
+pub mod pod_topology_spread;
+pub mod default_preemption;
\ No newline at end of file
@@ -0,0 +1,49 @@
+use crate::node::Node;
+use crate::pod::Pod;
+use crate::scheduler_algorithm::{CycleState, FilterPlugin, MAX_NODE_SCORE, PluginStatus, SchedulerPlugin, ScorePlugin};
+
+/// Filters nodes by the pod's node selector and required node affinity,
+/// scores nodes by the sum of weights of matched preferred node affinity terms.
+#[derive(Default)]
+pub struct NodeAffinityPlugin;
+
+impl NodeAffinityPlugin {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SchedulerPlugin for NodeAffinityPlugin {
+    fn name(&self) -> &str {
+        "NodeAffinity"
+    }
+}
+
+impl FilterPlugin for NodeAffinityPlugin {
+    fn filter(&mut self, _state: &CycleState, pod: &Pod, node: &Node) -> PluginStatus {
+        if pod.constraints.matches_node_labels(&node.labels) {
+            PluginStatus::Success
+        } else {
+            PluginStatus::UnschedulableAndUnresolvable(
+                "node didn't match pod's node affinity/selector".to_string())
+        }
+    }
+}
+
+impl ScorePlugin for NodeAffinityPlugin {
+    fn score(&mut self, _state: &CycleState, pod: &Pod, node: &Node) -> f64 {
+        match &pod.constraints.node_affinity {
+            Some(affinity) => affinity.preferred_score(&node.labels) as f64,
+            None => 0.0,
+        }
+    }
+
+    fn normalize_score(&mut self, _state: &CycleState, _pod: &Pod, scores: &mut [f64]) {
+        let max_score = scores.iter().cloned().fold(0.0, f64::max);
+        if max_score > 0.0 {
+            for score in scores.iter_mut() {
+                *score = *score * MAX_NODE_SCORE / max_score;
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::node::Node;
+use crate::pod::Pod;
+use crate::scheduler_algorithm::{CycleState, FilterPlugin, NOMINATED_PODS_KEY, NominatedPods, PluginStatus,
+                                 SchedulerPlugin};
+
+/// Filters out nodes without enough free resources for the pod's requests
+/// (resources of pods nominated to the node with not lower priority are reserved).
+#[derive(Default)]
+pub struct NodeResourcesFit;
+
+impl NodeResourcesFit {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SchedulerPlugin for NodeResourcesFit {
+    fn name(&self) -> &str {
+        "NodeResourcesFit"
+    }
+}
+
+impl FilterPlugin for NodeResourcesFit {
+    fn filter(&mut self, state: &CycleState, pod: &Pod, node: &Node) -> PluginStatus {
+        let mut reserved_cpu = 0.0;
+        let mut reserved_memory = 0.0;
+        if let Some(nominated_pods) = state.read::<NominatedPods>(NOMINATED_PODS_KEY) {
+            for (node_id, nominated_pod) in nominated_pods.borrow().values() {
+                if *node_id == node.id && nominated_pod.id != pod.id &&
+                    nominated_pod.priority_weight >= pod.priority_weight {
+                    reserved_cpu += nominated_pod.requested_cpu;
+                    reserved_memory += nominated_pod.requested_memory;
+                }
+            }
+        }
+        if node.can_place_pod(pod.requested_cpu + reserved_cpu, pod.requested_memory + reserved_memory) {
+            PluginStatus::Success
+        } else {
+            PluginStatus::Unschedulable("insufficient resources".to_string())
+        }
+    }
+}
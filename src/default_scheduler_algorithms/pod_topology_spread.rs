@@ -0,0 +1,152 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use crate::affinity::{TopologySpreadConstraint, UnsatisfiableConstraintAction};
+use crate::node::Node;
+use crate::pod::Pod;
+use crate::scheduler_algorithm::{CycleState, FilterPlugin, for_each_existing_pod, MAX_NODE_SCORE, PluginStatus,
+                                 PreFilterPlugin, PreScorePlugin, SchedulerPlugin, ScorePlugin};
+
+const PRE_FILTER_STATE_KEY: &str = "PreFilterPodTopologySpread";
+const PRE_SCORE_STATE_KEY: &str = "PreScorePodTopologySpread";
+
+/// For every constraint of the pod: topology value -> count of matched pods.
+struct TopologyCounts {
+    counts: Vec<HashMap<String, u64>>,
+}
+
+/// Counts pods matched by the constraints in every topology domain. Only domains of nodes, which
+/// match the pod's node selector and node affinity, are counted (empty domains have zero count).
+fn count_matched_pods(state: &CycleState, pod: &Pod, nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
+                      constraints: &[&TopologySpreadConstraint]) -> TopologyCounts {
+    let mut counts = vec![HashMap::<String, u64>::default(); constraints.len()];
+    for node in nodes.values() {
+        let node = node.borrow();
+        if !pod.constraints.matches_node_labels(&node.labels) {
+            continue;
+        }
+        for (constraint, domain_counts) in constraints.iter().zip(counts.iter_mut()) {
+            if let Some(value) = node.labels.get(&constraint.topology_key) {
+                domain_counts.entry(value.clone()).or_insert(0);
+            }
+        }
+    }
+    for_each_existing_pod(state, pod, nodes, |node_labels, existing_pod| {
+        if !pod.constraints.matches_node_labels(node_labels) {
+            return;
+        }
+        for (constraint, domain_counts) in constraints.iter().zip(counts.iter_mut()) {
+            if let Some(value) = node_labels.get(&constraint.topology_key) {
+                if constraint.label_selector.matches(&existing_pod.labels) {
+                    *domain_counts.entry(value.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+    });
+    TopologyCounts { counts }
+}
+
+fn constraints_with_action(pod: &Pod, action: UnsatisfiableConstraintAction) -> Vec<&TopologySpreadConstraint> {
+    pod.constraints.topology_spread_constraints.iter()
+        .filter(|constraint| constraint.when_unsatisfiable == action)
+        .collect()
+}
+
+/// Filters out nodes which increase the skew of DoNotSchedule topology spread constraints above max_skew,
+/// prefers nodes with less matched pods in their domains for ScheduleAnyway constraints.
+#[derive(Default)]
+pub struct PodTopologySpread;
+
+impl PodTopologySpread {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SchedulerPlugin for PodTopologySpread {
+    fn name(&self) -> &str {
+        "PodTopologySpread"
+    }
+}
+
+impl PreFilterPlugin for PodTopologySpread {
+    fn pre_filter(&mut self, state: &mut CycleState, pod: &Pod,
+                  nodes: &BTreeMap<u32, Rc<RefCell<Node>>>) -> PluginStatus {
+        let constraints = constraints_with_action(pod, UnsatisfiableConstraintAction::DoNotSchedule);
+        let counts = count_matched_pods(state, pod, nodes, &constraints);
+        state.write(PRE_FILTER_STATE_KEY, counts);
+        PluginStatus::Success
+    }
+}
+
+impl FilterPlugin for PodTopologySpread {
+    fn filter(&mut self, state: &CycleState, pod: &Pod, node: &Node) -> PluginStatus {
+        let topology_counts = match state.read::<TopologyCounts>(PRE_FILTER_STATE_KEY) {
+            Some(topology_counts) => topology_counts,
+            None => return PluginStatus::Success,
+        };
+        let constraints = constraints_with_action(pod, UnsatisfiableConstraintAction::DoNotSchedule);
+        for (constraint, counts) in constraints.iter().zip(topology_counts.counts.iter()) {
+            let value = match node.labels.get(&constraint.topology_key) {
+                Some(value) => value,
+                None => {
+                    return PluginStatus::UnschedulableAndUnresolvable(
+                        "node didn't have the topology key of the spread constraint".to_string());
+                }
+            };
+            let self_match = constraint.label_selector.matches(&pod.labels) as u64;
+            let min_count = counts.values().cloned().min().unwrap_or(0);
+            let count = counts.get(value).cloned().unwrap_or(0);
+            if count + self_match > min_count + constraint.max_skew {
+                return PluginStatus::UnschedulableAndUnresolvable(
+                    "node didn't match pod topology spread constraints".to_string());
+            }
+        }
+        PluginStatus::Success
+    }
+}
+
+impl PreScorePlugin for PodTopologySpread {
+    fn pre_score(&mut self, state: &mut CycleState, pod: &Pod,
+                 nodes: &BTreeMap<u32, Rc<RefCell<Node>>>, _filtered_node_ids: &[u32]) -> PluginStatus {
+        let constraints = constraints_with_action(pod, UnsatisfiableConstraintAction::ScheduleAnyway);
+        let counts = count_matched_pods(state, pod, nodes, &constraints);
+        state.write(PRE_SCORE_STATE_KEY, counts);
+        PluginStatus::Success
+    }
+}
+
+impl ScorePlugin for PodTopologySpread {
+    /// Returns count of matched pods in domains of the node (infinity if the node doesn't have a topology key).
+    fn score(&mut self, state: &CycleState, pod: &Pod, node: &Node) -> f64 {
+        let topology_counts = match state.read::<TopologyCounts>(PRE_SCORE_STATE_KEY) {
+            Some(topology_counts) => topology_counts,
+            None => return 0.0,
+        };
+        let constraints = constraints_with_action(pod, UnsatisfiableConstraintAction::ScheduleAnyway);
+        let mut score = 0.0;
+        for (constraint, counts) in constraints.iter().zip(topology_counts.counts.iter()) {
+            match node.labels.get(&constraint.topology_key) {
+                Some(value) => score += counts.get(value).cloned().unwrap_or(0) as f64,
+                None => return f64::INFINITY,
+            }
+        }
+        score
+    }
+
+    /// Nodes with less matched pods get higher scores, nodes without topology keys get zero.
+    fn normalize_score(&mut self, _state: &CycleState, _pod: &Pod, scores: &mut [f64]) {
+        let finite_scores = scores.iter().cloned().filter(|score| score.is_finite());
+        let max_score = finite_scores.clone().fold(f64::MIN, f64::max);
+        let min_score = finite_scores.fold(f64::MAX, f64::min);
+        for score in scores.iter_mut() {
+            *score = if !score.is_finite() {
+                0.0
+            } else if max_score > min_score {
+                MAX_NODE_SCORE * (max_score - *score) / (max_score - min_score)
+            } else {
+                MAX_NODE_SCORE
+            };
+        }
+    }
+}
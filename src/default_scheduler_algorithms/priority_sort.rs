@@ -0,0 +1,24 @@
+use crate::pod::Pod;
+use crate::scheduler_algorithm::{QueueSortPlugin, SchedulerPlugin};
+
+/// Sorts pods by priority weight, pods with equal priority are scheduled in FIFO order.
+#[derive(Default)]
+pub struct PrioritySort;
+
+impl PrioritySort {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SchedulerPlugin for PrioritySort {
+    fn name(&self) -> &str {
+        "PrioritySort"
+    }
+}
+
+impl QueueSortPlugin for PrioritySort {
+    fn less(&self, first: &Pod, second: &Pod) -> bool {
+        first.priority_weight > second.priority_weight
+    }
+}
@@ -0,0 +1,55 @@
+use crate::node::Node;
+use crate::pod::Pod;
+use crate::scheduler_algorithm::{CycleState, FilterPlugin, MAX_NODE_SCORE, PluginStatus, SchedulerPlugin, ScorePlugin};
+use crate::taints::{TaintEffect, tolerates_taints, toleration_time};
+
+/// Filters out nodes with NoSchedule/NoExecute taints which the pod doesn't tolerate,
+/// prefers nodes with less not tolerated PreferNoSchedule taints.
+#[derive(Default)]
+pub struct TaintToleration;
+
+impl TaintToleration {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SchedulerPlugin for TaintToleration {
+    fn name(&self) -> &str {
+        "TaintToleration"
+    }
+}
+
+impl FilterPlugin for TaintToleration {
+    fn filter(&mut self, _state: &CycleState, pod: &Pod, node: &Node) -> PluginStatus {
+        let tolerations = &pod.constraints.tolerations;
+        if tolerates_taints(tolerations, &node.taints, TaintEffect::NoSchedule) &&
+            tolerates_taints(tolerations, &node.taints, TaintEffect::NoExecute) {
+            PluginStatus::Success
+        } else {
+            PluginStatus::UnschedulableAndUnresolvable(
+                "node had taints that the pod didn't tolerate".to_string())
+        }
+    }
+}
+
+impl ScorePlugin for TaintToleration {
+    fn score(&mut self, _state: &CycleState, pod: &Pod, node: &Node) -> f64 {
+        node.taints.iter()
+            .filter(|taint| taint.effect == TaintEffect::PreferNoSchedule)
+            .filter(|taint| toleration_time(&pod.constraints.tolerations, taint).is_none())
+            .count() as f64
+    }
+
+    /// Nodes with less intolerable taints get higher scores.
+    fn normalize_score(&mut self, _state: &CycleState, _pod: &Pod, scores: &mut [f64]) {
+        let max_score = scores.iter().cloned().fold(0.0, f64::max);
+        for score in scores.iter_mut() {
+            if max_score > 0.0 {
+                *score = MAX_NODE_SCORE * (1.0 - *score / max_score);
+            } else {
+                *score = MAX_NODE_SCORE;
+            }
+        }
+    }
+}
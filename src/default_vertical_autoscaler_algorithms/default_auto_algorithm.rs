@@ -75,7 +75,7 @@ impl VerticalAutoscalerAlgorithm for AutoVerticalAutoscalerAlgorithm {
         }
     }
 
-    fn try_to_apply_recommendation(&mut self, pod: &Pod, node: &Rc<RefCell<Node>>, recommendation: VPARecommendation) -> bool {
+    fn try_to_apply_recommendation(&mut self, _pod: &Pod, _node: &Rc<RefCell<Node>>, _recommendation: VPARecommendation) -> bool {
         true
     }
 }
\ No newline at end of file
@@ -1,7 +1,8 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::hash::{Hash, Hasher};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use crate::load_model::LoadModel;
-use crate::pod::{Pod, PodStatus};
+use crate::pod::{Pod, PodStatus, SchedulingConstraints};
 
 #[derive(Clone, Serialize)]
 pub struct PodTemplate {
@@ -12,6 +13,79 @@ pub struct PodTemplate {
     pub limit_cpu: f32,
     pub limit_memory: f64,
     pub priority_weight: u64,
+    pub labels: BTreeMap<String, String>,
+    pub constraints: SchedulingConstraints,
+    pub image: Option<String>,
+    /// Time of the pod's work (for pods of jobs).
+    pub duration: Option<f64>,
+}
+
+impl PodTemplate {
+    /// Template without labels, placement constraints, image and duration
+    pub fn new(requested_cpu: f32, requested_memory: f64, limit_cpu: f32, limit_memory: f64, priority_weight: u64,
+               cpu_load_model: Box<dyn LoadModel>, memory_load_model: Box<dyn LoadModel>) -> Self {
+        Self {
+            cpu_load_model, memory_load_model,
+            requested_cpu, requested_memory,
+            limit_cpu, limit_memory,
+            priority_weight,
+            labels: BTreeMap::default(),
+            constraints: SchedulingConstraints::default(),
+            image: None,
+            duration: None,
+        }
+    }
+
+    pub fn with_labels(mut self, labels: BTreeMap<String, String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Set placement constraints (node selector, affinity, tolerations, topology spread)
+    pub fn with_constraints(mut self, constraints: SchedulingConstraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    pub fn with_image(mut self, image: Option<String>) -> Self {
+        self.image = image;
+        self
+    }
+
+    /// Set the time of the pod's work (required for pods of jobs)
+    pub fn with_duration(mut self, duration: f64) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Create a pending pod by the template
+    pub fn create_pod(&self, id: u64, deployment_id: Option<u64>) -> Pod {
+        let mut pod = Pod::new(id, self.cpu_load_model.clone(), self.memory_load_model.clone(),
+                               self.requested_cpu, self.requested_memory, self.limit_cpu, self.limit_memory,
+                               self.priority_weight, PodStatus::Pending, deployment_id);
+        pod.labels = self.labels.clone();
+        pod.constraints = self.constraints.clone();
+        pod.image = self.image.clone();
+        pod.duration = self.duration;
+        pod
+    }
+}
+
+/// Strategy of replacing replicas of the deployment after the update of its template
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DeploymentStrategy {
+    /// Old replicas are replaced gradually: there are at most `max_surge` replicas above the desired count
+    /// and at most `max_unavailable` of desired replicas aren't available (running).
+    RollingUpdate { max_surge: u64, max_unavailable: u64 },
+    /// All old replicas are removed (and terminated) before new ones are created.
+    Recreate,
+}
+
+impl Default for DeploymentStrategy {
+    fn default() -> Self {
+        DeploymentStrategy::RollingUpdate { max_surge: 1, max_unavailable: 0 }
+    }
 }
 
 #[derive(Clone, Serialize)]
@@ -20,6 +94,9 @@ pub struct Deployment {
     pub pod_template: PodTemplate,
     pub cnt_replicas: u64,
     pub start_time: f64,
+    pub strategy: DeploymentStrategy,
+    /// Revision of the pod template (increased by every update).
+    pub revision: u64,
 }
 
 impl Deployment {
@@ -27,19 +104,13 @@ impl Deployment {
         Self {
             id, pod_template, cnt_replicas,
             start_time: -1.,
+            strategy: DeploymentStrategy::default(),
+            revision: 1,
         }
     }
 
     pub fn create_new_replica(&self, id: u64) -> Pod {
-        Pod::new(id,
-                 self.pod_template.cpu_load_model.clone(),
-                 self.pod_template.memory_load_model.clone(),
-                 self.pod_template.requested_cpu,
-                 self.pod_template.requested_memory,
-                 self.pod_template.limit_cpu,
-                 self.pod_template.limit_memory,
-                 self.pod_template.priority_weight,
-                 PodStatus::Pending, Some(self.id))
+        self.pod_template.create_pod(id, Some(self.id))
     }
 }
 
@@ -55,4 +126,28 @@ impl PartialEq<Self> for Deployment {
     }
 }
 
-impl Eq for Deployment {}
\ No newline at end of file
+impl Eq for Deployment {}
+
+/// State of the rollout of the deployment's revision
+#[derive(Clone, Debug, Serialize)]
+pub struct DeploymentRollout {
+    pub revision: u64,
+    pub start_time: f64,
+    pub finish_time: Option<f64>,
+    /// Replicas of previous revisions which aren't removed yet.
+    pub old_replica_ids: BTreeSet<u64>,
+    /// Min count of available (running) replicas since the start of the rollout.
+    pub min_available_replicas: u64,
+}
+
+impl DeploymentRollout {
+    pub fn new(revision: u64, start_time: f64, old_replica_ids: BTreeSet<u64>, available_replicas: u64) -> Self {
+        Self {
+            revision,
+            start_time,
+            finish_time: None,
+            old_replica_ids,
+            min_available_replicas: available_replicas,
+        }
+    }
+}
\ No newline at end of file
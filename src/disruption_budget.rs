@@ -0,0 +1,52 @@
+//! Representation of the k8s PodDisruptionBudget
+
+use serde::{Deserialize, Serialize};
+use crate::affinity::LabelSelector;
+
+/// Pods covered by the disruption budget
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DisruptionBudgetTarget {
+    /// Replicas of the deployment (its desired count of replicas is expected).
+    Deployment { deployment_id: u64 },
+    /// Placed pods matched by the selector (all of them are expected).
+    Selector { selector: LabelSelector },
+}
+
+/// Limit of voluntary evictions (node removals and drains, pods' recreation by VPA):
+/// the eviction is blocked if less than desired pods of the target would be available (running) after it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PodDisruptionBudget {
+    pub target: DisruptionBudgetTarget,
+    /// Min count of available pods.
+    pub min_available: Option<u64>,
+    /// Max count of expected pods which aren't available (used if min_available isn't set).
+    pub max_unavailable: Option<u64>,
+}
+
+impl PodDisruptionBudget {
+    pub fn with_min_available(target: DisruptionBudgetTarget, min_available: u64) -> Self {
+        Self {
+            target,
+            min_available: Some(min_available),
+            max_unavailable: None,
+        }
+    }
+
+    pub fn with_max_unavailable(target: DisruptionBudgetTarget, max_unavailable: u64) -> Self {
+        Self {
+            target,
+            min_available: None,
+            max_unavailable: Some(max_unavailable),
+        }
+    }
+
+    /// Count of pods which must stay available
+    pub fn desired_available(&self, expected_count: u64) -> u64 {
+        if self.min_available.is_some() {
+            self.min_available.unwrap()
+        } else {
+            expected_count.saturating_sub(self.max_unavailable.unwrap_or(0))
+        }
+    }
+}
@@ -79,18 +79,55 @@ pub mod pod {
         pub new_requested_memory: f64,
         pub new_limit_memory: f64,
     }
+
+    #[derive(Clone, Serialize)]
+    pub struct PodOOMKilled {
+        pub pod_id: u64,
+        pub node_id: u32,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct PodRestarted {
+        pub pod_id: u64,
+        pub node_id: u32,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct PodTerminated {
+        pub pod_id: u64,
+        pub node_id: u32,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct PodStarted {
+        pub pod_id: u64,
+        pub node_id: u32,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct PodSucceeded {
+        pub pod_id: u64,
+        pub node_id: u32,
+    }
 }
 
 // DEPLOYMENT EVENTS //
 pub mod deployment {
     use serde::Serialize;
-    use crate::deployment::Deployment;
+    use crate::deployment::{Deployment, DeploymentStrategy, PodTemplate};
 
     #[derive(Clone, Serialize)]
     pub struct DeploymentCreateRequest {
         pub deployment: Deployment,
     }
 
+    #[derive(Clone, Serialize)]
+    pub struct DeploymentUpdateRequest {
+        pub id: u64,
+        pub pod_template: PodTemplate,
+        pub strategy: DeploymentStrategy,
+    }
+
     #[derive(Clone, Serialize)]
     pub struct DeploymentHorizontalAutoscaling {
         pub id: u64,
@@ -98,14 +135,64 @@ pub mod deployment {
     }
 }
 
+// JOB EVENTS //
+pub mod job {
+    use serde::Serialize;
+    use crate::job::{CronJob, Job};
+
+    #[derive(Clone, Serialize)]
+    pub struct JobCreateRequest {
+        pub job: Job,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct JobDeadlineExceeded {
+        pub job_id: u64,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct CronJobCreateRequest {
+        pub cron_job: CronJob,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct CronJobSchedule {
+        pub cron_job_id: u64,
+    }
+}
+
 // NODE CHANGING STATUS EVENTS //
 pub mod node {
     use serde::Serialize;
+    use crate::cloud_provider::ProvisioningError;
     use crate::node::{NodeState};
+    use crate::taints::{Taint, TaintEffect};
+
+    #[derive(Clone, Serialize)]
+    pub struct AllocateNewNodes {
+        pub group_id: usize,
+        pub node_ids: Vec<u32>,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct NodeProvisioningFailed {
+        pub group_id: usize,
+        pub node_id: u32,
+        pub error: ProvisioningError,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct SpotTerminationNotice {
+        pub group_id: usize,
+        pub node_id: u32,
+        pub start_id: u64,
+    }
 
     #[derive(Clone, Serialize)]
-    pub struct AllocateNewDefaultNodes {
-        pub cnt_nodes: u32,
+    pub struct SpotReclamation {
+        pub group_id: usize,
+        pub node_id: u32,
+        pub start_id: u64,
     }
 
     #[derive(Clone, Serialize)]
@@ -113,6 +200,12 @@ pub mod node {
         pub node_id: u32,
     }
 
+    /// The node isn't removed, evictions of its pods are blocked by disruption budgets
+    #[derive(Clone, Serialize)]
+    pub struct NodeRemovalFailed {
+        pub node_id: u32,
+    }
+
     #[derive(Clone, Serialize)]
     pub struct NodeStatusChanged {
         pub node_id: u32,
@@ -122,6 +215,95 @@ pub mod node {
     #[derive(Clone, Serialize)]
     pub struct UpdatePodsResources {
     }
+
+    #[derive(Clone, Serialize)]
+    pub struct ContainerStart {
+        pub pod_id: u64,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct ContainerStop {
+        pub pod_id: u64,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct ContainerExit {
+        pub pod_id: u64,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct NodeTaintAdded {
+        pub node_id: u32,
+        pub taint: Taint,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct NodeTaintRemoved {
+        pub node_id: u32,
+        pub key: String,
+        pub effect: TaintEffect,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct NoExecuteTaintEviction {
+        pub pod_id: u64,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct NodeDrainRequest {
+        pub node_id: u32,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct SendHeartbeat {}
+
+    #[derive(Clone, Serialize)]
+    pub struct NodeHeartbeat {
+        pub node_id: u32,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct NodeLifecycleMonitor {}
+}
+
+// FAULT INJECTION EVENTS //
+pub mod fault {
+    use serde::Serialize;
+
+    #[derive(Clone, Serialize)]
+    pub struct FaultInjectorStart {}
+
+    #[derive(Clone, Serialize)]
+    pub struct NodeFailureInjected {
+        pub unit_id: usize,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct NodeRepairInjected {
+        pub unit_id: usize,
+    }
+}
+
+// MAINTENANCE EVENTS //
+pub mod maintenance {
+    use serde::Serialize;
+
+    #[derive(Clone, Serialize)]
+    pub struct MaintenanceWindowStart {
+        pub window_id: usize,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct MaintenanceNodeDrain {
+        pub window_id: usize,
+        pub node_id: u32,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct MaintenanceNodeRebooted {
+        pub window_id: usize,
+        pub node_id: u32,
+    }
 }
 
 // SCHEDULER'S WORK EVENTS //
@@ -150,11 +332,30 @@ pub mod scheduler {
 // API SERVER INTERACTION EVENTS //
 pub mod api_server {
     use serde::Serialize;
+    use crate::disruption_budget::PodDisruptionBudget;
 
     #[derive(Clone, Serialize)]
     pub struct PodRemoveRequest {
         pub pod_id: u64,
     }
+
+    #[derive(Clone, Serialize)]
+    pub struct PodPreemptionRequest {
+        pub preemptor_id: u64,
+        pub node_id: u32,
+        pub victim_ids: Vec<u64>,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct DisruptionBudgetCreateRequest {
+        pub id: u64,
+        pub budget: PodDisruptionBudget,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct DisruptionBudgetRemoveRequest {
+        pub id: u64,
+    }
 }
 
 pub mod autoscaler {
@@ -183,4 +384,21 @@ pub mod logger {
     #[derive(Clone, Serialize)]
     pub struct MetricsSnapshot {
     }
-}
\ No newline at end of file
+
+    #[derive(Clone, Serialize)]
+    pub struct CostAccounting {
+    }
+}
+
+pub mod outage {
+    use serde::Serialize;
+
+    #[derive(Clone, Serialize)]
+    pub struct ComponentOutageStart {
+        pub queue_events: bool,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct ComponentOutageEnd {
+    }
+}
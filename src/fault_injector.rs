@@ -0,0 +1,188 @@
+//! Injector of random failures of nodes (independent or correlated by a label, like zone or rack)
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
+use crate::api_server::APIServer;
+use crate::events::fault::{FaultInjectorStart, NodeFailureInjected, NodeRepairInjected};
+use crate::events::node::NodeStatusChanged;
+use crate::node::{NodeState, LABEL_HOSTNAME};
+use crate::pod::{Pod, PodStatus};
+use crate::simulation_config::{FailureDistribution, FaultInjectorConfig, SimulationConfig};
+use crate::simulation_metrics::FaultInjectionStats;
+
+/// Nodes which fail and are repaired together
+pub struct FailureUnit {
+    pub name: String,
+    pub node_ids: Vec<u32>,
+    /// Nodes crashed by the last failure of the unit.
+    pub failed_node_ids: Vec<u32>,
+    pub failure_time: f64,
+    /// Positions in trace distributions.
+    failure_trace_index: usize,
+    repair_trace_index: usize,
+}
+
+pub struct FaultInjector {
+    pub id: u32,
+    pub units: Vec<FailureUnit>,
+    pub stats: FaultInjectionStats,
+    /// Whether nodes are grouped into failure units (nodes added later join them).
+    started: bool,
+    config: FaultInjectorConfig,
+    api_server: Rc<RefCell<APIServer>>,
+
+    ctx: SimulationContext,
+    sim_config: Rc<SimulationConfig>,
+}
+
+impl FaultInjector {
+    pub fn new(config: FaultInjectorConfig, api_server: Rc<RefCell<APIServer>>, ctx: SimulationContext,
+               sim_config: Rc<SimulationConfig>) -> Self {
+        config.validate();
+        Self {
+            id: ctx.id(),
+            units: Vec::default(),
+            stats: FaultInjectionStats::default(),
+            started: false,
+            config,
+            api_server,
+            ctx,
+            sim_config,
+        }
+    }
+
+    /// Sample time from the distribution with the simulation's random generator
+    fn sample(ctx: &mut SimulationContext, distribution: &FailureDistribution, trace_index: &mut usize) -> f64 {
+        match distribution {
+            FailureDistribution::Exponential { mean } => {
+                -mean * (1.0 - ctx.rand()).ln()
+            }
+            FailureDistribution::Weibull { shape, scale } => {
+                scale * (-(1.0 - ctx.rand()).ln()).powf(1.0 / shape)
+            }
+            FailureDistribution::Trace { values } => {
+                let value = values[*trace_index % values.len()];
+                *trace_index += 1;
+                value
+            }
+        }
+    }
+
+    fn unit_name(&self, labels: &BTreeMap<String, String>) -> String {
+        let label = self.config.correlation_label.clone().unwrap_or_else(|| LABEL_HOSTNAME.to_string());
+        labels.get(&label).or(labels.get(LABEL_HOSTNAME)).unwrap().clone()
+    }
+
+    /// Group working nodes into failure units and plan their first failures
+    fn start(&mut self) {
+        self.started = true;
+        let mut units = BTreeMap::<String, Vec<u32>>::default();
+        for (node_id, node) in self.api_server.borrow().working_nodes.iter() {
+            units.entry(self.unit_name(&node.borrow().labels)).or_default().push(*node_id);
+        }
+
+        for (name, node_ids) in units {
+            self.add_unit(name, node_ids);
+        }
+    }
+
+    fn add_unit(&mut self, name: String, node_ids: Vec<u32>) {
+        self.units.push(FailureUnit {
+            name,
+            node_ids,
+            failed_node_ids: Vec::default(),
+            failure_time: 0.0,
+            failure_trace_index: 0,
+            repair_trace_index: 0,
+        });
+        self.plan_failure(self.units.len() - 1);
+    }
+
+    /// The node added to the cluster after the start (e.g. by the cluster autoscaler) joins
+    /// the failure unit of its label or forms a new one
+    pub fn on_node_added(&mut self, node_id: u32, labels: &BTreeMap<String, String>) {
+        if !self.started || self.units.iter().any(|unit| unit.node_ids.contains(&node_id)) {
+            return;
+        }
+        let name = self.unit_name(labels);
+        let unit = self.units.iter_mut().find(|unit| unit.name == name);
+        if unit.is_some() {
+            unit.unwrap().node_ids.push(node_id);
+            return;
+        }
+        self.add_unit(name, vec![node_id]);
+    }
+
+    /// Pods of the node crashed by the injected failure are returned to the scheduler by the API server,
+    /// the work of their running containers since their last starts is lost
+    pub fn on_node_crashed(&mut self, node_id: u32, failure_time: f64, pods: &[Pod]) {
+        if !self.units.iter().any(|unit| unit.failed_node_ids.contains(&node_id)) {
+            return;
+        }
+        for pod in pods {
+            if pod.status == PodStatus::Running && pod.container_run_start_time <= failure_time {
+                self.stats.lost_pod_seconds += failure_time - pod.container_run_start_time;
+            }
+            self.stats.rescheduled_pod_count += 1;
+        }
+    }
+
+    fn plan_failure(&mut self, unit_id: usize) {
+        let unit = self.units.get_mut(unit_id).unwrap();
+        let delay = Self::sample(&mut self.ctx, &self.config.time_to_failure, &mut unit.failure_trace_index);
+        self.ctx.emit(NodeFailureInjected { unit_id }, self.id, delay);
+    }
+
+    /// Crash working nodes of the unit and plan their repair
+    fn inject_failure(&mut self, unit_id: usize) {
+        let api_server_id = self.api_server.borrow().id;
+        let now = self.ctx.time();
+        let unit = self.units.get_mut(unit_id).unwrap();
+        unit.failure_time = now;
+        unit.failed_node_ids.clear();
+        for node_id in unit.node_ids.iter() {
+            let api_server = self.api_server.borrow();
+            let node = api_server.working_nodes.get(node_id);
+            if node.is_none() || node.unwrap().borrow().state != NodeState::Working {
+                continue;
+            }
+            self.stats.node_failure_count += 1;
+            unit.failed_node_ids.push(*node_id);
+            self.ctx.emit(NodeStatusChanged { node_id: *node_id, new_status: NodeState::Failed },
+                          api_server_id, self.sim_config.control_plane_message_delay);
+        }
+
+        let delay = Self::sample(&mut self.ctx, &self.config.time_to_repair, &mut unit.repair_trace_index);
+        self.ctx.emit(NodeRepairInjected { unit_id }, self.id, delay);
+    }
+
+    /// Recover nodes crashed by the last failure of the unit and plan the next failure
+    fn repair(&mut self, unit_id: usize) {
+        let api_server_id = self.api_server.borrow().id;
+        let unit = self.units.get_mut(unit_id).unwrap();
+        for node_id in std::mem::take(&mut unit.failed_node_ids) {
+            self.stats.node_downtime += self.ctx.time() - unit.failure_time;
+            self.ctx.emit(NodeStatusChanged { node_id, new_status: NodeState::Working },
+                          api_server_id, self.sim_config.control_plane_message_delay);
+        }
+        self.plan_failure(unit_id);
+    }
+}
+
+impl EventHandler for FaultInjector {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            FaultInjectorStart {} => {
+                self.start();
+            }
+            NodeFailureInjected { unit_id } => {
+                self.inject_failure(unit_id);
+            }
+            NodeRepairInjected { unit_id } => {
+                self.repair(unit_id);
+            }
+        })
+    }
+}
@@ -18,7 +18,7 @@ const NUM_BUCKETS: usize = 100;
 impl Histogram {
     pub fn new(max_value: f64) -> Self {
         Self {
-            bucket_weight: vec![0; NUM_BUCKETS as usize],
+            bucket_weight: vec![0; NUM_BUCKETS],
             total_weight: 0,
             bucket_size: max_value / (NUM_BUCKETS as f64),
             min_bucket: NUM_BUCKETS - 1,
@@ -30,7 +30,7 @@ impl Histogram {
 
     pub fn min(&self) -> f64 {
         if self.bucket_weight[self.min_bucket] == 0 {
-            return -1.0;
+            -1.0
         } else {
             self.get_bucket_start(self.min_bucket)
         }
@@ -38,7 +38,7 @@ impl Histogram {
 
     pub fn max(&self) -> f64 {
         if self.bucket_weight[self.max_bucket] == 0 {
-            return -1.0;
+            -1.0
         } else {
             self.get_bucket_start(self.max_bucket)
         }
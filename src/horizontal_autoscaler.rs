@@ -2,9 +2,12 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use dslab_core::{cast, Event, EventHandler, SimulationContext};
 use crate::api_server::APIServer;
+use crate::control_plane_outage::OutageState;
 use crate::events::autoscaler::HorizontalAutoscalerCycle;
 use crate::events::deployment::DeploymentHorizontalAutoscaling;
+use crate::events::outage::{ComponentOutageEnd, ComponentOutageStart};
 use crate::metrics_server::{MetricsServer, PodStatistic};
+use crate::network::NetworkModel;
 use crate::simulation_config::SimulationConfig;
 use crate::horizontal_autoscaler_algorithm::HorizontalAutoscalerAlgorithm;
 
@@ -13,6 +16,8 @@ pub struct HorizontalAutoscaler {
     api_server: Rc<RefCell<APIServer>>,
     metrics_server: Rc<RefCell<MetricsServer>>,
     hpa_algorithm: Box<dyn HorizontalAutoscalerAlgorithm>,
+    network: Rc<RefCell<NetworkModel>>,
+    pub outage: OutageState,
 
     ctx: SimulationContext,
     sim_config: Rc<SimulationConfig>,
@@ -20,13 +25,15 @@ pub struct HorizontalAutoscaler {
 
 impl HorizontalAutoscaler {
     pub fn new(api_server: Rc<RefCell<APIServer>>, metrics_server: Rc<RefCell<MetricsServer>>,
-               hpa_algorithm: Box<dyn HorizontalAutoscalerAlgorithm>,
+               hpa_algorithm: Box<dyn HorizontalAutoscalerAlgorithm>, network: Rc<RefCell<NetworkModel>>,
                ctx: SimulationContext, sim_config: Rc<SimulationConfig>) -> Self {
         Self {
             id: ctx.id(),
             api_server,
             metrics_server,
             hpa_algorithm,
+            network,
+            outage: OutageState::default(),
             ctx,
             sim_config
         }
@@ -36,11 +43,10 @@ impl HorizontalAutoscaler {
         let api_server = self.api_server.borrow();
         let metrics_server = self.metrics_server.borrow();
         for (deployment_id, replicas) in &api_server.deployment_to_replicas {
-            let statistics = replicas.into_iter()
+            let statistics = replicas.iter()
                 .map(|id| metrics_server.get_pod_statistics(*id));
             let not_fully_deployed = statistics.clone()
-                .map(|statistic| statistic.is_none())
-                .any(|x| x);
+                .any(|statistic| statistic.is_none());
             if not_fully_deployed {
                 continue
             }
@@ -52,21 +58,40 @@ impl HorizontalAutoscaler {
             let new_cnt_replicas = self.hpa_algorithm
                 .get_new_count_replicas(deployment, &statistics, self.ctx.time());
             if new_cnt_replicas != deployment.cnt_replicas {
-                self.ctx.emit(DeploymentHorizontalAutoscaling {
+                self.network.borrow_mut().send(&self.ctx, DeploymentHorizontalAutoscaling {
                     id: deployment.id, new_cnt_replicas
                 }, api_server.id, self.sim_config.message_delay);
             }
         }
     }
+
+    /// Restart after the outage: stabilization state of the algorithm is lost
+    fn restart(&mut self) {
+        self.hpa_algorithm.reset();
+        for event in self.outage.finish() {
+            self.on(event);
+        }
+    }
 }
 
 impl EventHandler for HorizontalAutoscaler {
     fn on(&mut self, event: Event) {
+        let event = self.outage.intercept::<HorizontalAutoscalerCycle>(event);
+        if event.is_none() {
+            return;
+        }
+        let event = event.unwrap();
         cast!(match event.data {
             HorizontalAutoscalerCycle {} => {
                 self.try_to_scale();
                 self.ctx.emit(HorizontalAutoscalerCycle{}, self.id, self.sim_config.hpa_interval);
             }
+            ComponentOutageStart { queue_events } => {
+                self.outage.start(queue_events);
+            }
+            ComponentOutageEnd {} => {
+                self.restart();
+            }
         })
     }
 }
\ No newline at end of file
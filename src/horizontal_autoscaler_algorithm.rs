@@ -4,4 +4,7 @@ use crate::metrics_server::PodStatistic;
 pub trait HorizontalAutoscalerAlgorithm {
     fn get_new_count_replicas(&mut self, deployment: &Deployment,
                               statistics: &Vec<PodStatistic>, now_time: f64) -> u64;
+
+    /// Forgets the in-memory state (on restart of the autoscaler)
+    fn reset(&mut self) {}
 }
\ No newline at end of file
@@ -0,0 +1,113 @@
+//! Representation of the k8s Job and CronJob
+
+use std::collections::BTreeSet;
+use serde::Serialize;
+use crate::deployment::PodTemplate;
+use crate::pod::Pod;
+
+/// Parameters of the job (as in the spec of k8s Job)
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct JobSpec {
+    /// Count of pods which must succeed to complete the job.
+    pub completions: u64,
+    /// Max count of pods of the job working at the same time.
+    pub parallelism: u64,
+    /// Count of failures (OOM kills of job's containers) after which the job fails.
+    pub backoff_limit: u64,
+    /// Time since the start of the job after which it fails if it isn't completed.
+    pub active_deadline_seconds: Option<f64>,
+}
+
+impl JobSpec {
+    pub fn new(completions: u64, parallelism: u64, backoff_limit: u64,
+               active_deadline_seconds: Option<f64>) -> Self {
+        Self {
+            completions,
+            parallelism,
+            backoff_limit,
+            active_deadline_seconds,
+        }
+    }
+}
+
+impl Default for JobSpec {
+    fn default() -> Self {
+        Self::new(1, 1, 6, None)
+    }
+}
+
+/// Job status
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum JobStatus {
+    Active,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Clone, Serialize)]
+pub struct Job {
+    pub id: u64,
+    /// Template of job's pods (pods have the duration of the work).
+    pub pod_template: PodTemplate,
+    pub spec: JobSpec,
+    /// Cron job which created this job.
+    pub cron_job_id: Option<u64>,
+
+    pub status: JobStatus,
+    pub start_time: f64,
+    pub completion_time: Option<f64>,
+    /// Pods of the job which aren't finished yet.
+    pub active_pod_ids: BTreeSet<u64>,
+    pub succeeded_count: u64,
+    pub failed_count: u64,
+}
+
+impl Job {
+    pub fn new(id: u64, pod_template: PodTemplate, spec: JobSpec) -> Self {
+        Self {
+            id, pod_template, spec,
+            cron_job_id: None,
+            status: JobStatus::Active,
+            start_time: -1.,
+            completion_time: None,
+            active_pod_ids: BTreeSet::default(),
+            succeeded_count: 0,
+            failed_count: 0,
+        }
+    }
+
+    pub fn create_new_pod(&self, id: u64) -> Pod {
+        let mut pod = self.pod_template.create_pod(id, None);
+        pod.job_id = Some(self.id);
+        pod
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct CronJob {
+    pub id: u64,
+    pub pod_template: PodTemplate,
+    /// Spec of created jobs.
+    pub spec: JobSpec,
+    /// Period of jobs' creation (the first job is created on the submit of the cron job).
+    pub schedule_period: f64,
+    /// Max count of created jobs (infinite if None).
+    pub jobs_limit: Option<u64>,
+    pub job_ids: Vec<u64>,
+}
+
+impl CronJob {
+    pub fn new(id: u64, pod_template: PodTemplate, spec: JobSpec, schedule_period: f64,
+               jobs_limit: Option<u64>) -> Self {
+        Self {
+            id, pod_template, spec, schedule_period, jobs_limit,
+            job_ids: Vec::default(),
+        }
+    }
+
+    pub fn create_new_job(&self, id: u64) -> Job {
+        let mut job = Job::new(id, self.pod_template.clone(), self.spec.clone());
+        job.cron_job_id = Some(self.id);
+        job
+    }
+}
@@ -0,0 +1,172 @@
+//! Controller of k8s jobs: keeps pods of active jobs working until they are completed or failed
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
+use crate::api_server::APIServer;
+use crate::events::api_server::PodRemoveRequest;
+use crate::events::assigning::PodAssigningRequest;
+use crate::events::job::{JobCreateRequest, JobDeadlineExceeded};
+use crate::events::pod::{PodOOMKilled, PodSucceeded};
+use crate::job::{Job, JobStatus};
+use crate::network::NetworkModel;
+use crate::simulation_config::SimulationConfig;
+use crate::simulation_metrics::JobCompletion;
+
+pub struct JobController {
+    pub id: u32,
+    pub jobs: BTreeMap<u64, Job>,
+    pod_to_job_map: HashMap<u64, u64>,
+    api_server: Rc<RefCell<APIServer>>,
+    network: Rc<RefCell<NetworkModel>>,
+
+    ctx: SimulationContext,
+    sim_config: Rc<SimulationConfig>,
+
+    job_completions: Vec<JobCompletion>,
+    first_job_start_time: Option<f64>,
+    last_job_completion_time: Option<f64>,
+
+    job_counter: u64,
+}
+
+impl JobController {
+    pub fn new(api_server: Rc<RefCell<APIServer>>, network: Rc<RefCell<NetworkModel>>, ctx: SimulationContext,
+               sim_config: Rc<SimulationConfig>) -> Self {
+        Self {
+            id: ctx.id(),
+            jobs: BTreeMap::default(),
+            pod_to_job_map: HashMap::default(),
+            api_server,
+            network,
+            ctx,
+            sim_config,
+            job_completions: Vec::default(),
+            first_job_start_time: None,
+            last_job_completion_time: None,
+            job_counter: 0,
+        }
+    }
+
+    pub fn generate_job_id(&mut self) -> u64 {
+        self.job_counter += 1;
+        self.job_counter
+    }
+
+    pub fn add_job(&mut self, mut job: Job) {
+        let job_id = job.id;
+        job.start_time = self.ctx.time();
+        if self.first_job_start_time.is_none() {
+            self.first_job_start_time = Some(job.start_time);
+        }
+        if job.spec.active_deadline_seconds.is_some() {
+            self.ctx.emit(JobDeadlineExceeded { job_id }, self.id, job.spec.active_deadline_seconds.unwrap());
+        }
+        self.jobs.insert(job_id, job);
+        self.sync_job(job_id);
+    }
+
+    /// Create new pods of the job while it has less than `parallelism` working pods
+    /// (and pods which are working or succeeded are less than `completions`)
+    fn sync_job(&mut self, job_id: u64) {
+        let job = self.jobs.get_mut(&job_id).unwrap();
+        if job.status != JobStatus::Active {
+            return;
+        }
+        while (job.active_pod_ids.len() as u64) < job.spec.parallelism &&
+            job.succeeded_count + (job.active_pod_ids.len() as u64) < job.spec.completions {
+            let pod_id = self.api_server.borrow_mut().generate_pod_id();
+            self.network.borrow_mut().send_reliable(&self.ctx, PodAssigningRequest { pod: job.create_new_pod(pod_id) },
+                                                    self.api_server.borrow().id, self.sim_config.message_delay);
+            job.active_pod_ids.insert(pod_id);
+            self.pod_to_job_map.insert(pod_id, job_id);
+        }
+    }
+
+    /// Finish the job, its pods which are still working are removed
+    fn finish_job(&mut self, job_id: u64, status: JobStatus) {
+        let job = self.jobs.get_mut(&job_id).unwrap();
+        job.status = status;
+        job.completion_time = Some(self.ctx.time());
+        for pod_id in std::mem::take(&mut job.active_pod_ids) {
+            self.pod_to_job_map.remove(&pod_id);
+            self.network.borrow_mut().send_reliable(&self.ctx, PodRemoveRequest { pod_id }, self.api_server.borrow().id,
+                                                    self.sim_config.message_delay);
+        }
+
+        self.job_completions.push(JobCompletion {
+            job_id,
+            cron_job_id: job.cron_job_id,
+            start_time: job.start_time,
+            completion_time: self.ctx.time(),
+            succeeded: job.status == JobStatus::Succeeded,
+        });
+        self.last_job_completion_time = Some(self.ctx.time());
+    }
+
+    fn on_pod_succeeded(&mut self, pod_id: u64) {
+        let job_id = self.pod_to_job_map.remove(&pod_id);
+        if job_id.is_none() {
+            return;
+        }
+        let job_id = job_id.unwrap();
+        let job = self.jobs.get_mut(&job_id).unwrap();
+        job.active_pod_ids.remove(&pod_id);
+        job.succeeded_count += 1;
+
+        if job.succeeded_count >= job.spec.completions {
+            self.finish_job(job_id, JobStatus::Succeeded);
+        } else {
+            self.sync_job(job_id);
+        }
+    }
+
+    fn on_pod_failed(&mut self, pod_id: u64) {
+        let job_id = self.pod_to_job_map.get(&pod_id);
+        if job_id.is_none() {
+            return;
+        }
+        let job_id = *job_id.unwrap();
+        let job = self.jobs.get_mut(&job_id).unwrap();
+        job.failed_count += 1;
+
+        if job.failed_count > job.spec.backoff_limit {
+            self.finish_job(job_id, JobStatus::Failed);
+        }
+    }
+
+    /// Jobs finished since the previous call
+    pub fn take_job_completions(&mut self) -> Vec<JobCompletion> {
+        std::mem::take(&mut self.job_completions)
+    }
+
+    /// Time from the start of the first job to the completion of the last finished job
+    pub fn makespan(&self) -> f64 {
+        if self.last_job_completion_time.is_none() {
+            return 0.0;
+        }
+        self.last_job_completion_time.unwrap() - self.first_job_start_time.unwrap()
+    }
+}
+
+impl EventHandler for JobController {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            JobCreateRequest { job } => {
+                self.add_job(job);
+            }
+            JobDeadlineExceeded { job_id } => {
+                if self.jobs.get(&job_id).unwrap().status == JobStatus::Active {
+                    self.finish_job(job_id, JobStatus::Failed);
+                }
+            }
+            PodSucceeded { pod_id, .. } => {
+                self.on_pod_succeeded(pod_id);
+            }
+            PodOOMKilled { pod_id, .. } => {
+                self.on_pod_failed(pod_id);
+            }
+        })
+    }
+}
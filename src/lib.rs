@@ -1,23 +1,41 @@
+// the crate is named after the project; checks of options before unwrap() and &Vec arguments of traits
+// are conventions of the code
+#![allow(non_snake_case, clippy::unnecessary_unwrap, clippy::too_many_arguments, clippy::ptr_arg)]
+
 pub mod simulation;
 pub mod api_server;
 pub mod node;
 pub mod pod;
+pub mod affinity;
+pub mod taints;
+pub mod priority_class;
 pub mod events;
 pub mod scheduler;
 pub mod experiment;
 pub mod simulation_config;
 pub mod experiment_config;
 pub mod scheduler_algorithm;
+pub mod scheduler_profile;
 pub mod default_scheduler_algorithms;
 pub mod cluster_autoscaler;
 pub mod cluster_autoscaler_algorithm;
 pub mod default_cluster_autoscaler_algorithms;
+pub mod cloud_provider;
 mod metrics_server;
 pub mod vertical_autoscaler;
 pub mod vertical_autoscaler_algorithm;
 pub mod load_model;
 pub mod default_vertical_autoscaler_algorithms;
 pub mod deployment;
+pub mod job;
+pub mod disruption_budget;
+pub mod job_controller;
+pub mod cron_job_controller;
+pub mod maintenance_controller;
+pub mod fault_injector;
+pub mod node_lifecycle_controller;
+pub mod network;
+pub mod control_plane_outage;
 pub mod horizontal_autoscaler;
 pub mod horizontal_autoscaler_algorithm;
 pub mod default_horizontal_autoscaler_algorithms;
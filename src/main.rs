@@ -1,17 +1,13 @@
-use std::time::Instant;
 use dslab_core::Simulation;
-use K8s_simulator::default_cluster_autoscaler_algorithms::default_simple_algorithm::SimpleClusterAutoscalerAlgorithm;
 use K8s_simulator::default_horizontal_autoscaler_algorithms::default_horizontal_algorithm::ControlledResources::MemoryOnly;
 use K8s_simulator::default_horizontal_autoscaler_algorithms::default_horizontal_algorithm::ResourcesHorizontalAutoscalerAlgorithm;
-use K8s_simulator::default_scheduler_algorithms::lrp_algorithm::LRPAlgorithm;
 use K8s_simulator::default_scheduler_algorithms::mrp_algorithm::MRPAlgorithm;
-use K8s_simulator::default_vertical_autoscaler_algorithms::default_auto_algorithm::AutoVerticalAutoscalerAlgorithm;
-use K8s_simulator::default_vertical_autoscaler_algorithms::default_auto_algorithm::ControlledValuesMode::{RequestsAndLimits, RequestsOnly};
 use K8s_simulator::load_model::{ConstantLoadModel, ResourceSnapshot, TraceLoadModel};
 use K8s_simulator::logger::StdoutLogger;
+use K8s_simulator::scheduler_profile::SchedulerProfile;
 use K8s_simulator::simulation::K8sSimulation;
 use K8s_simulator::simulation_config::SimulationConfig;
-use K8s_simulator::simulation_metrics::{EmptyMetricsLogger, FileMetricsLogger};
+use K8s_simulator::simulation_metrics::FileMetricsLogger;
 
 fn main() {
     let sim = Simulation::new(42);
@@ -21,7 +17,7 @@ fn main() {
                                          Box::new(FileMetricsLogger::new(10.)),
                                          Box::new(StdoutLogger::new()),
                                          sim_config,
-                                         Box::new(MRPAlgorithm::new()),
+                                         SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
                                          None,
                                          None,
                                          Some(Box::new(ResourcesHorizontalAutoscalerAlgorithm::new(
@@ -0,0 +1,117 @@
+//! Controller of maintenance windows: drains and reboots nodes of windows one after another
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
+use crate::api_server::APIServer;
+use crate::events::maintenance::{MaintenanceNodeDrain, MaintenanceNodeRebooted, MaintenanceWindowStart};
+use crate::events::node::{NodeStatusChanged, NodeTaintRemoved};
+use crate::node::NodeState;
+use crate::simulation_config::{MaintenanceWindow, SimulationConfig};
+use crate::taints::{TAINT_UNSCHEDULABLE, TaintEffect};
+
+/// State of the maintenance window
+pub struct MaintenanceWindowState {
+    pub window: MaintenanceWindow,
+    /// Nodes which aren't under maintenance yet.
+    pub pending_node_ids: VecDeque<u32>,
+    /// Count of nodes which are drained or rebooted now.
+    pub active_nodes_count: u64,
+    pub rebooted_node_ids: Vec<u32>,
+}
+
+pub struct MaintenanceController {
+    pub id: u32,
+    pub windows: Vec<MaintenanceWindowState>,
+    api_server: Rc<RefCell<APIServer>>,
+
+    ctx: SimulationContext,
+    sim_config: Rc<SimulationConfig>,
+}
+
+impl MaintenanceController {
+    pub fn new(api_server: Rc<RefCell<APIServer>>, ctx: SimulationContext,
+               sim_config: Rc<SimulationConfig>) -> Self {
+        Self {
+            id: ctx.id(),
+            windows: Vec::default(),
+            api_server,
+            ctx,
+            sim_config,
+        }
+    }
+
+    /// Add the window with ids of its nodes, return window_id
+    pub fn add_window(&mut self, window: MaintenanceWindow, node_ids: Vec<u32>) -> usize {
+        self.windows.push(MaintenanceWindowState {
+            window,
+            pending_node_ids: node_ids.into_iter().collect(),
+            active_nodes_count: 0,
+            rebooted_node_ids: Vec::default(),
+        });
+        self.windows.len() - 1
+    }
+
+    /// Start maintenance of pending nodes while less than max_unavailable_nodes are under maintenance
+    fn start_next_nodes(&mut self, window_id: usize) {
+        let state = self.windows.get_mut(window_id).unwrap();
+        while state.active_nodes_count < state.window.max_unavailable_nodes {
+            let node_id = state.pending_node_ids.pop_front();
+            if node_id.is_none() {
+                return;
+            }
+            state.active_nodes_count += 1;
+            self.ctx.emit(MaintenanceNodeDrain { window_id, node_id: node_id.unwrap() }, self.id, 0.0);
+        }
+    }
+
+    /// Drain the node (retry later if evictions are blocked), then reboot it
+    fn drain_node(&mut self, window_id: usize, node_id: u32) {
+        let api_server_id = self.api_server.borrow().id;
+        if !self.api_server.borrow().working_nodes.contains_key(&node_id) {
+            // node isn't working, skip it
+            self.windows[window_id].active_nodes_count -= 1;
+            self.start_next_nodes(window_id);
+            return;
+        }
+        if !self.api_server.borrow_mut().drain_node(node_id) {
+            self.ctx.emit(MaintenanceNodeDrain { window_id, node_id }, self.id, self.sim_config.drain_retry_interval);
+            return;
+        }
+        self.ctx.emit(NodeStatusChanged { node_id, new_status: NodeState::Failed },
+                      api_server_id, self.sim_config.control_plane_message_delay);
+        self.ctx.emit(MaintenanceNodeRebooted { window_id, node_id }, self.id,
+                      self.windows[window_id].window.reboot_duration);
+    }
+
+    fn on_node_rebooted(&mut self, window_id: usize, node_id: u32) {
+        let api_server_id = self.api_server.borrow().id;
+        self.ctx.emit(NodeStatusChanged { node_id, new_status: NodeState::Working },
+                      api_server_id, self.sim_config.control_plane_message_delay);
+        self.ctx.emit(NodeTaintRemoved { node_id, key: TAINT_UNSCHEDULABLE.to_string(),
+                                         effect: TaintEffect::NoSchedule },
+                      api_server_id, self.sim_config.control_plane_message_delay);
+
+        let state = self.windows.get_mut(window_id).unwrap();
+        state.active_nodes_count -= 1;
+        state.rebooted_node_ids.push(node_id);
+        self.start_next_nodes(window_id);
+    }
+}
+
+impl EventHandler for MaintenanceController {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            MaintenanceWindowStart { window_id } => {
+                self.start_next_nodes(window_id);
+            }
+            MaintenanceNodeDrain { window_id, node_id } => {
+                self.drain_node(window_id, node_id);
+            }
+            MaintenanceNodeRebooted { window_id, node_id } => {
+                self.on_node_rebooted(window_id, node_id);
+            }
+        })
+    }
+}
@@ -1,9 +1,12 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::rc::Rc;
 use dslab_core::{cast, Event, EventHandler, SimulationContext};
 use crate::api_server::APIServer;
+use crate::control_plane_outage::OutageState;
 use crate::events::autoscaler::MetricsServerSnapshot;
+use crate::events::outage::{ComponentOutageEnd, ComponentOutageStart};
 use crate::histogram::Histogram;
 use crate::simulation_config::SimulationConfig;
 
@@ -26,6 +29,7 @@ pub struct MetricsServer {
     pods_cpu_distribution: HashMap<u64, Histogram>,
     pods_memory_distribution: HashMap<u64, Histogram>,
     pods_last_snapshot: HashMap<u64, PodSnapshot>,
+    pub outage: OutageState,
 
     api_server: Rc<RefCell<APIServer>>,
 
@@ -41,23 +45,20 @@ impl MetricsServer {
             pods_cpu_distribution: HashMap::default(),
             pods_memory_distribution: HashMap::default(),
             pods_last_snapshot: HashMap::default(),
+            outage: OutageState::default(),
             api_server,
             ctx,
             sim_config
         }
     }
 
-    pub fn get_pod_statistics(&self, pod_id: u64) -> Option<PodStatistic> {
+    pub fn get_pod_statistics(&self, pod_id: u64) -> Option<PodStatistic<'_>> {
         // get last snapshot
-        let last_snapshot = self.pods_last_snapshot.get(&pod_id);
-        if last_snapshot.is_none() {
-            return None;
-        }
-        let last_snapshot = *last_snapshot.unwrap();
+        let last_snapshot = *self.pods_last_snapshot.get(&pod_id)?;
 
         Some(PodStatistic {
-            cpu_distribution: &self.pods_cpu_distribution.get(&pod_id).unwrap(),
-            memory_distribution: &self.pods_memory_distribution.get(&pod_id).unwrap(),
+            cpu_distribution: self.pods_cpu_distribution.get(&pod_id).unwrap(),
+            memory_distribution: self.pods_memory_distribution.get(&pod_id).unwrap(),
             last_snapshot,
         })
     }
@@ -70,16 +71,16 @@ impl MetricsServer {
 
     fn make_snapshot(&mut self) {
         let nodes = &self.api_server.borrow().working_nodes;
-        for (_, node) in nodes.into_iter() {
+        for (_, node) in nodes.iter() {
             let pods = &node.borrow().pods;
-            for (_, pod) in pods.into_iter() {
+            for (_, pod) in pods.iter() {
                 let pod_snapshot = PodSnapshot {
                     cpu: pod.cpu,
                     memory: pod.memory,
                     snapshot_time: self.ctx.time()
                 };
-                if !self.pods_cpu_distribution.contains_key(&pod.id) {
-                    self.pods_cpu_distribution.insert(pod.id, Histogram::new(pod.limit_cpu as f64));
+                if let Entry::Vacant(entry) = self.pods_cpu_distribution.entry(pod.id) {
+                    entry.insert(Histogram::new(pod.limit_cpu as f64));
                     self.pods_memory_distribution.insert(pod.id, Histogram::new(pod.limit_memory));
                 }
                 self.pods_cpu_distribution.get_mut(&pod.id).unwrap()
@@ -90,15 +91,36 @@ impl MetricsServer {
             }
         }
     }
+
+    /// Restart after the outage: collected statistics of pods are lost
+    fn restart(&mut self) {
+        self.pods_cpu_distribution.clear();
+        self.pods_memory_distribution.clear();
+        self.pods_last_snapshot.clear();
+        for event in self.outage.finish() {
+            self.on(event);
+        }
+    }
 }
 
 impl EventHandler for MetricsServer {
     fn on(&mut self, event: Event) {
+        let event = self.outage.intercept::<MetricsServerSnapshot>(event);
+        if event.is_none() {
+            return;
+        }
+        let event = event.unwrap();
         cast!(match event.data {
             MetricsServerSnapshot {} => {
                 self.make_snapshot();
                 self.ctx.emit(MetricsServerSnapshot{}, self.id, self.sim_config.metrics_server_interval);
             }
+            ComponentOutageStart { queue_events } => {
+                self.outage.start(queue_events);
+            }
+            ComponentOutageEnd {} => {
+                self.restart();
+            }
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,145 @@
+//! Model of the network between components: latencies, losses and partitions of messages
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use dslab_core::event::EventData;
+use dslab_core::SimulationContext;
+use crate::simulation_config::{LatencyDistribution, LinkConfig, NetworkConfig, SimulationConfig};
+
+/// Whether the name of the component matches the pattern (exact name or prefix with `*` at the end)
+fn name_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+fn any_matches(patterns: &[String], name: &str) -> bool {
+    patterns.iter().any(|pattern| name_matches(pattern, name))
+}
+
+pub struct NetworkModel {
+    /// Names of components by their ids.
+    names: HashMap<u32, String>,
+    config: NetworkConfig,
+    pub lost_message_count: u64,
+
+    ctx: SimulationContext,
+}
+
+impl NetworkModel {
+    pub fn new(ctx: SimulationContext, sim_config: Rc<SimulationConfig>) -> Self {
+        Self {
+            names: HashMap::default(),
+            config: sim_config.network.clone(),
+            lost_message_count: 0,
+            ctx,
+        }
+    }
+
+    pub fn register_component(&mut self, id: u32, name: &str) {
+        self.names.insert(id, name.to_string());
+    }
+
+    fn name(&self, id: u32) -> &str {
+        self.names.get(&id).map_or("", |name| name.as_str())
+    }
+
+    /// End of the partition between components which is active at the time
+    fn partition_end(&self, src: u32, dst: u32, time: f64) -> Option<f64> {
+        let (src, dst) = (self.name(src), self.name(dst));
+        self.config.partitions.iter()
+            .filter(|partition| partition.start_time <= time && time < partition.start_time + partition.duration)
+            .filter(|partition| {
+                let in_group = |name: &str| any_matches(&partition.components, name);
+                let in_peers = |name: &str| if partition.peers.is_empty() {
+                    !in_group(name)
+                } else {
+                    any_matches(&partition.peers, name)
+                };
+                (in_group(src) && in_peers(dst)) || (in_group(dst) && in_peers(src))
+            })
+            .map(|partition| partition.start_time + partition.duration)
+            .reduce(f64::max)
+    }
+
+    /// Whether messages between components are lost due to an active partition
+    pub fn is_partitioned(&self, src: u32, dst: u32) -> bool {
+        self.partition_end(src, dst, self.ctx.time()).is_some()
+    }
+
+    fn find_link(&self, src: u32, dst: u32) -> Option<&LinkConfig> {
+        let (src, dst) = (self.name(src), self.name(dst));
+        self.config.links.iter()
+            .find(|link| link.from.as_ref().is_none_or(|from| name_matches(from, src)) &&
+                link.to.as_ref().is_none_or(|to| name_matches(to, dst)))
+    }
+
+    fn sample_latency(&self, distribution: &LatencyDistribution) -> f64 {
+        match distribution {
+            LatencyDistribution::Constant { value } => *value,
+            LatencyDistribution::Uniform { min, max } => min + (max - min) * self.ctx.rand(),
+            LatencyDistribution::Exponential { mean } => -mean * (1.0 - self.ctx.rand()).ln(),
+        }
+    }
+
+    /// Latency and jitter of the link between components
+    fn link_delay(&self, link: &LinkConfig) -> f64 {
+        let mut delay = 0.0;
+        if link.latency.is_some() {
+            delay += self.sample_latency(link.latency.as_ref().unwrap());
+        }
+        if link.jitter > 0.0 {
+            delay += link.jitter * self.ctx.rand();
+        }
+        delay
+    }
+
+    /// Send the event from the component of the context to dst through the network: it's delivered
+    /// after the delay plus the latency of the link, or lost (by the link or the partition
+    /// active at the sending or at the delivery)
+    pub fn send<T: EventData>(&mut self, ctx: &SimulationContext, data: T, dst: u32, delay: f64) {
+        let src = ctx.id();
+        if src == dst {
+            ctx.emit(data, dst, delay);
+            return;
+        }
+        if self.is_partitioned(src, dst) {
+            self.lost_message_count += 1;
+            return;
+        }
+
+        let mut delay = delay;
+        let link = self.find_link(src, dst);
+        if link.is_some() {
+            let link = link.unwrap();
+            if link.loss_probability > 0.0 && self.ctx.rand() < link.loss_probability {
+                self.lost_message_count += 1;
+                return;
+            }
+            delay += self.link_delay(link);
+        }
+        if self.partition_end(src, dst, self.ctx.time() + delay).is_some() {
+            self.lost_message_count += 1;
+            return;
+        }
+        ctx.emit(data, dst, delay);
+    }
+
+    /// Send the event which is retried by the sender until it's delivered (binding of pods to nodes):
+    /// it isn't lost by the link, the partition delays it until the partition ends
+    pub fn send_reliable<T: EventData>(&mut self, ctx: &SimulationContext, data: T, dst: u32, delay: f64) {
+        let src = ctx.id();
+        let mut delivery_time = self.ctx.time() + delay;
+        if src != dst {
+            let link = self.find_link(src, dst);
+            if link.is_some() {
+                delivery_time += self.link_delay(link.unwrap());
+            }
+            while let Some(end_time) = self.partition_end(src, dst, delivery_time) {
+                delivery_time = end_time;
+            }
+        }
+        ctx.emit(data, dst, delivery_time - self.ctx.time());
+    }
+}
@@ -1,18 +1,33 @@
 //! Representation of the k8s node
 
 use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 use dslab_core::context::SimulationContext;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 use dslab_core::{cast, Event, EventHandler};
 use serde::Serialize;
 use crate::api_server::APIServer;
-use crate::events::assigning::{PodAssigningRequest, PodMigrationRequest, PodMigrationSucceeded, PodPlacementFailed, PodPlacementRequest, PodPlacementSucceeded};
-use crate::events::node::UpdatePodsResources;
-use crate::events::pod::PodRequestAndLimitsChange;
-use crate::pod::Pod;
+use crate::events::assigning::{PodAssigningRequest, PodMigrationRequest, PodPlacementFailed, PodPlacementRequest, PodPlacementSucceeded};
+use crate::events::node::{ContainerExit, ContainerStart, ContainerStop, NodeHeartbeat, NoExecuteTaintEviction, SendHeartbeat,
+                          UpdatePodsResources};
+use crate::events::pod::{PodOOMKilled, PodRequestAndLimitsChange, PodRestarted, PodStarted, PodSucceeded,
+                         PodTerminated};
+use crate::logger::Logger;
+use crate::network::NetworkModel;
+use crate::pod::{Pod, PodStatus, QoSClass};
 use crate::simulation_config::SimulationConfig;
+use crate::taints::{Taint, TaintEffect, toleration_time};
+
+/// Well-known label with the name of the node (set for every node)
+pub const LABEL_HOSTNAME: &str = "kubernetes.io/hostname";
+/// Well-known label with the zone of the node
+pub const LABEL_ZONE: &str = "topology.kubernetes.io/zone";
+/// Label with the name of the node group of the cluster autoscaler (set for nodes of groups)
+pub const LABEL_NODE_GROUP: &str = "cluster-autoscaler/node-group";
+/// Label with the capacity type (spot or on-demand) of nodes of node groups
+pub const LABEL_CAPACITY_TYPE: &str = "node.kubernetes.io/capacity-type";
 
 /// Node state (for imitation crash of the node)
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -30,6 +45,72 @@ impl Display for NodeState {
     }
 }
 
+/// Kubelet-style ranking of pods for the node-pressure eviction (the first pod is evicted first):
+/// by QoS class (BestEffort, Burstable, Guaranteed), then pods using more memory than requested,
+/// then by priority, then by memory usage above the request.
+fn eviction_order(first: &Pod, second: &Pod) -> Ordering {
+    let usage_above_request = |pod: &Pod| pod.memory - pod.requested_memory;
+    second.qos_class().cmp(&first.qos_class())
+        .then((usage_above_request(second) > 0.0).cmp(&(usage_above_request(first) > 0.0)))
+        .then(first.priority_weight.cmp(&second.priority_weight))
+        .then(usage_above_request(second).total_cmp(&usage_above_request(first)))
+        .then(first.id.cmp(&second.id))
+}
+
+/// Minimal cpu shares of a pod (for pods without cpu requests)
+const MIN_CPU_SHARES: f64 = 0.002;
+
+/// CFS-like sharing of the node's cpu, demands are (wanted cpu capped by the limit, requested cpu) of pods.
+/// Pods which want less than requested get what they want, the rest of cpu (including cpu requested,
+/// but unused by them) is split between other pods in proportion to their requests (cpu shares).
+/// Pods whose demands are below their shares get their demands, the leftover is split again between others.
+fn share_cpu(mut capacity: f64, demands: &BTreeMap<u64, (f64, f64)>) -> BTreeMap<u64, f64> {
+    let mut granted = BTreeMap::default();
+    let mut contending = Vec::default();
+    for (pod_id, (wanted, requested)) in demands.iter() {
+        if wanted <= requested {
+            granted.insert(*pod_id, *wanted);
+            capacity -= wanted;
+        } else {
+            contending.push(*pod_id);
+        }
+    }
+
+    let shares = |pod_id: &u64| demands[pod_id].1.max(MIN_CPU_SHARES);
+    while !contending.is_empty() {
+        let total_shares: f64 = contending.iter().map(shares).sum();
+        let fair_share = |pod_id: &u64| capacity.max(0.0) * shares(pod_id) / total_shares;
+        let (satisfied, throttled): (Vec<u64>, Vec<u64>) = contending.into_iter()
+            .partition(|pod_id| demands[pod_id].0 <= fair_share(pod_id));
+        if satisfied.is_empty() {
+            for pod_id in throttled {
+                granted.insert(pod_id, fair_share(&pod_id));
+            }
+            break;
+        }
+        for pod_id in satisfied {
+            capacity -= demands[&pod_id].0;
+            granted.insert(pod_id, demands[&pod_id].0);
+        }
+        contending = throttled;
+    }
+    granted
+}
+
+/// Shares of resources of the node (averages of shares of CPU and memory) integrated over time
+/// since the last cost accounting, in share-seconds
+#[derive(Clone, Debug, Default)]
+pub struct ShareIntegrals {
+    /// Time up to which shares are integrated.
+    pub time: f64,
+    /// Resources requested by pods.
+    pub requested: f64,
+    /// Resources allocated to pods, but not used by them.
+    pub wasted: f64,
+    /// Resources requested by pods of deployments.
+    pub deployments: BTreeMap<u64, f64>,
+}
+
 pub struct Node {
     pub id: u32,
     pub cpu_total: f32,
@@ -39,11 +120,24 @@ pub struct Node {
     pub cpu_used: f32,
     pub memory_used: f64,
     pub state: NodeState,
+    pub labels: BTreeMap<String, String>,
+    pub taints: Vec<Taint>,
+    /// Price of the node per hour.
+    pub price: f64,
     pub pods: HashMap<u64, Pod>,
+    /// Removed pods which still hold their requests until they are stopped.
+    pub terminating_pods: Vec<Pod>,
+    /// Pulled images (and images being pulled) with time when they are available.
+    pub image_cache: BTreeMap<String, f64>,
+    share_integrals: ShareIntegrals,
 
     pub memory_overuse_count: u64,
+    /// Pods evicted due to memory pressure for every QoS class
+    pub evictions_per_qos: BTreeMap<QoSClass, u64>,
 
     pub api_server: Rc<RefCell<APIServer>>,
+    network: Rc<RefCell<NetworkModel>>,
+    logger: Rc<RefCell<Box<dyn Logger>>>,
     ctx: SimulationContext,
     sim_config: Rc<SimulationConfig>,
 }
@@ -53,11 +147,18 @@ impl Node {
         cpu_total: f32,
         memory_total: f64,
         state: NodeState,
+        mut labels: BTreeMap<String, String>,
         api_server: Rc<RefCell<APIServer>>,
+        network: Rc<RefCell<NetworkModel>>,
+        logger: Rc<RefCell<Box<dyn Logger>>>,
         ctx: SimulationContext,
         sim_config: Rc<SimulationConfig>,
     ) -> Self {
         ctx.emit(UpdatePodsResources{}, ctx.id(), sim_config.update_pods_resources_period);
+        if sim_config.node_lifecycle.is_some() {
+            ctx.emit(SendHeartbeat {}, ctx.id(), 0.0);
+        }
+        labels.insert(LABEL_HOSTNAME.to_string(), ctx.name().to_string());
         Self {
             id: ctx.id(),
             cpu_total,
@@ -67,20 +168,29 @@ impl Node {
             cpu_used: 0.0,
             memory_used: 0.0,
             state,
+            labels,
+            taints: Vec::default(),
+            price: 0.0,
             pods: HashMap::new(),
+            terminating_pods: Vec::default(),
+            image_cache: BTreeMap::default(),
+            share_integrals: ShareIntegrals::default(),
             memory_overuse_count: 0,
+            evictions_per_qos: BTreeMap::default(),
             api_server,
+            network,
+            logger,
             ctx,
             sim_config
         }
     }
 
     pub fn get_free_cpu(&self) -> f32 {
-        (self.cpu_total as f32) - self.cpu_allocated
+        self.cpu_total - self.cpu_allocated
     }
 
     pub fn get_free_memory(&self) -> f64 {
-        (self.memory_total as f64) - self.memory_allocated
+        self.memory_total - self.memory_allocated
     }
 
     pub fn get_cpu_utilization(&self) -> f64 {
@@ -88,10 +198,39 @@ impl Node {
     }
 
     pub fn get_memory_utilization(&self) -> f64 {
-        self.get_free_memory() / (self.memory_total as f64)
+        self.get_free_memory() / self.memory_total
+    }
+
+    /// Add current shares of resources to integrals since their last update (called before every change
+    /// of pods and their resources)
+    fn integrate_shares(&mut self) {
+        let duration = self.ctx.time() - self.share_integrals.time;
+        self.share_integrals.time = self.ctx.time();
+        if duration <= 0.0 {
+            return;
+        }
+        for pod in self.pods.values() {
+            let share = (pod.requested_cpu as f64 / self.cpu_total as f64 +
+                pod.requested_memory / self.memory_total) / 2.0;
+            self.share_integrals.requested += share * duration;
+            if pod.deployment_id.is_some() {
+                *self.share_integrals.deployments.entry(pod.deployment_id.unwrap()).or_default() += share * duration;
+            }
+        }
+        let wasted_share = ((self.cpu_allocated - self.cpu_used) as f64 / self.cpu_total as f64 +
+            (self.memory_allocated - self.memory_used) / self.memory_total) / 2.0;
+        self.share_integrals.wasted += wasted_share.max(0.0) * duration;
+    }
+
+    /// Integrals of shares of resources since the last call (for the cost accounting)
+    pub fn take_share_integrals(&mut self) -> ShareIntegrals {
+        self.integrate_shares();
+        let time = self.share_integrals.time;
+        std::mem::replace(&mut self.share_integrals, ShareIntegrals { time, ..ShareIntegrals::default() })
     }
 
     pub fn add_pod(&mut self, mut pod: Pod) -> Option<Pod> {
+        self.integrate_shares();
         pod.start_time = self.ctx.time();
 
         let mut cnt_replicas: u64 = 1;
@@ -111,31 +250,46 @@ impl Node {
             return Some(pod);
         }
 
-        pod.cpu = pod.get_wanted_cpu(self.ctx.time(), cnt_replicas + 1)
-            .min(pod.limit_cpu as f64)
-            .min(self.get_free_cpu() as f64) as f32;
-        pod.memory = wanted_memory;
-
+        // the container is created (after the image is pulled) before the pod starts to work
+        let image_available_time = match &pod.image {
+            Some(image) => *self.image_cache.entry(image.clone())
+                .or_insert(self.ctx.time() + self.sim_config.image_pull_duration(image)),
+            None => self.ctx.time(),
+        };
+        let start_delay = (image_available_time - self.ctx.time()).max(0.0) + self.sim_config.pod_start_duration;
+        pod.status = PodStatus::ContainerCreating;
+        pod.container_start_time = Some(self.ctx.time() + start_delay);
+        self.cpu_allocated += pod.requested_cpu;
+        self.memory_allocated += pod.requested_memory;
+        self.ctx.emit(ContainerStart { pod_id: pod.id }, self.id, start_delay);
 
-        self.cpu_used += pod.cpu;
-        self.memory_used += pod.memory;
-        self.cpu_allocated += pod.cpu.max(pod.requested_cpu);
-        self.memory_allocated += pod.memory.max(pod.requested_memory);
-
-        if pod.deployment_id.is_some() {
-            self.api_server.borrow_mut()
-                .deployments_start_time.insert(pod.deployment_id.unwrap(), pod.start_time);
-        }
         self.pods.insert(pod.id, pod);
         None
     }
 
+    /// Removes the pod from the node, its requests are held until it's stopped (for pod_stop_duration).
+    /// Returns the pod to be scheduled again.
     pub fn remove_pod(&mut self, pod_id: u64) -> Option<Pod> {
-        let pod = self.pods.get(&pod_id);
-        if pod.is_none() {
+        let mut pod = self.take_pod(pod_id)?;
+        if self.sim_config.pod_stop_duration > 0.0 {
+            let mut terminating_pod = pod.clone();
+            terminating_pod.status = PodStatus::Terminating;
+            self.cpu_allocated += terminating_pod.requested_cpu;
+            self.memory_allocated += terminating_pod.requested_memory;
+            self.terminating_pods.push(terminating_pod);
+            self.ctx.emit(ContainerStop { pod_id }, self.id, self.sim_config.pod_stop_duration);
+        }
+        pod.status = PodStatus::Pending;
+        Some(pod)
+    }
+
+    /// Removes the pod and releases its resources at once.
+    fn take_pod(&mut self, pod_id: u64) -> Option<Pod> {
+        if !self.pods.contains_key(&pod_id) {
             return None;
         }
-        let pod = pod.unwrap();
+        self.integrate_shares();
+        let pod = self.pods.get(&pod_id).unwrap();
 
         self.cpu_used -= pod.cpu;
         self.memory_used -= pod.memory;
@@ -145,6 +299,8 @@ impl Node {
         let mut pod = self.pods.remove(&pod_id).unwrap();
         pod.cpu = 0.0;
         pod.memory = 0.0;
+        pod.throttled_cpu = 0.0;
+        pod.container_start_time = None;
         Some(pod)
     }
 
@@ -154,51 +310,173 @@ impl Node {
     }
 
     fn update_pods_resources(&mut self) {
-        let mut pods_to_evict = Vec::default();
+        self.integrate_shares();
+        let mut pods_to_kill = Vec::default();
+        let mut cpu_demands = BTreeMap::<u64, (f64, f64)>::default();
+        let mut wanted_cpus = BTreeMap::<u64, f64>::default();
         for (_, pod) in self.pods.iter_mut() {
+            // the container is being created or waits for the restart, it doesn't use resources
+            if pod.container_start_time.is_some() {
+                continue;
+            }
+
             let mut cnt_replicas: u64 = 1;
             if pod.deployment_id.is_some() {
                 cnt_replicas = self.api_server.borrow()
-                    .get_real_cnt_replicas(pod.deployment_id.unwrap());
+                    .get_real_cnt_replicas(pod.deployment_id.unwrap())
+                    .max(1);
             }
 
-            let wanted_memory = pod.get_wanted_memory(self.ctx.time(), cnt_replicas)
-                .min(pod.limit_memory);
+            let wanted_memory = pod.get_wanted_memory(self.ctx.time(), cnt_replicas);
+            if wanted_memory > pod.limit_memory {
+                pods_to_kill.push(pod.id);
+                continue;
+            }
             if wanted_memory > pod.requested_memory {
                 self.memory_overuse_count += 1;
             }
-            let free_memory = (self.memory_total as f64) - self.memory_allocated;
-            if wanted_memory - pod.memory.max(pod.requested_memory) > free_memory {
-                pods_to_evict.push(pod.id);
-                continue;
-            } else {
-                self.memory_allocated = self.memory_allocated - pod.memory.max(pod.requested_memory) + wanted_memory.max(pod.requested_memory);
-                self.memory_used = self.memory_used - pod.memory + wanted_memory;
-                pod.memory = wanted_memory;
-            }
+            self.memory_allocated = self.memory_allocated - pod.memory.max(pod.requested_memory) + wanted_memory.max(pod.requested_memory);
+            self.memory_used = self.memory_used - pod.memory + wanted_memory;
+            pod.memory = wanted_memory;
+
+            let wanted_cpu = pod.get_wanted_cpu(self.ctx.time(), cnt_replicas);
+            cpu_demands.insert(pod.id, (wanted_cpu.min(pod.limit_cpu as f64), pod.requested_cpu as f64));
+            wanted_cpus.insert(pod.id, wanted_cpu);
+        }
 
-            let wanted_cpu = pod.get_wanted_cpu(self.ctx.time(), cnt_replicas)
-                .min(pod.limit_cpu as f64);
-            let free_cpu = (self.cpu_total as f32) - self.cpu_allocated;
-            let new_cpu = pod.cpu + ((wanted_cpu as f32) - pod.cpu).min(free_cpu);
+        pods_to_kill.sort();
+        for pod_id in pods_to_kill {
+            self.oom_kill_pod(pod_id);
+        }
+
+        // requests of containers waiting for the restart stay reserved
+        let reserved_cpu: f64 = self.pods.values()
+            .filter(|pod| !cpu_demands.contains_key(&pod.id))
+            .map(|pod| pod.requested_cpu as f64)
+            .sum();
+        for (pod_id, granted_cpu) in share_cpu(self.cpu_total as f64 - reserved_cpu, &cpu_demands) {
+            let pod = self.pods.get_mut(&pod_id).unwrap();
+            let new_cpu = granted_cpu as f32;
             self.cpu_allocated = self.cpu_allocated - pod.cpu.max(pod.requested_cpu) + new_cpu.max(pod.requested_cpu);
             self.cpu_used = self.cpu_used - pod.cpu + new_cpu;
             pod.cpu = new_cpu;
+            pod.throttled_cpu = (wanted_cpus[&pod_id] - granted_cpu).max(0.0) as f32;
         }
 
-        for pod_id in pods_to_evict {
+        while self.memory_allocated > self.memory_total && !self.pods.is_empty() {
+            let pod_id = self.pods.values().min_by(|first, second| eviction_order(first, second))
+                .unwrap().id;
+            let qos_class = self.pods.get(&pod_id).unwrap().qos_class();
+            *self.evictions_per_qos.entry(qos_class).or_insert(0) += 1;
+            self.logger.borrow_mut().log_info(&self.ctx,
+                format!("pod {} ({} QoS) is evicted due to memory pressure", pod_id, qos_class));
             self.evict_pod(pod_id);
         }
     }
 
+    /// Kills the container of the pod which exceeded its memory limit, the container is restarted
+    /// in place after the CrashLoopBackOff delay. The pod keeps its requests on the node meanwhile.
+    fn oom_kill_pod(&mut self, pod_id: u64) {
+        let mut pod = self.take_pod(pod_id).unwrap();
+        // the backoff is reset if the container worked long enough since its last (re)start
+        if self.ctx.time() - pod.container_run_start_time >= self.sim_config.container_backoff_reset {
+            pod.restart_count = 0;
+        }
+        let restart_delay = (self.sim_config.container_restart_initial_backoff * 2f64.powi(pod.restart_count as i32))
+            .min(self.sim_config.container_restart_max_backoff);
+        pod.oom_kill_count += 1;
+        pod.container_start_time = Some(self.ctx.time() + restart_delay);
+        self.cpu_allocated += pod.requested_cpu;
+        self.memory_allocated += pod.requested_memory;
+        self.pods.insert(pod_id, pod);
+
+        self.logger.borrow_mut().log_info(&self.ctx,
+            format!("pod {} is OOM killed, restart in {} seconds", pod_id, restart_delay));
+        self.network.borrow_mut().send_reliable(&self.ctx, PodOOMKilled { pod_id, node_id: self.id },
+                                                self.api_server.borrow().id, self.sim_config.message_delay);
+        self.ctx.emit(ContainerStart { pod_id }, self.id, restart_delay);
+    }
+
+    /// Starts the created container (the pod begins to work) or restarts the OOM killed one.
+    fn start_container(&mut self, pod_id: u64) {
+        let pod = self.pods.get_mut(&pod_id);
+        if pod.is_none() {
+            return;
+        }
+        let pod = pod.unwrap();
+        // the pod could be moved to the node again after this start was planned
+        if pod.container_start_time.is_none_or(|time| time > self.ctx.time()) {
+            return;
+        }
+        pod.container_start_time = None;
+        pod.start_time = self.ctx.time();
+        pod.container_run_start_time = self.ctx.time();
+
+        if pod.status == PodStatus::ContainerCreating {
+            pod.status = PodStatus::Running;
+            if pod.deployment_id.is_some() {
+                let deployment_id = pod.deployment_id.unwrap();
+                let deployment_start_time = self.api_server.borrow().get_deployment_start_time(deployment_id);
+                if deployment_start_time >= 0. {
+                    pod.start_time = deployment_start_time;
+                } else {
+                    self.api_server.borrow_mut().deployments_start_time.insert(deployment_id, pod.start_time);
+                }
+            }
+            self.network.borrow_mut().send_reliable(&self.ctx, PodStarted { pod_id, node_id: self.id },
+                                                    self.api_server.borrow().id, self.sim_config.message_delay);
+        } else {
+            pod.restart_count += 1;
+            self.network.borrow_mut().send(&self.ctx, PodRestarted { pod_id, node_id: self.id },
+                                           self.api_server.borrow().id, self.sim_config.message_delay);
+        }
+
+        if pod.duration.is_some() {
+            self.ctx.emit(ContainerExit { pod_id }, self.id, pod.duration.unwrap());
+        }
+    }
+
+    /// Complete the pod which container has worked for its duration (resources are released at once)
+    fn exit_container(&mut self, pod_id: u64) {
+        let pod = self.pods.get(&pod_id);
+        if pod.is_none() {
+            return;
+        }
+        let pod = pod.unwrap();
+        // the container could be restarted (or the pod moved to the node again) after this exit was planned
+        if pod.container_start_time.is_some() || pod.start_time + pod.duration.unwrap() > self.ctx.time() {
+            return;
+        }
+
+        let mut pod = self.take_pod(pod_id).unwrap();
+        pod.status = PodStatus::Succeeded;
+        self.logger.borrow_mut().log_info(&self.ctx, format!("pod {} is {}", pod_id, pod.status));
+        self.network.borrow_mut().send_reliable(&self.ctx, PodSucceeded { pod_id, node_id: self.id },
+                                                self.api_server.borrow().id, self.sim_config.message_delay);
+    }
+
+    /// Releases requests of the stopped pod.
+    fn stop_container(&mut self, pod_id: u64) {
+        let position = self.terminating_pods.iter().position(|pod| pod.id == pod_id);
+        if position.is_none() {
+            return;
+        }
+        self.integrate_shares();
+        let pod = self.terminating_pods.remove(position.unwrap());
+        self.cpu_allocated -= pod.requested_cpu;
+        self.memory_allocated -= pod.requested_memory;
+        self.network.borrow_mut().send_reliable(&self.ctx, PodTerminated { pod_id, node_id: self.id },
+                                                self.api_server.borrow().id, self.sim_config.message_delay);
+    }
+
     fn is_under_pressure(&self, memory_allocated: f64) -> bool {
         memory_allocated >= self.memory_total * self.sim_config.memory_pressure_threshold
     }
 
     fn evict_pod(&mut self, pod_id: u64) {
         let pod = self.remove_pod(pod_id).unwrap();
-        self.ctx.emit(PodMigrationRequest { pod, source_node_id: self.id },
-                      self.api_server.borrow().id, self.sim_config.message_delay);
+        self.network.borrow_mut().send_reliable(&self.ctx, PodMigrationRequest { pod, source_node_id: self.id },
+                                                self.api_server.borrow().id, self.sim_config.message_delay);
     }
 }
 
@@ -209,20 +487,24 @@ impl EventHandler for Node {
                 let pod_id = pod.id;
                 let add_pod_res = self.add_pod(pod);
                 if add_pod_res.is_none() {
-                    self.ctx.emit(PodPlacementSucceeded { pod_id, node_id }, self.api_server.borrow().id,
-                        self.sim_config.message_delay);
+                    self.network.borrow_mut().send_reliable(&self.ctx, PodPlacementSucceeded { pod_id, node_id },
+                        self.api_server.borrow().id, self.sim_config.message_delay);
                 } else {
-                    self.ctx.emit(PodPlacementFailed { pod: add_pod_res.unwrap(), node_id },
+                    self.network.borrow_mut().send_reliable(&self.ctx,
+                        PodPlacementFailed { pod: add_pod_res.unwrap(), node_id },
                         self.api_server.borrow().id, self.sim_config.message_delay);
                 }
             }
             UpdatePodsResources {} => {
-                self.update_pods_resources();
+                // pods of the failed node don't work (until the node is recovered or they are evicted)
+                if self.state == NodeState::Working {
+                    self.update_pods_resources();
+                }
                 self.ctx.emit(UpdatePodsResources{}, self.id, self.sim_config.update_pods_resources_period);
             }
             PodRequestAndLimitsChange { pod_id, new_requested_cpu, new_limit_cpu,
                 new_requested_memory, new_limit_memory } => {
-                let mut pod = self.remove_pod(pod_id);
+                let pod = self.remove_pod(pod_id);
                 if pod.is_none() {
                     return;
                 }
@@ -233,9 +515,41 @@ impl EventHandler for Node {
                 pod.requested_memory = new_requested_memory;
                 pod.limit_memory = new_limit_memory;
 
-                self.ctx.emit(PodAssigningRequest {pod}, self.api_server.borrow().id,
+                self.network.borrow_mut().send_reliable(&self.ctx, PodAssigningRequest {pod}, self.api_server.borrow().id,
                     self.sim_config.message_delay);
             }
+            SendHeartbeat {} => {
+                if self.state == NodeState::Working {
+                    self.network.borrow_mut().send(&self.ctx, NodeHeartbeat { node_id: self.id },
+                                                   self.api_server.borrow().id, self.sim_config.message_delay);
+                }
+                self.ctx.emit(SendHeartbeat {}, self.id,
+                              self.sim_config.node_lifecycle.as_ref().unwrap().heartbeat_interval);
+            }
+            ContainerStart { pod_id } => {
+                self.start_container(pod_id);
+            }
+            ContainerStop { pod_id } => {
+                self.stop_container(pod_id);
+            }
+            ContainerExit { pod_id } => {
+                self.exit_container(pod_id);
+            }
+            NoExecuteTaintEviction { pod_id } => {
+                let pod = self.pods.get(&pod_id);
+                if pod.is_none() {
+                    return;
+                }
+                let tolerations = &pod.unwrap().constraints.tolerations;
+                // the taint could be removed while the pod was tolerating it
+                let must_be_evicted = self.taints.iter()
+                    .filter(|taint| taint.effect == TaintEffect::NoExecute)
+                    .any(|taint| toleration_time(tolerations, taint)
+                        .is_none_or(|time| time.is_finite()));
+                if must_be_evicted {
+                    self.evict_pod(pod_id);
+                }
+            }
         })
     }
 }
\ No newline at end of file
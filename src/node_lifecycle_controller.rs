@@ -0,0 +1,111 @@
+//! Node lifecycle controller: detects failed nodes by missed heartbeats, marks them NotReady
+//! with the unreachable NoExecute taint, which evicts their pods after their tolerations
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
+use crate::api_server::APIServer;
+use crate::events::node::NodeLifecycleMonitor;
+use crate::simulation_config::{NodeLifecycleConfig, SimulationConfig};
+use crate::simulation_metrics::NodeOutage;
+use crate::taints::{Taint, TAINT_UNREACHABLE, TaintEffect, toleration_time};
+
+pub struct NodeLifecycleController {
+    pub id: u32,
+    /// NotReady nodes with the time since which they are NotReady.
+    pub not_ready_nodes: BTreeMap<u32, f64>,
+    /// NotReady nodes with counts of pods evicted by the unreachable taint.
+    evicted_pod_counts: BTreeMap<u32, u64>,
+    /// NotReady nodes which outages are already recorded.
+    evicted_node_ids: BTreeSet<u32>,
+    config: NodeLifecycleConfig,
+    api_server: Rc<RefCell<APIServer>>,
+
+    ctx: SimulationContext,
+}
+
+impl NodeLifecycleController {
+    pub fn new(api_server: Rc<RefCell<APIServer>>, ctx: SimulationContext,
+               sim_config: Rc<SimulationConfig>) -> Self {
+        Self {
+            id: ctx.id(),
+            not_ready_nodes: BTreeMap::default(),
+            evicted_pod_counts: BTreeMap::default(),
+            evicted_node_ids: BTreeSet::default(),
+            config: sim_config.node_lifecycle.clone().unwrap_or_default(),
+            api_server,
+            ctx,
+        }
+    }
+
+    /// Check heartbeats of nodes: mark NotReady nodes which missed the grace period,
+    /// record outages of nodes NotReady longer than the eviction timeout and return nodes with fresh heartbeats
+    fn monitor_nodes(&mut self) {
+        let now = self.ctx.time();
+        let node_ids: Vec<u32> = {
+            let api_server = self.api_server.borrow();
+            api_server.working_nodes.keys().chain(api_server.failed_nodes.keys()).cloned().collect()
+        };
+
+        for node_id in node_ids {
+            let last_heartbeat = *self.api_server.borrow().node_heartbeats.get(&node_id).unwrap_or(&now);
+            let not_ready_time = self.not_ready_nodes.get(&node_id).cloned();
+            if not_ready_time.is_none() {
+                if now - last_heartbeat > self.config.grace_period {
+                    self.mark_not_ready(node_id);
+                }
+            } else if last_heartbeat > not_ready_time.unwrap() {
+                self.mark_ready(node_id);
+            } else if !self.evicted_node_ids.contains(&node_id) &&
+                now - not_ready_time.unwrap() >= self.config.pod_eviction_timeout {
+                self.record_outage(node_id, last_heartbeat, not_ready_time.unwrap());
+            }
+        }
+    }
+
+    /// Taint the node with the unreachable NoExecute taint, pods without its tolerations
+    /// tolerate it for the eviction timeout
+    fn mark_not_ready(&mut self, node_id: u32) {
+        self.not_ready_nodes.insert(node_id, self.ctx.time());
+        let taint = Taint::new(TAINT_UNREACHABLE, "", TaintEffect::NoExecute);
+        let mut api_server = self.api_server.borrow_mut();
+        let evicted_pod_count = api_server.get_node(node_id).map_or(0, |node| node.borrow().pods.values()
+            .filter(|pod| toleration_time(&pod.constraints.tolerations, &taint).is_none_or(|time| time.is_finite()))
+            .count() as u64);
+        self.evicted_pod_counts.insert(node_id, evicted_pod_count);
+        api_server.add_node_taint_with_default_toleration(node_id, taint, self.config.pod_eviction_timeout);
+    }
+
+    fn mark_ready(&mut self, node_id: u32) {
+        self.not_ready_nodes.remove(&node_id);
+        self.evicted_pod_counts.remove(&node_id);
+        self.evicted_node_ids.remove(&node_id);
+        self.api_server.borrow_mut().remove_node_taint(node_id, TAINT_UNREACHABLE, TaintEffect::NoExecute);
+    }
+
+    /// Pods of the node without tolerations are evicted by the taint after the eviction timeout
+    fn record_outage(&mut self, node_id: u32, last_heartbeat: f64, not_ready_time: f64) {
+        self.evicted_node_ids.insert(node_id);
+        let mut api_server = self.api_server.borrow_mut();
+        let failure_time = api_server.node_failure_times.get(&node_id).cloned().unwrap_or(last_heartbeat);
+        api_server.add_node_outage(NodeOutage {
+            node_id,
+            failure_time,
+            not_ready_time,
+            eviction_time: self.ctx.time(),
+            evicted_pod_count: self.evicted_pod_counts.get(&node_id).cloned().unwrap_or(0),
+        });
+    }
+}
+
+impl EventHandler for NodeLifecycleController {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            NodeLifecycleMonitor {} => {
+                self.monitor_nodes();
+                self.ctx.emit(NodeLifecycleMonitor {}, self.id, self.config.monitor_period);
+            }
+        })
+    }
+}
@@ -2,16 +2,23 @@
 
 use std::fmt::{Display, Formatter};
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use serde::Serialize;
-use crate::deployment::Deployment;
+use crate::affinity::{NodeAffinity, PodAffinity, TopologySpreadConstraint};
 use crate::load_model::LoadModel;
+use crate::priority_class::PreemptionPolicy;
+use crate::taints::Toleration;
 
 
 /// Pod status
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum PodStatus {
     Pending,
+    /// The pod is bound to the node, its container is being created (it holds requests, but doesn't work).
+    ContainerCreating,
     Running,
+    /// The pod is being stopped on the node (it holds requests, but doesn't work).
+    Terminating,
     Succeeded,
     Failed,
     Unknown,
@@ -21,7 +28,9 @@ impl Display for PodStatus {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
             PodStatus::Pending => write!(f, "pending"),
+            PodStatus::ContainerCreating => write!(f, "container creating"),
             PodStatus::Running => write!(f, "running"),
+            PodStatus::Terminating => write!(f, "terminating"),
             PodStatus::Succeeded => write!(f, "succeeded"),
             PodStatus::Failed => write!(f, "failed"),
             PodStatus::Unknown => write!(f, "unknown"),
@@ -29,12 +38,63 @@ impl Display for PodStatus {
     }
 }
 
+/// Quality of service class of the pod, derived from its requests and limits
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum QoSClass {
+    /// Requests are equal to limits for both cpu and memory.
+    Guaranteed,
+    /// At least one request is set, but requests aren't equal to limits.
+    Burstable,
+    /// No requests are set (limits are always set in the simulator and only cap the usage).
+    BestEffort,
+}
+
+impl Display for QoSClass {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            QoSClass::Guaranteed => write!(f, "guaranteed"),
+            QoSClass::Burstable => write!(f, "burstable"),
+            QoSClass::BestEffort => write!(f, "best effort"),
+        }
+    }
+}
+
+/// Placement constraints of the pod (for a scheduler)
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SchedulingConstraints {
+    /// Labels which the node must have.
+    pub node_selector: BTreeMap<String, String>,
+    pub node_affinity: Option<NodeAffinity>,
+    pub tolerations: Vec<Toleration>,
+    /// Pod should run in the same topology domain as pods matched by these terms.
+    pub pod_affinity: Option<PodAffinity>,
+    /// Pod shouldn't run in the same topology domain as pods matched by these terms.
+    pub pod_anti_affinity: Option<PodAffinity>,
+    /// Matched pods (e.g. replicas of the deployment) should be balanced across topology domains.
+    pub topology_spread_constraints: Vec<TopologySpreadConstraint>,
+    /// Whether the pod can preempt pods with lower priority if it doesn't fit anywhere.
+    pub preemption_policy: PreemptionPolicy,
+}
+
+impl SchedulingConstraints {
+    /// Checks node selector and required node affinity of the pod.
+    pub fn matches_node_labels(&self, labels: &BTreeMap<String, String>) -> bool {
+        let selector_matches = self.node_selector.iter()
+            .all(|(key, value)| labels.get(key) == Some(value));
+        let affinity_matches = self.node_affinity.as_ref()
+            .is_none_or(|affinity| affinity.required_matches(labels));
+        selector_matches && affinity_matches
+    }
+}
+
 #[derive(Clone, Serialize)]
 pub struct Pod {
     pub id: u64,
 
     pub cpu: f32,
     pub memory: f64,
+    /// Cpu wanted by the load model, but not granted (because of the limit or the contention on the node).
+    pub throttled_cpu: f32,
     pub cpu_load_model: Box<dyn LoadModel>,
     pub memory_load_model: Box<dyn LoadModel>,
 
@@ -43,14 +103,30 @@ pub struct Pod {
     pub limit_cpu: f32,
     pub limit_memory: f64,
     pub priority_weight: u64,
+    pub labels: BTreeMap<String, String>,
+    pub constraints: SchedulingConstraints,
 
     pub scheduling_attempts: Option<u64>,
     pub scheduling_timestamp: Option<f64>,
+    pub nominated_node_id: Option<u32>,
 
     pub start_time: f64,
     pub status: PodStatus,
+    /// Count of container restarts after OOM kills (since the reset of the restart backoff).
+    pub restart_count: u64,
+    pub oom_kill_count: u64,
+    /// Time of the last start (or restart) of the container.
+    pub container_run_start_time: f64,
+    /// Time when the container starts if it isn't running yet (it's being created)
+    /// or it's OOM killed and waits for the restart in CrashLoopBackOff.
+    pub container_start_time: Option<f64>,
+    /// Container image, it's pulled on the node before the start if the node doesn't have it.
+    pub image: Option<String>,
+    /// Time of the container's work after which the pod succeeds (it works forever if None).
+    pub duration: Option<f64>,
 
     pub deployment_id: Option<u64>,
+    pub job_id: Option<u64>,
 }
 
 impl Pod {
@@ -70,6 +146,7 @@ impl Pod {
             id,
             cpu: 0.0,
             memory: 0.0,
+            throttled_cpu: 0.0,
             cpu_load_model,
             memory_load_model,
             requested_cpu,
@@ -77,13 +154,35 @@ impl Pod {
             limit_cpu,
             limit_memory,
             priority_weight,
+            labels: BTreeMap::default(),
+            constraints: SchedulingConstraints::default(),
             scheduling_attempts: None,
             scheduling_timestamp: None,
+            nominated_node_id: None,
             start_time: 0.0,
             status,
+            restart_count: 0,
+            oom_kill_count: 0,
+            container_run_start_time: 0.0,
+            container_start_time: None,
+            image: None,
+            duration: None,
             deployment_id,
+            job_id: None,
         }
     }
+
+    pub fn qos_class(&self) -> QoSClass {
+        if self.requested_cpu == 0.0 && self.requested_memory == 0.0 {
+            QoSClass::BestEffort
+        } else if self.requested_cpu > 0.0 && self.requested_memory > 0.0 &&
+            self.requested_cpu == self.limit_cpu && self.requested_memory == self.limit_memory {
+            QoSClass::Guaranteed
+        } else {
+            QoSClass::Burstable
+        }
+    }
+
     pub fn get_wanted_cpu(&mut self, time: f64, cnt_replicas: u64) -> f64 {
         self.cpu_load_model.get_resource(time, time - self.start_time, cnt_replicas)
     }
@@ -0,0 +1,29 @@
+//! Priority classes of k8s pods
+
+use serde::{Deserialize, Serialize};
+
+/// Whether the pod can preempt pods with lower priority
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum PreemptionPolicy {
+    #[default]
+    PreemptLowerPriority,
+    Never,
+}
+
+/// Named priority of pods (set by `priority_class_name` in the trace)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriorityClass {
+    /// Priority weight of pods of this class.
+    pub value: u64,
+    #[serde(default)]
+    pub preemption_policy: PreemptionPolicy,
+}
+
+impl PriorityClass {
+    pub fn new(value: u64, preemption_policy: PreemptionPolicy) -> Self {
+        Self {
+            value,
+            preemption_policy,
+        }
+    }
+}
@@ -1,59 +1,245 @@
 use std::cell::RefCell;
-use std::collections::BinaryHeap;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, BTreeMap, BTreeSet};
 use std::rc::Rc;
 use dslab_core::{cast, Event, EventHandler, SimulationContext};
 use crate::api_server::APIServer;
+use crate::cluster_autoscaler_algorithm::PodMoveSimulator;
+use crate::control_plane_outage::OutageState;
 use crate::events::scheduler::{FlushUnschedulableQueue, MoveRequest, PodBackoffRetry, SchedulingCycle};
-use crate::node::{Node, NodeState};
-use crate::scheduler_algorithm::SchedulerAlgorithm;
-use crate::simulation_config::SimulationConfig;
-use std::time::{Duration, Instant};
+use crate::simulation_config::{SchedulingCostModel, SimulationConfig};
+use std::time::Instant;
 use crate::events::assigning::{PodAssigningFailed, PodAssigningSucceeded};
-use crate::events::node::NodeStatusChanged;
 use crate::pod::Pod;
+use crate::events::api_server::PodPreemptionRequest;
+use crate::events::outage::{ComponentOutageEnd, ComponentOutageStart};
+use crate::network::NetworkModel;
+use crate::scheduler_algorithm::{ASSUMED_PODS_KEY, AssumedPods, CycleState, NOMINATED_PODS_KEY, NominatedPods,
+                                 PluginStatus, QueueSortPlugin};
+use crate::scheduler_profile::SchedulerProfile;
 
 const UNSCHEDULABLE_QUEUE_FLUSH_TIMEOUT: f64 = 30.0;
 const POD_MIN_UNSCHEDULABLE_TIMEOUT: f64 = 30.0;
 
+/// Pod in the ActiveQueue, the greatest entry is the pod which is scheduled first according to
+/// the QueueSort plugin (pods which are equal for the plugin are scheduled in FIFO order)
+pub struct QueuedPod {
+    pub pod: Pod,
+    sequence_number: u64,
+    queue_sort: Rc<dyn QueueSortPlugin>,
+}
+
+impl Ord for QueuedPod {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.queue_sort.less(&self.pod, &other.pod) {
+            Ordering::Greater
+        } else if self.queue_sort.less(&other.pod, &self.pod) {
+            Ordering::Less
+        } else {
+            other.sequence_number.cmp(&self.sequence_number)
+        }
+    }
+}
+
+impl PartialOrd for QueuedPod {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for QueuedPod {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedPod {}
+
 pub struct Scheduler {
     pub id: u32,
-    pub active_queue: BinaryHeap<Pod>,
+    pub active_queue: BinaryHeap<QueuedPod>,
+    queued_pod_counter: u64,
     pub unschedulable_queue: Vec<Pod>,
     api_server: Rc<RefCell<APIServer>>,
-    scheduler_algorithm: Box<dyn SchedulerAlgorithm>,
+    profile: SchedulerProfile,
+    assumed_pods: AssumedPods,
+    nominated_pods: NominatedPods,
     scheduling_cycle: i64,
     moving_cycle: i64,
+    filtered_nodes_count: usize,
+    scored_nodes_count: usize,
+    network: Rc<RefCell<NetworkModel>>,
+    pub outage: OutageState,
     ctx: SimulationContext,
     sim_config: Rc<SimulationConfig>,
 }
 
 impl Scheduler {
-    pub fn new(api_server: Rc<RefCell<APIServer>>, scheduler_algorithm: Box<dyn SchedulerAlgorithm>,
+    pub fn new(api_server: Rc<RefCell<APIServer>>, profile: SchedulerProfile, network: Rc<RefCell<NetworkModel>>,
                ctx: SimulationContext, sim_config: Rc<SimulationConfig>) -> Self {
         Self {
             id: ctx.id(),
             active_queue: BinaryHeap::default(),
+            queued_pod_counter: 0,
             unschedulable_queue: Vec::default(),
             api_server,
-            scheduler_algorithm,
+            profile,
+            assumed_pods: AssumedPods::default(),
+            nominated_pods: NominatedPods::default(),
             scheduling_cycle: 0,
             moving_cycle: -1,
+            filtered_nodes_count: 0,
+            scored_nodes_count: 0,
+            network,
+            outage: OutageState::default(),
             ctx,
             sim_config
         }
     }
 
-    /// Add pod to the ActiveQueue
+    /// Add pod to the ActiveQueue (it's scheduled after the restart during the outage)
     pub fn add_pod(&mut self, pod: Pod) {
-        if self.active_queue.is_empty() {
+        self.assumed_pods.borrow_mut().remove(&pod.id);
+        if self.active_queue.is_empty() && !self.outage.is_down {
             self.ctx.emit(SchedulingCycle {}, self.id, 0.0);
         }
-        self.active_queue.push(pod);
+        self.push_to_active_queue(pod);
+    }
+
+    /// Push pod to the ActiveQueue without starting the scheduling cycle
+    fn push_to_active_queue(&mut self, pod: Pod) {
+        self.queued_pod_counter += 1;
+        self.active_queue.push(QueuedPod {
+            pod,
+            sequence_number: self.queued_pod_counter,
+            queue_sort: self.profile.queue_sort(),
+        });
     }
 
-    /// Pop next pod in the ActiveQueue
+    /// Remove the pod from the queues (and forget its assumption and nomination),
+    /// returns false if the pod isn't in the queues
+    pub fn remove_pod(&mut self, pod_id: u64) -> bool {
+        self.assumed_pods.borrow_mut().remove(&pod_id);
+        self.nominated_pods.borrow_mut().remove(&pod_id);
+        let queued_count = self.active_queue.len() + self.unschedulable_queue.len();
+        self.active_queue.retain(|queued| queued.pod.id != pod_id);
+        self.unschedulable_queue.retain(|pod| pod.id != pod_id);
+        self.active_queue.len() + self.unschedulable_queue.len() < queued_count
+    }
+
+    /// Pop next pod in the ActiveQueue (in the order of the QueueSort plugin)
     pub fn get_pod(&mut self) -> Option<Pod> {
-        self.active_queue.pop()
+        self.active_queue.pop().map(|queued| queued.pod)
+    }
+
+    /// Forgets assumed pods which are already placed on nodes (or which nodes are removed)
+    /// and nominations to removed nodes.
+    fn cleanup_assumed_and_nominated_pods(&mut self) {
+        let api_server = self.api_server.borrow();
+        self.assumed_pods.borrow_mut().retain(|pod_id, (node_id, _)| {
+            api_server.working_nodes.get(node_id)
+                .is_some_and(|node| !node.borrow().pods.contains_key(pod_id))
+        });
+        self.nominated_pods.borrow_mut()
+            .retain(|_, (node_id, _)| api_server.working_nodes.contains_key(node_id));
+    }
+
+    /// Runs all extension points of the profile for the pod, returns chosen node
+    /// and the binding delay requested by permit plugins.
+    fn find_node_for_pod(&mut self, state: &mut CycleState, pod: &mut Pod) -> Option<(u32, f64)> {
+        let api_server = self.api_server.borrow();
+        let nodes = &api_server.working_nodes;
+
+        if !self.profile.run_pre_filter_plugins(state, pod, nodes).is_success() {
+            return None;
+        }
+
+        // the nominated node is checked first, it's chosen without scoring if it's feasible
+        let mut filtered_nodes = Vec::<u32>::default();
+        if let Some(nominated_node_id) = pod.nominated_node_id {
+            if let Some(node) = nodes.get(&nominated_node_id) {
+                self.filtered_nodes_count += 1;
+                if self.profile.run_filter_plugins(state, pod, &node.borrow()).is_success() {
+                    filtered_nodes.push(nominated_node_id);
+                }
+            }
+        }
+        let is_nominated_node_feasible = !filtered_nodes.is_empty();
+        let mut node_statuses = BTreeMap::<u32, PluginStatus>::default();
+        if !is_nominated_node_feasible {
+            self.filtered_nodes_count += nodes.len();
+            for (node_id, node) in nodes.iter() {
+                let status = self.profile.run_filter_plugins(state, pod, &node.borrow());
+                if status.is_success() {
+                    filtered_nodes.push(*node_id);
+                } else {
+                    node_statuses.insert(*node_id, status);
+                }
+            }
+        }
+
+        if filtered_nodes.is_empty() {
+            let result = self.profile.run_post_filter_plugins(state, pod, nodes, &node_statuses);
+            if let Some(result) = result {
+                pod.nominated_node_id = result.nominated_node_id;
+                if let Some(node_id) = result.nominated_node_id {
+                    self.nominated_pods.borrow_mut().insert(pod.id, (node_id, pod.clone()));
+                    if !result.victim_ids.is_empty() {
+                        self.network.borrow_mut().send_reliable(&self.ctx, PodPreemptionRequest {
+                            preemptor_id: pod.id,
+                            node_id,
+                            victim_ids: result.victim_ids,
+                        }, api_server.id, self.sim_config.control_plane_message_delay);
+                    }
+                }
+            }
+            return None;
+        }
+
+        let node_id = if is_nominated_node_feasible {
+            filtered_nodes[0]
+        } else {
+            if !self.profile.run_pre_score_plugins(state, pod, nodes, &filtered_nodes).is_success() {
+                return None;
+            }
+            self.scored_nodes_count += filtered_nodes.len();
+            let node_scores = self.profile.run_score_plugins(state, pod, nodes, &filtered_nodes);
+            let mut max_score_ind = 0;
+            for i in 0..filtered_nodes.len() {
+                if node_scores[i] > node_scores[max_score_ind] {
+                    max_score_ind = i;
+                }
+            }
+            filtered_nodes[max_score_ind]
+        };
+
+        if !self.profile.run_reserve_plugins(state, pod, node_id).is_success() {
+            return None;
+        }
+        let binding_delay = match self.profile.run_permit_plugins(state, pod, node_id) {
+            PluginStatus::Success => 0.0,
+            PluginStatus::Wait(delay) => delay,
+            _ => {
+                self.profile.run_unreserve_plugins(state, pod, node_id);
+                return None;
+            }
+        };
+        if !self.profile.run_bind_plugins(state, pod, node_id).is_success() {
+            self.profile.run_unreserve_plugins(state, pod, node_id);
+            return None;
+        }
+        Some((node_id, binding_delay))
+    }
+
+    /// Returns the time of the scheduling algorithm's work on the last pod according to the cost model.
+    fn scheduling_cost(&self, start_of_algorithm_work: Instant) -> f64 {
+        match self.sim_config.scheduling_cost_model {
+            SchedulingCostModel::Deterministic { per_pod_cost, per_node_filter_cost, per_node_score_cost } => {
+                per_pod_cost + self.filtered_nodes_count as f64 * per_node_filter_cost +
+                    self.scored_nodes_count as f64 * per_node_score_cost
+            }
+            SchedulingCostModel::WallClock => start_of_algorithm_work.elapsed().as_secs_f64(),
+        }
     }
 
     pub fn schedule_next_pod(&mut self) {
@@ -71,39 +257,36 @@ impl Scheduler {
                 let mut elapsed_time = self.sim_config.control_plane_message_delay;
 
                 let start_of_algorithm_work = Instant::now();
+                self.filtered_nodes_count = 0;
+                self.scored_nodes_count = 0;
 
-                let filtered_nodes = self.scheduler_algorithm.filter(&pod,
-                                                                     &self.api_server.borrow().working_nodes);
-                if filtered_nodes.is_empty() {
-                    elapsed_time += start_of_algorithm_work.elapsed().as_secs_f64();
-                    if !self.api_server.borrow().working_nodes.is_empty() {
-                        self.ctx.emit(SchedulingCycle {}, self.id, elapsed_time);
-                    }
+                self.cleanup_assumed_and_nominated_pods();
+                let mut state = CycleState::default();
+                state.write(ASSUMED_PODS_KEY, self.assumed_pods.clone());
+                state.write(NOMINATED_PODS_KEY, self.nominated_pods.clone());
+                let scheduling_result = self.find_node_for_pod(&mut state, &mut pod);
+                if scheduling_result.is_none() {
+                    elapsed_time += self.scheduling_cost(start_of_algorithm_work);
+                    // the loop goes on without working nodes too, so that remaining pods become unschedulable
+                    // and are scaled up for by the cluster autoscaler
+                    self.ctx.emit(SchedulingCycle {}, self.id, elapsed_time);
                     elapsed_time += self.sim_config.control_plane_message_delay;
                     self.ctx.emit(PodAssigningFailed { pod, scheduling_cycle: self.scheduling_cycle },
                                   self.id, elapsed_time);
                     return;
                 }
-                let node_scores = self.scheduler_algorithm.score(&pod,
-                                                                 &self.api_server.borrow().working_nodes,
-                                                                 &filtered_nodes);
-                let mut max_score_ind = 0;
-                for i in 0..filtered_nodes.len() {
-                    if node_scores[i] > node_scores[max_score_ind] {
-                        max_score_ind = i;
-                    }
-                }
-                let node_id = filtered_nodes[max_score_ind];
+                let (node_id, binding_delay) = scheduling_result.unwrap();
 
-                elapsed_time += start_of_algorithm_work.elapsed().as_secs_f64() + self.sim_config.message_delay;
-                if !self.api_server.borrow().working_nodes.is_empty() {
-                    self.ctx.emit(SchedulingCycle {}, self.id, elapsed_time);
-                }
+                elapsed_time += self.scheduling_cost(start_of_algorithm_work) + self.sim_config.message_delay;
+                self.ctx.emit(SchedulingCycle {}, self.id, elapsed_time);
                 pod.scheduling_attempts = None;
                 pod.scheduling_timestamp = None;
-                elapsed_time += self.sim_config.control_plane_message_delay;
-                self.ctx.emit(PodAssigningSucceeded { pod, node_id },
-                              self.api_server.borrow().id, elapsed_time);
+                pod.nominated_node_id = None;
+                self.nominated_pods.borrow_mut().remove(&pod.id);
+                self.assumed_pods.borrow_mut().insert(pod.id, (node_id, pod.clone()));
+                elapsed_time += self.sim_config.control_plane_message_delay + binding_delay;
+                self.network.borrow_mut().send_reliable(&self.ctx, PodAssigningSucceeded { pod, node_id },
+                                                        self.api_server.borrow().id, elapsed_time);
             },
         }
     }
@@ -120,7 +303,7 @@ impl Scheduler {
 
     fn calculate_backoff_duration(&self, pod: &Pod) -> f64 {
         let mut duration = self.sim_config.pod_initial_backoff_duration;
-        for i in 0..pod.scheduling_attempts.unwrap() {
+        for _ in 0..pod.scheduling_attempts.unwrap() {
             duration *= 2.0;
             if duration >= self.sim_config.pod_max_backoff_duration {
                 return duration;
@@ -130,8 +313,7 @@ impl Scheduler {
     }
 
     pub fn move_pods_to_active_or_backoff(&mut self, mut pods: Vec<Pod>) {
-        while !pods.is_empty() {
-            let pod = pods.pop().unwrap();
+        while let Some(pod) = pods.pop() {
             if self.ctx.time() - pod.scheduling_timestamp.unwrap() < self.calculate_backoff_duration(&pod) {
                 let left_backoff_duration = self.calculate_backoff_duration(&pod) - self.ctx.time() + pod.scheduling_timestamp.unwrap();
                 self.ctx.emit(PodBackoffRetry { pod }, self.id, left_backoff_duration);
@@ -145,8 +327,7 @@ impl Scheduler {
     pub fn flush_unschedulable_queue(&mut self) {
         let mut new_unschedulable_queue = Vec::<Pod>::default();
         let mut pods_to_flush = Vec::<Pod>::default();
-        while !self.unschedulable_queue.is_empty() {
-            let pod = self.unschedulable_queue.pop().unwrap();
+        while let Some(pod) = self.unschedulable_queue.pop() {
             if self.ctx.time() - pod.scheduling_timestamp.unwrap() < POD_MIN_UNSCHEDULABLE_TIMEOUT {
                 new_unschedulable_queue.push(pod);
             } else {
@@ -159,17 +340,108 @@ impl Scheduler {
 
     pub fn move_all_to_active_or_backoff(&mut self) {
         let mut pods_to_move = Vec::<Pod>::default();
-        while !self.unschedulable_queue.is_empty() {
-            let pod = self.unschedulable_queue.pop().unwrap();
+        while let Some(pod) = self.unschedulable_queue.pop() {
             pods_to_move.push(pod);
         }
         self.move_pods_to_active_or_backoff(pods_to_move);
     }
 
+    /// Restart after the outage: pending pods of the queues are moved to the ActiveQueue, so backoffs
+    /// and nominations of pods are lost, assumptions are kept until their bindings reach the API server
+    fn restart(&mut self) {
+        // pods keep their order in the ActiveQueue
+        let mut pods: Vec<Pod> = std::mem::take(&mut self.active_queue).into_sorted_vec().into_iter()
+            .rev()
+            .map(|queued| queued.pod)
+            .collect();
+        pods.append(&mut self.unschedulable_queue);
+        self.cleanup_assumed_and_nominated_pods();
+        self.nominated_pods.borrow_mut().clear();
+        self.moving_cycle = self.scheduling_cycle;
+        for mut pod in pods {
+            pod.scheduling_attempts = None;
+            pod.scheduling_timestamp = None;
+            pod.nominated_node_id = None;
+            self.push_to_active_queue(pod);
+        }
+        if !self.active_queue.is_empty() {
+            self.ctx.emit(SchedulingCycle {}, self.id, 0.0);
+        }
+        for event in self.outage.finish() {
+            self.on(event);
+        }
+    }
+
+    /// Handle the event received during the outage
+    fn hold_event(&mut self, event: Event) {
+        if event.data.is::<FlushUnschedulableQueue>() {
+            self.outage.hold_periodic(event);
+        } else if event.data.is::<SchedulingCycle>() {
+            // the scheduling loop is started again on restart
+        } else if self.outage.queue_events() {
+            self.outage.hold(event);
+        } else {
+            // pods of dropped events are still pending in the API server
+            cast!(match event.data {
+                PodAssigningFailed { pod, .. } => {
+                    self.push_to_active_queue(pod);
+                }
+                PodBackoffRetry { pod } => {
+                    self.push_to_active_queue(pod);
+                }
+                MoveRequest {} => {
+                    self.outage.dropped_event_count += 1;
+                }
+            })
+        }
+    }
+}
+
+impl PodMoveSimulator for Scheduler {
+    fn can_move_pods(&mut self, pods: &Vec<Pod>, excluded_node_ids: &BTreeSet<u32>,
+                     moved_requests: &mut BTreeMap<u32, (f32, f64)>) -> bool {
+        let api_server = self.api_server.borrow();
+        let nodes = &api_server.working_nodes;
+        let mut new_moved_requests = moved_requests.clone();
+        for pod in pods {
+            let mut state = CycleState::default();
+            state.write(ASSUMED_PODS_KEY, self.assumed_pods.clone());
+            state.write(NOMINATED_PODS_KEY, self.nominated_pods.clone());
+            if !self.profile.run_pre_filter_plugins(&mut state, pod, nodes).is_success() {
+                return false;
+            }
+
+            let mut chosen_node_id = None;
+            for (node_id, node) in nodes.iter() {
+                if excluded_node_ids.contains(node_id) {
+                    continue;
+                }
+                let (moved_cpu, moved_memory) = *new_moved_requests.get(node_id).unwrap_or(&(0.0, 0.0));
+                let node = node.borrow();
+                if node.can_place_pod(pod.requested_cpu + moved_cpu, pod.requested_memory + moved_memory) &&
+                    self.profile.run_filter_plugins(&state, pod, &node).is_success() {
+                    chosen_node_id = Some(*node_id);
+                    break;
+                }
+            }
+            if chosen_node_id.is_none() {
+                return false;
+            }
+            let requests = new_moved_requests.entry(chosen_node_id.unwrap()).or_insert((0.0, 0.0));
+            requests.0 += pod.requested_cpu;
+            requests.1 += pod.requested_memory;
+        }
+        *moved_requests = new_moved_requests;
+        true
+    }
 }
 
 impl EventHandler for Scheduler {
     fn on(&mut self, event: Event) {
+        if self.outage.is_down && !event.data.is::<ComponentOutageEnd>() {
+            self.hold_event(event);
+            return;
+        }
         cast!(match event.data {
             SchedulingCycle {} => {
                 self.schedule_next_pod();
@@ -187,6 +459,12 @@ impl EventHandler for Scheduler {
             MoveRequest {} => {
                 self.move_all_to_active_or_backoff();
             }
+            ComponentOutageStart { queue_events } => {
+                self.outage.start(queue_events);
+            }
+            ComponentOutageEnd {} => {
+                self.restart();
+            }
         })
     }
 }
\ No newline at end of file
@@ -1,17 +1,156 @@
+//! Extension points of the scheduling framework (like in kube-scheduler).
+
+use std::any::Any;
 use std::cell::RefCell;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 use crate::node::Node;
 use crate::pod::Pod;
 
-pub trait SchedulerAlgorithm {
-    /// Filter nodes by need pod, returns node_id for filtered nodes.
-    fn filter(&self, pod: &Pod, nodes: &BTreeMap<u32, Rc<RefCell<Node>>>) -> Vec<u32>;
+/// Max score of the node given by a single score plugin (after normalization).
+pub const MAX_NODE_SCORE: f64 = 10.0;
+
+/// Key of the assumed pods in the cycle state.
+pub const ASSUMED_PODS_KEY: &str = "AssumedPods";
+
+/// Pods bound by the scheduler, which aren't placed on nodes yet: pod_id -> (node_id, pod).
+pub type AssumedPods = Rc<RefCell<BTreeMap<u64, (u32, Pod)>>>;
+
+/// Key of the nominated pods in the cycle state.
+pub const NOMINATED_PODS_KEY: &str = "NominatedPods";
+
+/// Pods waiting for resources freed by preemption: pod_id -> (nominated node_id, pod).
+pub type NominatedPods = Rc<RefCell<BTreeMap<u64, (u32, Pod)>>>;
+
+/// Calls f with labels of the node and the pod for every other pod placed on the nodes
+/// or assumed on them by the scheduler.
+pub fn for_each_existing_pod<F>(state: &CycleState, pod: &Pod, nodes: &BTreeMap<u32, Rc<RefCell<Node>>>, mut f: F)
+    where F: FnMut(&BTreeMap<String, String>, &Pod) {
+    for node in nodes.values() {
+        let node = node.borrow();
+        for existing_pod in node.pods.values().filter(|existing_pod| existing_pod.id != pod.id) {
+            f(&node.labels, existing_pod);
+        }
+    }
+    if let Some(assumed_pods) = state.read::<AssumedPods>(ASSUMED_PODS_KEY) {
+        for (node_id, assumed_pod) in assumed_pods.borrow().values() {
+            if assumed_pod.id == pod.id {
+                continue;
+            }
+            if let Some(node) = nodes.get(node_id) {
+                f(&node.borrow().labels, assumed_pod);
+            }
+        }
+    }
+}
+
+/// Result of the plugin's work at some extension point.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PluginStatus {
+    /// Pod can go further through the scheduling cycle.
+    Success,
+    /// Pod can't be scheduled (or can't be placed on the node), with the reason.
+    /// Preemption of lower priority pods on the node can help.
+    Unschedulable(String),
+    /// Pod can't be placed on the node and preemption won't help, with the reason.
+    UnschedulableAndUnresolvable(String),
+    /// Plugin doesn't process this pod (next bind plugin is called).
+    Skip,
+    /// Binding of the pod must be delayed for the given time (for permit plugins).
+    Wait(f64),
+}
+
+impl PluginStatus {
+    pub fn is_success(&self) -> bool {
+        *self == PluginStatus::Success
+    }
+}
+
+/// Data shared by plugins during the scheduling cycle of a single pod.
+#[derive(Default)]
+pub struct CycleState {
+    data: HashMap<String, Box<dyn Any>>,
+}
+
+impl CycleState {
+    pub fn write<T: Any>(&mut self, key: &str, value: T) {
+        self.data.insert(key.to_string(), Box::new(value));
+    }
+
+    pub fn read<T: Any>(&self, key: &str) -> Option<&T> {
+        self.data.get(key).and_then(|value| (**value).downcast_ref::<T>())
+    }
+
+    pub fn delete(&mut self, key: &str) {
+        self.data.remove(key);
+    }
+}
+
+/// Result of the successful post filter plugin.
+#[derive(Clone, Debug, Default)]
+pub struct PostFilterResult {
+    /// Node on which the pod should be placed in one of next scheduling cycles.
+    pub nominated_node_id: Option<u32>,
+    /// Pods which must be evicted from the nominated node to free resources for the pod.
+    pub victim_ids: Vec<u64>,
+}
+
+pub trait SchedulerPlugin {
+    /// Name of the plugin (used in reasons of scheduling failures and to disable plugins).
+    fn name(&self) -> &str;
+}
+
+pub trait QueueSortPlugin: SchedulerPlugin {
+    /// Returns true if the first pod should be scheduled before the second one.
+    fn less(&self, first: &Pod, second: &Pod) -> bool;
+}
+
+pub trait PreFilterPlugin: SchedulerPlugin {
+    /// Prepares the cycle state (or checks cluster-wide conditions) before filtering.
+    fn pre_filter(&mut self, state: &mut CycleState, pod: &Pod,
+                  nodes: &BTreeMap<u32, Rc<RefCell<Node>>>) -> PluginStatus;
+}
+
+pub trait FilterPlugin: SchedulerPlugin {
+    /// Checks if the pod can be placed on the node.
+    fn filter(&mut self, state: &CycleState, pod: &Pod, node: &Node) -> PluginStatus;
+}
+
+pub trait PostFilterPlugin: SchedulerPlugin {
+    /// Called when there are no feasible nodes for the pod, returns None if the plugin can't help.
+    fn post_filter(&mut self, state: &CycleState, pod: &Pod,
+                   nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
+                   node_statuses: &BTreeMap<u32, PluginStatus>) -> Option<PostFilterResult>;
+}
 
-    /// Score nodes by need pod, returns scores for nodes from filtered_node_ids.
-    fn score(&self, pod: &Pod, nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
-             filtered_node_ids: &Vec<u32>) -> Vec<f64>;
+pub trait PreScorePlugin: SchedulerPlugin {
+    /// Prepares the cycle state before scoring of the filtered nodes.
+    fn pre_score(&mut self, state: &mut CycleState, pod: &Pod,
+                 nodes: &BTreeMap<u32, Rc<RefCell<Node>>>, filtered_node_ids: &[u32]) -> PluginStatus;
 }
 
-//pub fn scheduler_algorithm_resolver(config_str: String) -> Box<dyn SchedulerAlgorithm> {
-//}
\ No newline at end of file
+pub trait ScorePlugin: SchedulerPlugin {
+    /// Scores the node for the pod, the node with the max weighted sum of scores is chosen.
+    fn score(&mut self, state: &CycleState, pod: &Pod, node: &Node) -> f64;
+
+    /// Normalizes scores of all filtered nodes (scores are in the order of filtered nodes).
+    fn normalize_score(&mut self, _state: &CycleState, _pod: &Pod, _scores: &mut [f64]) {}
+}
+
+pub trait ReservePlugin: SchedulerPlugin {
+    /// Reserves resources of the chosen node for the pod.
+    fn reserve(&mut self, state: &mut CycleState, pod: &Pod, node_id: u32) -> PluginStatus;
+
+    /// Rollbacks reservation if some of the next extension points failed.
+    fn unreserve(&mut self, state: &mut CycleState, pod: &Pod, node_id: u32);
+}
+
+pub trait PermitPlugin: SchedulerPlugin {
+    /// Approves, denies or delays binding of the pod to the chosen node.
+    fn permit(&mut self, state: &mut CycleState, pod: &Pod, node_id: u32) -> PluginStatus;
+}
+
+pub trait BindPlugin: SchedulerPlugin {
+    /// Binds the pod to the node, returns Skip if the next bind plugin should do it.
+    fn bind(&mut self, state: &mut CycleState, pod: &Pod, node_id: u32) -> PluginStatus;
+}
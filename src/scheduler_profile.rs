@@ -0,0 +1,261 @@
+//! Scheduling profile: ordered lists of plugins for every extension point of the scheduler.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use crate::default_scheduler_algorithms::default_binder::DefaultBinder;
+use crate::default_scheduler_algorithms::default_preemption::DefaultPreemption;
+use crate::default_scheduler_algorithms::inter_pod_affinity::InterPodAffinity;
+use crate::default_scheduler_algorithms::node_affinity::NodeAffinityPlugin;
+use crate::default_scheduler_algorithms::node_resources_fit::NodeResourcesFit;
+use crate::default_scheduler_algorithms::pod_topology_spread::PodTopologySpread;
+use crate::default_scheduler_algorithms::priority_sort::PrioritySort;
+use crate::default_scheduler_algorithms::taint_toleration::TaintToleration;
+use crate::node::Node;
+use crate::pod::Pod;
+use crate::scheduler_algorithm::{BindPlugin, CycleState, FilterPlugin, PermitPlugin, PluginStatus,
+                                 PostFilterPlugin, PostFilterResult, PreFilterPlugin, PreScorePlugin,
+                                 QueueSortPlugin, ReservePlugin, ScorePlugin};
+
+pub struct WeightedScorePlugin {
+    pub plugin: Box<dyn ScorePlugin>,
+    pub weight: f64,
+}
+
+pub struct SchedulerProfile {
+    queue_sort: Rc<dyn QueueSortPlugin>,
+    pre_filter: Vec<Box<dyn PreFilterPlugin>>,
+    filter: Vec<Box<dyn FilterPlugin>>,
+    post_filter: Vec<Box<dyn PostFilterPlugin>>,
+    pre_score: Vec<Box<dyn PreScorePlugin>>,
+    score: Vec<WeightedScorePlugin>,
+    reserve: Vec<Box<dyn ReservePlugin>>,
+    permit: Vec<Box<dyn PermitPlugin>>,
+    bind: Vec<Box<dyn BindPlugin>>,
+}
+
+impl Default for SchedulerProfile {
+    /// Profile with default plugins: PrioritySort, NodeAffinity, TaintToleration, InterPodAffinity,
+    /// PodTopologySpread, NodeResourcesFit, DefaultPreemption, DefaultBinder.
+    /// NodeResourcesFit is the last filter, so preemption is tried only for nodes which passed other filters.
+    fn default() -> Self {
+        Self {
+            queue_sort: Rc::new(PrioritySort::new()),
+            pre_filter: vec![Box::new(InterPodAffinity::new()), Box::new(PodTopologySpread::new())],
+            filter: vec![
+                Box::new(NodeAffinityPlugin::new()),
+                Box::new(TaintToleration::new()),
+                Box::new(InterPodAffinity::new()),
+                Box::new(PodTopologySpread::new()),
+                Box::new(NodeResourcesFit::new()),
+            ],
+            post_filter: vec![Box::new(DefaultPreemption::new())],
+            pre_score: vec![Box::new(InterPodAffinity::new()), Box::new(PodTopologySpread::new())],
+            score: vec![
+                WeightedScorePlugin { plugin: Box::new(NodeAffinityPlugin::new()), weight: 1.0 },
+                WeightedScorePlugin { plugin: Box::new(TaintToleration::new()), weight: 1.0 },
+                WeightedScorePlugin { plugin: Box::new(InterPodAffinity::new()), weight: 1.0 },
+                WeightedScorePlugin { plugin: Box::new(PodTopologySpread::new()), weight: 1.0 },
+            ],
+            reserve: Vec::default(),
+            permit: Vec::default(),
+            bind: vec![Box::new(DefaultBinder::new())],
+        }
+    }
+}
+
+impl SchedulerProfile {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates profile with default plugins and the score plugin added to default score plugins.
+    pub fn from_score_plugin(score_plugin: Box<dyn ScorePlugin>) -> Self {
+        let mut profile = Self::new();
+        profile.add_score_plugin(score_plugin, 1.0);
+        profile
+    }
+
+    /// Creates profile with default plugins, but the score plugin is the only one
+    /// (preferences of pods and PreferNoSchedule taints aren't taken into account).
+    pub fn from_single_score_plugin(score_plugin: Box<dyn ScorePlugin>) -> Self {
+        let mut profile = Self::new();
+        profile.score.clear();
+        profile.add_score_plugin(score_plugin, 1.0);
+        profile
+    }
+
+    pub fn set_queue_sort_plugin(&mut self, plugin: Box<dyn QueueSortPlugin>) {
+        self.queue_sort = Rc::from(plugin);
+    }
+
+    /// QueueSort plugin which orders the ActiveQueue of the scheduler.
+    pub fn queue_sort(&self) -> Rc<dyn QueueSortPlugin> {
+        self.queue_sort.clone()
+    }
+
+    pub fn add_pre_filter_plugin(&mut self, plugin: Box<dyn PreFilterPlugin>) {
+        self.pre_filter.push(plugin);
+    }
+
+    pub fn add_filter_plugin(&mut self, plugin: Box<dyn FilterPlugin>) {
+        self.filter.push(plugin);
+    }
+
+    pub fn add_post_filter_plugin(&mut self, plugin: Box<dyn PostFilterPlugin>) {
+        self.post_filter.push(plugin);
+    }
+
+    pub fn add_pre_score_plugin(&mut self, plugin: Box<dyn PreScorePlugin>) {
+        self.pre_score.push(plugin);
+    }
+
+    pub fn add_score_plugin(&mut self, plugin: Box<dyn ScorePlugin>, weight: f64) {
+        self.score.push(WeightedScorePlugin { plugin, weight });
+    }
+
+    pub fn add_reserve_plugin(&mut self, plugin: Box<dyn ReservePlugin>) {
+        self.reserve.push(plugin);
+    }
+
+    pub fn add_permit_plugin(&mut self, plugin: Box<dyn PermitPlugin>) {
+        self.permit.push(plugin);
+    }
+
+    /// Bind plugins added later are called only if previous ones returned Skip.
+    pub fn add_bind_plugin(&mut self, plugin: Box<dyn BindPlugin>) {
+        self.bind.push(plugin);
+    }
+
+    /// Removes the plugin with the specified name from all extension points (except QueueSort).
+    pub fn disable_plugin(&mut self, name: &str) {
+        self.pre_filter.retain(|plugin| plugin.name() != name);
+        self.filter.retain(|plugin| plugin.name() != name);
+        self.post_filter.retain(|plugin| plugin.name() != name);
+        self.pre_score.retain(|plugin| plugin.name() != name);
+        self.score.retain(|weighted| weighted.plugin.name() != name);
+        self.reserve.retain(|plugin| plugin.name() != name);
+        self.permit.retain(|plugin| plugin.name() != name);
+        self.bind.retain(|plugin| plugin.name() != name);
+    }
+
+    /// Returns true if the first pod should be scheduled before the second one.
+    pub fn less(&self, first: &Pod, second: &Pod) -> bool {
+        self.queue_sort.less(first, second)
+    }
+
+    pub fn run_pre_filter_plugins(&mut self, state: &mut CycleState, pod: &Pod,
+                                  nodes: &BTreeMap<u32, Rc<RefCell<Node>>>) -> PluginStatus {
+        for plugin in self.pre_filter.iter_mut() {
+            let status = plugin.pre_filter(state, pod, nodes);
+            if !status.is_success() {
+                return status;
+            }
+        }
+        PluginStatus::Success
+    }
+
+    /// Runs filter plugins for the node, returns the status of the first failed plugin.
+    pub fn run_filter_plugins(&mut self, state: &CycleState, pod: &Pod, node: &Node) -> PluginStatus {
+        for plugin in self.filter.iter_mut() {
+            let status = plugin.filter(state, pod, node);
+            if !status.is_success() {
+                return status;
+            }
+        }
+        PluginStatus::Success
+    }
+
+    /// Runs post filter plugins until one of them returns the result.
+    pub fn run_post_filter_plugins(&mut self, state: &CycleState, pod: &Pod,
+                                   nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
+                                   node_statuses: &BTreeMap<u32, PluginStatus>) -> Option<PostFilterResult> {
+        for plugin in self.post_filter.iter_mut() {
+            let result = plugin.post_filter(state, pod, nodes, node_statuses);
+            if result.is_some() {
+                return result;
+            }
+        }
+        None
+    }
+
+    pub fn run_pre_score_plugins(&mut self, state: &mut CycleState, pod: &Pod,
+                                 nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
+                                 filtered_node_ids: &[u32]) -> PluginStatus {
+        for plugin in self.pre_score.iter_mut() {
+            let status = plugin.pre_score(state, pod, nodes, filtered_node_ids);
+            if !status.is_success() {
+                return status;
+            }
+        }
+        PluginStatus::Success
+    }
+
+    /// Returns weighted sums of normalized scores for filtered nodes.
+    pub fn run_score_plugins(&mut self, state: &CycleState, pod: &Pod,
+                             nodes: &BTreeMap<u32, Rc<RefCell<Node>>>,
+                             filtered_node_ids: &[u32]) -> Vec<f64> {
+        let mut total_scores = vec![0.0; filtered_node_ids.len()];
+        for weighted in self.score.iter_mut() {
+            let mut scores = Vec::<f64>::with_capacity(filtered_node_ids.len());
+            for node_id in filtered_node_ids {
+                let node = nodes.get(node_id).unwrap().borrow();
+                scores.push(weighted.plugin.score(state, pod, &node));
+            }
+            weighted.plugin.normalize_score(state, pod, &mut scores);
+            for (total_score, score) in total_scores.iter_mut().zip(scores) {
+                *total_score += weighted.weight * score;
+            }
+        }
+        total_scores
+    }
+
+    /// Runs reserve plugins, unreserves already reserved plugins if one of them failed.
+    pub fn run_reserve_plugins(&mut self, state: &mut CycleState, pod: &Pod, node_id: u32) -> PluginStatus {
+        for i in 0..self.reserve.len() {
+            let status = self.reserve[i].reserve(state, pod, node_id);
+            if !status.is_success() {
+                for plugin in self.reserve[..i].iter_mut().rev() {
+                    plugin.unreserve(state, pod, node_id);
+                }
+                return status;
+            }
+        }
+        PluginStatus::Success
+    }
+
+    pub fn run_unreserve_plugins(&mut self, state: &mut CycleState, pod: &Pod, node_id: u32) {
+        for plugin in self.reserve.iter_mut().rev() {
+            plugin.unreserve(state, pod, node_id);
+        }
+    }
+
+    /// Returns Success, the first rejection or Wait with the max requested delay.
+    pub fn run_permit_plugins(&mut self, state: &mut CycleState, pod: &Pod, node_id: u32) -> PluginStatus {
+        let mut max_delay: Option<f64> = None;
+        for plugin in self.permit.iter_mut() {
+            match plugin.permit(state, pod, node_id) {
+                PluginStatus::Success | PluginStatus::Skip => {}
+                PluginStatus::Wait(delay) => {
+                    max_delay = Some(max_delay.unwrap_or(0.0).max(delay));
+                }
+                status => return status,
+            }
+        }
+        match max_delay {
+            Some(delay) => PluginStatus::Wait(delay),
+            None => PluginStatus::Success,
+        }
+    }
+
+    /// Runs bind plugins until one of them doesn't skip the pod.
+    pub fn run_bind_plugins(&mut self, state: &mut CycleState, pod: &Pod, node_id: u32) -> PluginStatus {
+        for plugin in self.bind.iter_mut() {
+            let status = plugin.bind(state, pod, node_id);
+            if status != PluginStatus::Skip {
+                return status;
+            }
+        }
+        PluginStatus::Unschedulable("no bind plugin bound the pod".to_string())
+    }
+}
@@ -1,89 +1,162 @@
 use std::cell::RefCell;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 use dslab_core::context::SimulationContext;
 use dslab_core::simulation::Simulation;
 use crate::api_server::APIServer;
 use crate::scheduler::Scheduler;
-use crate::simulation_config::SimulationConfig;
+use crate::simulation_config::{ComponentOutage, ControlPlaneComponent, SimulationConfig};
 use sugars::{rc, refcell};
-use crate::cluster_autoscaler::ClusterAutoscaler;
+use crate::cluster_autoscaler::{ClusterAutoscaler, NodeGroupState};
 use crate::cluster_autoscaler_algorithm::ClusterAutoscalerAlgorithm;
+use crate::default_cluster_autoscaler_algorithms::expanders::expander_from_config;
+use crate::cloud_provider::{CloudProvider, SimulatedCloudProvider};
+use crate::cron_job_controller::CronJobController;
 use crate::dataset_reader::DatasetReader;
-use crate::deployment::{Deployment, PodTemplate};
-use crate::events::api_server::PodRemoveRequest;
+use crate::deployment::{Deployment, DeploymentRollout, DeploymentStrategy, PodTemplate};
+use crate::disruption_budget::PodDisruptionBudget;
+use crate::events::api_server::{DisruptionBudgetCreateRequest, DisruptionBudgetRemoveRequest, PodRemoveRequest};
 use crate::events::assigning::PodAssigningRequest;
 use crate::events::autoscaler::{ClusterAutoscalerScan, HorizontalAutoscalerCycle, MetricsServerSnapshot, VerticalAutoscalerCycle};
-use crate::events::deployment::DeploymentCreateRequest;
-use crate::events::node::NodeStatusChanged;
+use crate::events::deployment::{DeploymentCreateRequest, DeploymentUpdateRequest};
+use crate::events::job::{CronJobCreateRequest, JobCreateRequest};
+use crate::events::fault::FaultInjectorStart;
+use crate::events::maintenance::MaintenanceWindowStart;
+use crate::events::outage::{ComponentOutageEnd, ComponentOutageStart};
+use crate::events::node::{NodeDrainRequest, NodeLifecycleMonitor, NodeStatusChanged, NodeTaintAdded, NodeTaintRemoved,
+                          RemoveNode};
+use crate::fault_injector::FaultInjector;
 use crate::horizontal_autoscaler::HorizontalAutoscaler;
 use crate::horizontal_autoscaler_algorithm::HorizontalAutoscalerAlgorithm;
+use crate::job::{CronJob, Job, JobSpec};
+use crate::job_controller::JobController;
 use crate::load_model::LoadModel;
 use crate::logger::Logger;
+use crate::maintenance_controller::MaintenanceController;
 use crate::metrics_server::MetricsServer;
+use crate::network::NetworkModel;
 use crate::node::{Node, NodeState};
-use crate::pod::{Pod, PodStatus};
-use crate::scheduler_algorithm::SchedulerAlgorithm;
-use crate::simulation_metrics::MetricsLogger;
+use crate::node_lifecycle_controller::NodeLifecycleController;
+use crate::pod::{QoSClass, SchedulingConstraints};
+use crate::scheduler_profile::SchedulerProfile;
+use crate::simulation_metrics::{CapacityStats, CostReport, FaultInjectionStats, MetricsLogger};
+use crate::taints::{Taint, TAINT_UNSCHEDULABLE, TaintEffect};
 use crate::vertical_autoscaler::VerticalAutoscaler;
 use crate::vertical_autoscaler_algorithm::VerticalAutoscalerAlgorithm;
 
 pub struct K8sSimulation {
     scheduler: Rc<RefCell<Scheduler>>,
     api_server: Rc<RefCell<APIServer>>,
+    network: Rc<RefCell<NetworkModel>>,
+    job_controller: Rc<RefCell<JobController>>,
+    cron_job_controller: Rc<RefCell<CronJobController>>,
+    maintenance_controller: Rc<RefCell<MaintenanceController>>,
     cluster_autoscaler: Option<Rc<RefCell<ClusterAutoscaler>>>,
     metrics_server: Option<Rc<RefCell<MetricsServer>>>,
     vertical_autoscaler: Option<Rc<RefCell<VerticalAutoscaler>>>,
     horizontal_autoscaler: Option<Rc<RefCell<HorizontalAutoscaler>>>,
+    fault_injector: Option<Rc<RefCell<FaultInjector>>>,
+    node_lifecycle_controller: Option<Rc<RefCell<NodeLifecycleController>>>,
     
     sim: Simulation,
     ctx: SimulationContext,
     sim_config: Rc<SimulationConfig>,
+    logger: Rc<RefCell<Box<dyn Logger>>>,
 
     last_node_id: u64,
+    node_ids_by_name: HashMap<String, u32>,
 }
 
 impl K8sSimulation {
     /// Creates a simulation with specified config.
     pub fn new(mut sim: Simulation, metrics_logger: Box<dyn MetricsLogger>, logger: Box<dyn Logger>,
-               sim_config: SimulationConfig, scheduler_algorithm: Box<dyn SchedulerAlgorithm>,
+               sim_config: SimulationConfig, scheduler_profile: SchedulerProfile,
                cluster_autoscaler_algorithm: Option<Box<dyn ClusterAutoscalerAlgorithm>>,
                vertical_autoscaler_algorithm: Option<Box<dyn VerticalAutoscalerAlgorithm>>,
                horizontal_autoscaler_algorithm: Option<Box<dyn HorizontalAutoscalerAlgorithm>>) -> Self {
         let sim_config = rc!(sim_config);
+        let logger = rc!(refcell!(logger));
+
+        let network = rc!(refcell!(NetworkModel::new(sim.create_context("network"), sim_config.clone())));
 
         let api_server = rc!(refcell!(
-            APIServer::new(sim.create_context("api_server"), sim_config.clone(), metrics_logger)
+            APIServer::new(network.clone(), sim.create_context("api_server"), sim_config.clone(), metrics_logger)
         ));
         sim.add_handler("api_server", api_server.clone());
+        network.borrow_mut().register_component(api_server.borrow().id, "api_server");
 
-        let scheduler = rc!(refcell!(Scheduler::new(api_server.clone(), scheduler_algorithm,
+        let scheduler = rc!(refcell!(Scheduler::new(api_server.clone(), scheduler_profile, network.clone(),
                 sim.create_context("scheduler"), sim_config.clone())));
         sim.add_handler("scheduler", scheduler.clone());
+        network.borrow_mut().register_component(scheduler.borrow().id, "scheduler");
         {
             api_server.borrow_mut().set_scheduler(scheduler.clone());
         }
 
+        let job_controller = rc!(refcell!(
+            JobController::new(api_server.clone(), network.clone(), sim.create_context("job_controller"),
+                sim_config.clone())
+        ));
+        sim.add_handler("job_controller", job_controller.clone());
+        network.borrow_mut().register_component(job_controller.borrow().id, "job_controller");
+        {
+            api_server.borrow_mut().set_job_controller(job_controller.clone());
+        }
+
+        let cron_job_controller = rc!(refcell!(
+            CronJobController::new(job_controller.clone(), network.clone(), sim.create_context("cron_job_controller"),
+                sim_config.clone())
+        ));
+        sim.add_handler("cron_job_controller", cron_job_controller.clone());
+        network.borrow_mut().register_component(cron_job_controller.borrow().id, "cron_job_controller");
+
+        let maintenance_controller = rc!(refcell!(
+            MaintenanceController::new(api_server.clone(), sim.create_context("maintenance_controller"),
+                sim_config.clone())
+        ));
+        sim.add_handler("maintenance_controller", maintenance_controller.clone());
+
         let ctx = sim.create_context("simulation");
 
         let mut cluster_autoscaler_option = None;
         if cluster_autoscaler_algorithm.is_some() {
-            let mut cloud_nodes_pool = Vec::<Rc<RefCell<Node>>>::default();
-            for i in 0..sim_config.cloud_nodes_count {
-                let name = format!("cloud_node_{}", i);
-                let node_ctx = sim.create_context(&name);
-                let cpu = sim_config.default_node.cpu;
-                let memory = sim_config.default_node.memory;
-                let node = rc!(refcell!(Node::new(cpu, memory, NodeState::Working,
-                    api_server.clone(), node_ctx, sim_config.clone())));
-                cloud_nodes_pool.push(node.clone());
-                sim.add_handler(name, node.clone());
+            let mut node_groups = Vec::<NodeGroupState>::default();
+            for group_config in sim_config.autoscaler_node_groups() {
+                let mut nodes = Vec::<Rc<RefCell<Node>>>::default();
+                for i in 0..group_config.max_size {
+                    let name = format!("{}_node_{}", group_config.name, i);
+                    let node_ctx = sim.create_context(&name);
+                    let mut node = Node::new(group_config.cpu, group_config.memory, NodeState::Working,
+                        group_config.node_labels(),
+                        api_server.clone(), network.clone(), logger.clone(), node_ctx, sim_config.clone());
+                    node.taints = group_config.taints.clone();
+                    node.price = group_config.price;
+                    network.borrow_mut().register_component(node.id, &name);
+                    let node = rc!(refcell!(node));
+                    nodes.push(node.clone());
+                    sim.add_handler(name, node.clone());
+                }
+                node_groups.push(NodeGroupState::new(group_config, nodes));
+            }
+            let mut cluster_autoscaler_algorithm = cluster_autoscaler_algorithm.unwrap();
+            cluster_autoscaler_algorithm.set_scale_down_policy(sim_config.scale_down_utilization_threshold,
+                                                               sim_config.scale_down_delay_after_add,
+                                                               sim_config.scale_down_delay_after_failure);
+            if sim_config.expander.is_some() {
+                cluster_autoscaler_algorithm.set_expander(expander_from_config(
+                    sim_config.expander.as_ref().unwrap(), sim.create_context("expander")));
             }
             let cluster_ctx = sim.create_context("cluster_autoscaler");
             let cluster_autoscaler = rc!(refcell!(ClusterAutoscaler::new(
-                cloud_nodes_pool, api_server.clone(), scheduler.clone(),
-                cluster_autoscaler_algorithm.unwrap(), cluster_ctx, sim_config.clone()
+                node_groups, api_server.clone(), scheduler.clone(),
+                cluster_autoscaler_algorithm,
+                Box::new(SimulatedCloudProvider::new(sim.create_context("cloud_provider"), sim_config.clone())),
+                network.clone(), logger.clone(), cluster_ctx,
+                sim_config.clone()
             )));
+            api_server.borrow_mut().set_cluster_autoscaler(cluster_autoscaler.clone());
+            cluster_autoscaler.borrow_mut().add_min_size_nodes();
+            network.borrow_mut().register_component(cluster_autoscaler.borrow().id, "cluster_autoscaler");
             sim.add_handler("cluster_autoscaler", cluster_autoscaler.clone());
             cluster_autoscaler_option = Some(cluster_autoscaler.clone());
 
@@ -108,7 +181,8 @@ impl K8sSimulation {
             let vertical_ctx = sim.create_context("vertical_autoscaler");
             let vertical_autoscaler = rc!(refcell!(
                 VerticalAutoscaler::new(api_server.clone(), metrics_server_option.clone().unwrap(),
-                    vertical_autoscaler_algorithm.unwrap(), vertical_ctx, sim_config.clone())));
+                    vertical_autoscaler_algorithm.unwrap(), network.clone(), vertical_ctx, sim_config.clone())));
+            network.borrow_mut().register_component(vertical_autoscaler.borrow().id, "vertical_autoscaler");
             sim.add_handler("vertical_autoscaler", vertical_autoscaler.clone());
             vertical_autoscaler_option = Some(vertical_autoscaler.clone());
 
@@ -120,30 +194,69 @@ impl K8sSimulation {
             let horizontal_ctx = sim.create_context("horizontal_autoscaler");
             let horizontal_autoscaler = rc!(refcell!(
                 HorizontalAutoscaler::new(api_server.clone(), metrics_server_option.clone().unwrap(),
-                    horizontal_autoscaler_algorithm.unwrap(), horizontal_ctx, sim_config.clone())
+                    horizontal_autoscaler_algorithm.unwrap(), network.clone(), horizontal_ctx, sim_config.clone())
             ));
+            network.borrow_mut().register_component(horizontal_autoscaler.borrow().id, "horizontal_autoscaler");
             sim.add_handler("horizontal_autoscaler", horizontal_autoscaler.clone());
             horizontal_autoscaler_option = Some(horizontal_autoscaler.clone());
 
             ctx.emit(HorizontalAutoscalerCycle {}, horizontal_autoscaler.borrow().id, 0.0);
         }
 
+        let mut fault_injector_option = None;
+        if sim_config.fault_injector.is_some() {
+            let fault_injector = rc!(refcell!(
+                FaultInjector::new(sim_config.fault_injector.clone().unwrap(), api_server.clone(),
+                    sim.create_context("fault_injector"), sim_config.clone())
+            ));
+            sim.add_handler("fault_injector", fault_injector.clone());
+            fault_injector_option = Some(fault_injector.clone());
+
+            api_server.borrow_mut().set_fault_injector(fault_injector.clone());
+
+            // nodes are grouped into failure units at the start, after they are added
+            ctx.emit(FaultInjectorStart {}, fault_injector.borrow().id, 0.0);
+        }
+
+        let mut node_lifecycle_controller_option = None;
+        if sim_config.node_lifecycle.is_some() {
+            let node_lifecycle_controller = rc!(refcell!(
+                NodeLifecycleController::new(api_server.clone(), sim.create_context("node_lifecycle_controller"),
+                    sim_config.clone())
+            ));
+            sim.add_handler("node_lifecycle_controller", node_lifecycle_controller.clone());
+            node_lifecycle_controller_option = Some(node_lifecycle_controller.clone());
+
+            ctx.emit(NodeLifecycleMonitor {}, node_lifecycle_controller.borrow().id, 0.0);
+        }
+
         let mut sim = Self {
             scheduler,
             api_server,
+            network,
+            job_controller,
+            cron_job_controller,
+            maintenance_controller,
             cluster_autoscaler: cluster_autoscaler_option,
             metrics_server: metrics_server_option,
             vertical_autoscaler: vertical_autoscaler_option,
             horizontal_autoscaler: horizontal_autoscaler_option,
+            fault_injector: fault_injector_option,
+            node_lifecycle_controller: node_lifecycle_controller_option,
             sim,
             ctx,
             sim_config,
-            last_node_id: 0
+            logger,
+            last_node_id: 0,
+            node_ids_by_name: HashMap::default(),
         };
 
         for node_config in sim.sim_config.nodes.clone() {
             for _ in 0..node_config.count {
-                sim.add_node(node_config.cpu, node_config.memory);
+                let node_id = sim.add_node_with_labels(node_config.cpu, node_config.memory,
+                                                       node_config.labels.clone());
+                sim.node(node_id).borrow_mut().taints = node_config.taints.clone();
+                sim.node(node_id).borrow_mut().price = node_config.price;
             }
         }
 
@@ -152,39 +265,97 @@ impl K8sSimulation {
             dataset.parse(  sim.sim_config.trace.as_ref().unwrap().path.clone());
 
             for node in dataset.node_requests.iter() {
-                sim.add_node(node.cpu, node.memory);
+                let node_id = sim.add_node_with_labels(node.cpu, node.memory, node.labels.clone());
+                sim.node(node_id).borrow_mut().taints = node.taints.clone();
+                sim.node(node_id).borrow_mut().price = node.price;
+            }
+
+            while let Some(mut pod) = dataset.pod_requests.pop() {
+                sim.apply_priority_class(&pod.priority_class_name, &mut pod.priority_weight, &mut pod.constraints);
+                let pod_template = PodTemplate::new(pod.requested_cpu, pod.requested_memory, pod.limit_cpu,
+                                                    pod.limit_memory, pod.priority_weight,
+                                                    pod.cpu_load_model, pod.memory_load_model)
+                    .with_labels(pod.labels)
+                    .with_constraints(pod.constraints)
+                    .with_image(pod.image);
+                sim.submit_pod_from_template(pod_template, pod.timestamp);
             }
 
-            while !dataset.pod_requests.is_empty() {
-                let pod = dataset.pod_requests.pop().unwrap();
-                sim.submit_pod(pod.requested_cpu, pod.requested_memory,
-                               pod.limit_cpu, pod.limit_memory, pod.priority_weight,
-                               pod.cpu_load_model, pod.memory_load_model, pod.timestamp);
+            while let Some(mut deployment) = dataset.deployment_requests.pop() {
+                sim.apply_priority_class(&deployment.priority_class_name, &mut deployment.priority_weight,
+                                         &mut deployment.constraints);
+                let pod_template = PodTemplate::new(deployment.requested_cpu, deployment.requested_memory,
+                                                    deployment.limit_cpu, deployment.limit_memory,
+                                                    deployment.priority_weight,
+                                                    deployment.cpu_load_model, deployment.memory_load_model)
+                    .with_labels(deployment.labels)
+                    .with_constraints(deployment.constraints)
+                    .with_image(deployment.image);
+                sim.submit_deployment_from_template(pod_template, deployment.cnt_replicas, deployment.timestamp);
             }
 
-            while !dataset.deployment_requests.is_empty() {
-                let deployment = dataset.deployment_requests.pop().unwrap();
-                sim.submit_deployment(deployment.requested_cpu, deployment.requested_memory,
-                                      deployment.limit_cpu, deployment.limit_memory,
-                                      deployment.priority_weight,
-                                      deployment.cpu_load_model, deployment.memory_load_model,
-                                      deployment.cnt_replicas, deployment.timestamp);
+            while let Some(mut job) = dataset.job_requests.pop() {
+                sim.apply_priority_class(&job.priority_class_name, &mut job.priority_weight, &mut job.constraints);
+                let pod_template = PodTemplate::new(job.requested_cpu, job.requested_memory, job.limit_cpu,
+                                                    job.limit_memory, job.priority_weight,
+                                                    job.cpu_load_model, job.memory_load_model)
+                    .with_labels(job.labels)
+                    .with_constraints(job.constraints)
+                    .with_image(job.image)
+                    .with_duration(job.duration);
+                if job.schedule_period.is_some() {
+                    sim.submit_cron_job_from_template(pod_template, job.spec, job.schedule_period.unwrap(),
+                                                      job.jobs_limit, job.timestamp);
+                } else {
+                    sim.submit_job_from_template(pod_template, job.spec, job.timestamp);
+                }
             }
         }
 
+        for window in sim.sim_config.maintenance_windows.clone() {
+            let node_ids = window.nodes.iter().map(|name| *sim.node_ids_by_name.get(name)
+                .unwrap_or_else(|| panic!("Unknown node {} in maintenance window", name))).collect();
+            let start_time = window.start_time;
+            let window_id = sim.maintenance_controller.borrow_mut().add_window(window, node_ids);
+            sim.ctx.emit(MaintenanceWindowStart { window_id }, sim.maintenance_controller.borrow().id, start_time);
+        }
+
+        for outage in sim.sim_config.control_plane_outages.clone() {
+            sim.schedule_component_outage(outage);
+        }
+
         sim
     }
 
+    /// Replaces priority weight and preemption policy by ones of the priority class from the config.
+    fn apply_priority_class(&self, priority_class_name: &Option<String>, priority_weight: &mut u64,
+                            constraints: &mut SchedulingConstraints) {
+        if let Some(name) = priority_class_name {
+            let priority_class = self.sim_config.priority_classes.get(name)
+                .unwrap_or_else(|| panic!("Unknown priority class {}", name));
+            *priority_weight = priority_class.value;
+            constraints.preemption_policy = priority_class.preemption_policy.clone();
+        }
+    }
+
     /// Add new node to the k8s cluster, return node_id
     pub fn add_node(&mut self, cpu_total: f32, memory_total: f64) -> u32 {
+        self.add_node_with_labels(cpu_total, memory_total, BTreeMap::default())
+    }
+
+    /// Add new node with the specified labels to the k8s cluster, return node_id
+    pub fn add_node_with_labels(&mut self, cpu_total: f32, memory_total: f64,
+                                labels: BTreeMap<String, String>) -> u32 {
         self.last_node_id += 1;
         let name = format!("node_{}", self.last_node_id);
         let node_ctx = self.sim.create_context(&name);
-        let node = rc!(refcell!(Node::new(cpu_total, memory_total, NodeState::Working,
-            self.api_server.clone(), node_ctx, self.sim_config.clone())));
+        let node = rc!(refcell!(Node::new(cpu_total, memory_total, NodeState::Working, labels,
+            self.api_server.clone(), self.network.clone(), self.logger.clone(), node_ctx, self.sim_config.clone())));
         let node_id = node.borrow().id;
-        self.sim.add_handler(name, node.clone());
+        self.network.borrow_mut().register_component(node_id, &name);
+        self.sim.add_handler(name.clone(), node.clone());
         self.api_server.borrow_mut().add_new_node(node.clone());
+        self.node_ids_by_name.insert(name, node_id);
         node_id
     }
 
@@ -193,20 +364,89 @@ impl K8sSimulation {
                       self.api_server.borrow().id, self.sim_config.control_plane_message_delay + delay);
     }
 
+    /// Crash the node, its pods are rescheduled at once (or after the detection of the failure
+    /// and the eviction timeout, if node_lifecycle is set in the config)
     pub fn crash_node(&self, node_id: u32, delay: f64) {
         self.ctx.emit(NodeStatusChanged { node_id, new_status: NodeState::Failed },
                       self.api_server.borrow().id, self.sim_config.control_plane_message_delay + delay);
     }
 
+    /// Mark the node unschedulable, its pods keep working
+    pub fn cordon_node(&self, node_id: u32, delay: f64) {
+        self.add_node_taint(node_id, Taint::new(TAINT_UNSCHEDULABLE, "", TaintEffect::NoSchedule), delay);
+    }
+
+    pub fn uncordon_node(&self, node_id: u32, delay: f64) {
+        self.remove_node_taint(node_id, TAINT_UNSCHEDULABLE, TaintEffect::NoSchedule, delay);
+    }
+
+    /// Cordon the node and evict its pods gracefully, evictions blocked by disruption budgets
+    /// are retried every drain_retry_interval until the node is drained
+    pub fn drain_node(&self, node_id: u32, delay: f64) {
+        self.ctx.emit(NodeDrainRequest { node_id },
+                      self.api_server.borrow().id, self.sim_config.control_plane_message_delay + delay);
+    }
+
+    /// Remove the node from the cluster, it isn't removed if evictions of its pods are blocked
+    /// by disruption budgets (see blocked_eviction_count)
+    pub fn remove_node(&self, node_id: u32, delay: f64) {
+        self.ctx.emit(RemoveNode { node_id },
+                      self.api_server.borrow().id, self.sim_config.control_plane_message_delay + delay);
+    }
+
+    fn component_id(&self, component: ControlPlaneComponent) -> Option<u32> {
+        match component {
+            ControlPlaneComponent::Scheduler => Some(self.scheduler.borrow().id),
+            ControlPlaneComponent::ClusterAutoscaler => self.cluster_autoscaler.as_ref().map(|c| c.borrow().id),
+            ControlPlaneComponent::HorizontalAutoscaler => self.horizontal_autoscaler.as_ref().map(|c| c.borrow().id),
+            ControlPlaneComponent::VerticalAutoscaler => self.vertical_autoscaler.as_ref().map(|c| c.borrow().id),
+            ControlPlaneComponent::MetricsServer => self.metrics_server.as_ref().map(|c| c.borrow().id),
+        }
+    }
+
+    /// Stop the control plane component at outage.start_time and restart it after outage.duration,
+    /// it loses its in-memory state (queues of the scheduler, stabilization state of autoscalers)
+    pub fn schedule_component_outage(&self, outage: ComponentOutage) {
+        let component_id = self.component_id(outage.component)
+            .unwrap_or_else(|| panic!("{:?} isn't used in the simulation", outage.component));
+        let delay = (outage.start_time - self.ctx.time()).max(0.0);
+        self.ctx.emit(ComponentOutageStart { queue_events: outage.queue_events }, component_id, delay);
+        self.ctx.emit(ComponentOutageEnd {}, component_id, delay + outage.duration);
+    }
+
+    /// Returns nodes of the maintenance window (in the order of the config) which were already rebooted
+    pub fn maintenance_rebooted_nodes(&self, window_id: usize) -> Vec<u32> {
+        self.maintenance_controller.borrow().windows[window_id].rebooted_node_ids.clone()
+    }
+
+    /// Add taint to the node, pods not tolerating NoExecute taint are evicted
+    /// (after their tolerationSeconds, if it's set)
+    pub fn add_node_taint(&self, node_id: u32, taint: Taint, delay: f64) {
+        self.ctx.emit(NodeTaintAdded { node_id, taint },
+                      self.api_server.borrow().id, self.sim_config.control_plane_message_delay + delay);
+    }
+
+    /// Remove taint with the key and effect from the node
+    pub fn remove_node_taint(&self, node_id: u32, key: &str, effect: TaintEffect, delay: f64) {
+        self.ctx.emit(NodeTaintRemoved { node_id, key: key.to_string(), effect },
+                      self.api_server.borrow().id, self.sim_config.control_plane_message_delay + delay);
+    }
+
     pub fn submit_pod(&mut self, requested_cpu: f32, requested_memory: f64, limit_cpu: f32,
                       limit_memory: f64, priority_weight: u64,
                       cpu_load_model: Box<dyn LoadModel>,
                       memory_load_model: Box<dyn LoadModel>,
                       delay: f64) -> u64 {
+        let pod_template = PodTemplate::new(requested_cpu, requested_memory, limit_cpu, limit_memory,
+                                            priority_weight, cpu_load_model, memory_load_model);
+        self.submit_pod_from_template(pod_template, delay)
+    }
+
+    /// Submit pod created by the template (with labels, placement constraints and container image),
+    /// return pod_id
+    pub fn submit_pod_from_template(&mut self, pod_template: PodTemplate, delay: f64) -> u64 {
         let id = self.api_server.borrow_mut().generate_pod_id();
-        let pod = Pod::new(id, cpu_load_model, memory_load_model, requested_cpu,
-                           requested_memory, limit_cpu, limit_memory, priority_weight,
-                           PodStatus::Pending, None);
+        let pod = pod_template.create_pod(id, None);
         self.ctx.emit(PodAssigningRequest { pod }, self.api_server.borrow().id, delay);
         id
     }
@@ -217,18 +457,92 @@ impl K8sSimulation {
                              memory_load_model: Box<dyn LoadModel>,
                              cnt_replicas: u64,
                              delay: f64) -> u64 {
+        let pod_template = PodTemplate::new(requested_cpu, requested_memory, limit_cpu, limit_memory,
+                                            priority_weight, cpu_load_model, memory_load_model);
+        self.submit_deployment_from_template(pod_template, cnt_replicas, delay)
+    }
+
+    /// Submit deployment which replicas are created by the template, return deployment_id
+    pub fn submit_deployment_from_template(&mut self, pod_template: PodTemplate, cnt_replicas: u64,
+                                           delay: f64) -> u64 {
         let id = self.api_server.borrow_mut().generate_deployment_id();
-        let pod_template = PodTemplate {
-            cpu_load_model, memory_load_model,
-            requested_cpu, requested_memory,
-            limit_cpu, limit_memory,
-            priority_weight
-        };
         let deployment = Deployment::new(id, pod_template, cnt_replicas);
         self.ctx.emit(DeploymentCreateRequest { deployment }, self.api_server.borrow().id, delay);
         id
     }
 
+    /// Submit new revision of the deployment's pod template,
+    /// replicas are replaced by new ones according to the strategy
+    pub fn update_deployment(&mut self, deployment_id: u64, pod_template: PodTemplate, strategy: DeploymentStrategy,
+                             delay: f64) {
+        if let DeploymentStrategy::RollingUpdate { max_surge, max_unavailable } = strategy {
+            assert!(max_surge + max_unavailable > 0,
+                    "Rolling update needs positive max_surge or max_unavailable to make progress");
+        }
+        self.ctx.emit(DeploymentUpdateRequest { id: deployment_id, pod_template, strategy },
+                      self.api_server.borrow().id, delay);
+    }
+
+    pub fn submit_job(&mut self, requested_cpu: f32, requested_memory: f64, limit_cpu: f32,
+                      limit_memory: f64, priority_weight: u64,
+                      cpu_load_model: Box<dyn LoadModel>,
+                      memory_load_model: Box<dyn LoadModel>,
+                      duration: f64, spec: JobSpec,
+                      delay: f64) -> u64 {
+        let pod_template = PodTemplate::new(requested_cpu, requested_memory, limit_cpu, limit_memory,
+                                            priority_weight, cpu_load_model, memory_load_model)
+            .with_duration(duration);
+        self.submit_job_from_template(pod_template, spec, delay)
+    }
+
+    /// Submit job which pods are created by the template (it must have the duration), return job_id
+    pub fn submit_job_from_template(&mut self, pod_template: PodTemplate, spec: JobSpec, delay: f64) -> u64 {
+        assert!(pod_template.duration.is_some(), "Pod template of the job must have the duration");
+        let id = self.job_controller.borrow_mut().generate_job_id();
+        let job = Job::new(id, pod_template, spec);
+        self.ctx.emit(JobCreateRequest { job }, self.job_controller.borrow().id, delay);
+        id
+    }
+
+    /// Submit cron job which creates jobs every schedule_period seconds (at most jobs_limit of them),
+    /// return cron_job_id
+    pub fn submit_cron_job(&mut self, requested_cpu: f32, requested_memory: f64, limit_cpu: f32,
+                           limit_memory: f64, priority_weight: u64,
+                           cpu_load_model: Box<dyn LoadModel>,
+                           memory_load_model: Box<dyn LoadModel>,
+                           duration: f64, spec: JobSpec,
+                           schedule_period: f64, jobs_limit: Option<u64>,
+                           delay: f64) -> u64 {
+        let pod_template = PodTemplate::new(requested_cpu, requested_memory, limit_cpu, limit_memory,
+                                            priority_weight, cpu_load_model, memory_load_model)
+            .with_duration(duration);
+        self.submit_cron_job_from_template(pod_template, spec, schedule_period, jobs_limit, delay)
+    }
+
+    /// Submit cron job which jobs' pods are created by the template (it must have the duration),
+    /// return cron_job_id
+    pub fn submit_cron_job_from_template(&mut self, pod_template: PodTemplate, spec: JobSpec, schedule_period: f64,
+                                         jobs_limit: Option<u64>, delay: f64) -> u64 {
+        assert!(pod_template.duration.is_some(), "Pod template of the cron job must have the duration");
+        let id = self.cron_job_controller.borrow_mut().generate_cron_job_id();
+        let cron_job = CronJob::new(id, pod_template, spec, schedule_period, jobs_limit);
+        self.ctx.emit(CronJobCreateRequest { cron_job }, self.cron_job_controller.borrow().id, delay);
+        id
+    }
+
+    /// Add disruption budget limiting voluntary evictions of pods, return its id
+    pub fn add_disruption_budget(&mut self, budget: PodDisruptionBudget, delay: f64) -> u64 {
+        let id = self.api_server.borrow_mut().generate_disruption_budget_id();
+        self.ctx.emit(DisruptionBudgetCreateRequest { id, budget }, self.api_server.borrow().id,
+                      self.sim_config.control_plane_message_delay + delay);
+        id
+    }
+
+    pub fn remove_disruption_budget(&self, id: u64, delay: f64) {
+        self.ctx.emit(DisruptionBudgetRemoveRequest { id }, self.api_server.borrow().id,
+                      self.sim_config.control_plane_message_delay + delay);
+    }
+
     pub fn remove_pod(&self, pod_id: u64) {
         self.ctx.emit(PodRemoveRequest { pod_id }, self.api_server.borrow().id,
                       self.sim_config.message_delay);
@@ -239,6 +553,58 @@ impl K8sSimulation {
         self.api_server.borrow().working_nodes.clone()
     }
 
+    /// Returns results of failures injected by the fault injector.
+    pub fn fault_injection_stats(&self) -> FaultInjectionStats {
+        if self.fault_injector.is_none() {
+            return FaultInjectionStats::default();
+        }
+        self.fault_injector.as_ref().unwrap().borrow().stats.clone()
+    }
+
+    /// Returns sizes of node groups of the cluster autoscaler (including nodes being provisioned) by their names.
+    pub fn node_group_sizes(&self) -> BTreeMap<String, u64> {
+        let mut sizes = BTreeMap::default();
+        if let Some(cluster_autoscaler) = &self.cluster_autoscaler {
+            let cluster_autoscaler = cluster_autoscaler.borrow();
+            for (group_id, group) in cluster_autoscaler.node_groups.iter().enumerate() {
+                sizes.insert(group.config.name.clone(), cluster_autoscaler.node_group_size(group_id));
+            }
+        }
+        sizes
+    }
+
+    /// Returns total, per-deployment, wasted and idle costs of nodes up to the current time.
+    pub fn cost_report(&self) -> CostReport {
+        self.api_server.borrow_mut().account_costs();
+        self.api_server.borrow().cost_report()
+    }
+
+    /// Returns node hours, cost and disruptions of spot and on-demand nodes of node groups.
+    pub fn capacity_stats(&self) -> (CapacityStats, CapacityStats) {
+        if self.cluster_autoscaler.is_none() {
+            return (CapacityStats::default(), CapacityStats::default());
+        }
+        self.cluster_autoscaler.as_ref().unwrap().borrow().capacity_stats(&self.api_server.borrow())
+    }
+
+    /// Replace the cloud provider which provisions nodes of node groups (the cluster autoscaler must be used).
+    pub fn set_cloud_provider(&mut self, cloud_provider: Box<dyn CloudProvider>) {
+        self.cluster_autoscaler.as_ref().unwrap().borrow_mut().set_cloud_provider(cloud_provider);
+    }
+
+    /// Returns count of messages between components lost by the network.
+    pub fn lost_message_count(&self) -> u64 {
+        self.network.borrow().lost_message_count
+    }
+
+    /// Returns NotReady nodes (detected by missed heartbeats) with times since which they are NotReady.
+    pub fn not_ready_nodes(&self) -> BTreeMap<u32, f64> {
+        if self.node_lifecycle_controller.is_none() {
+            return BTreeMap::default();
+        }
+        self.node_lifecycle_controller.as_ref().unwrap().borrow().not_ready_nodes.clone()
+    }
+
     /// Returns the map with references to failed nodes.
     pub fn failed_nodes(&self) -> BTreeMap<u32, Rc<RefCell<Node>>> {
         self.api_server.borrow().failed_nodes.clone()
@@ -295,6 +661,62 @@ impl K8sSimulation {
         self.api_server.borrow().memory_used_load_rate()
     }
 
+    /// Returns the number of preemptions since the start of the simulation.
+    pub fn preemption_count(&self) -> u64 {
+        self.api_server.borrow().preemption_count()
+    }
+
+    /// Returns the number of voluntary evictions blocked by disruption budgets since the start of the simulation.
+    pub fn blocked_eviction_count(&self) -> u64 {
+        self.api_server.borrow().blocked_eviction_count()
+    }
+
+    /// Returns the number of OOM kills of pods' containers since the start of the simulation.
+    pub fn oom_kill_count(&self) -> u64 {
+        self.api_server.borrow().oom_kill_count()
+    }
+
+    /// Returns the number of containers' restarts after OOM kills since the start of the simulation.
+    pub fn restart_count(&self) -> u64 {
+        self.api_server.borrow().restart_count()
+    }
+
+    /// Returns the cpu wanted by running pods, but not granted to them at the moment.
+    pub fn throttled_cpu(&self) -> f64 {
+        self.api_server.borrow().throttled_cpu()
+    }
+
+    /// Returns the number of pods evicted due to node memory pressure for every QoS class.
+    pub fn evicted_pods_per_qos(&self) -> BTreeMap<QoSClass, u64> {
+        self.api_server.borrow().evicted_pods_per_qos()
+    }
+
+    /// Returns the last rollout of the deployment (None if the deployment isn't updated).
+    pub fn deployment_rollout(&self, deployment_id: u64) -> Option<DeploymentRollout> {
+        self.api_server.borrow().deployment_rollouts.get(&deployment_id).cloned()
+    }
+
+    /// Returns the job (its status, counts of succeeded and failed pods).
+    pub fn job(&self, job_id: u64) -> Option<Job> {
+        self.job_controller.borrow().jobs.get(&job_id).cloned()
+    }
+
+    /// Returns ids of jobs created by the cron job.
+    pub fn cron_job_jobs(&self, cron_job_id: u64) -> Vec<u64> {
+        self.cron_job_controller.borrow().cron_jobs.get(&cron_job_id)
+            .map_or(Vec::default(), |cron_job| cron_job.job_ids.clone())
+    }
+
+    /// Returns the time from the start of the first job to the completion of the last finished job.
+    pub fn jobs_makespan(&self) -> f64 {
+        self.job_controller.borrow().makespan()
+    }
+
+    /// Returns the skew of placed replicas across topology domains for every deployment.
+    pub fn deployments_skew(&self) -> BTreeMap<u64, u64> {
+        self.api_server.borrow().deployments_skew()
+    }
+
     pub fn finish_simulation(&self, path: &str) -> Result<(), std::io::Error> {
         self.api_server.borrow_mut().finish_and_save_log_metrics(path)
     }
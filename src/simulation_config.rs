@@ -1,6 +1,10 @@
 //! Simulation configuration.
 
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
+use crate::node::{LABEL_CAPACITY_TYPE, LABEL_NODE_GROUP};
+use crate::priority_class::PriorityClass;
+use crate::taints::Taint;
 
 /// Holds configuration of a single node or a set of identical nodes.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -11,6 +15,15 @@ pub struct NodeConfig {
     pub memory: f64,
     /// Number of such nodes.
     pub count: u32,
+    /// Node labels (e.g. zone or hardware type).
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Node taints (e.g. for dedicated node pools).
+    #[serde(default)]
+    pub taints: Vec<Taint>,
+    /// Price of the node per hour.
+    #[serde(default)]
+    pub price: f64,
 }
 
 impl NodeConfig {
@@ -18,9 +31,95 @@ impl NodeConfig {
         Self {
             cpu,
             memory,
-            count
+            count,
+            labels: BTreeMap::default(),
+            taints: Vec::default(),
+            price: 0.0,
+        }
+    }
+}
+
+/// Time from the start of the spot node to its reclamation by the cloud
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ReclamationModel {
+    /// Nodes are reclaimed with the constant rate (per hour), i.e. after exponentially distributed times.
+    HazardRate { rate: f64 },
+    /// Lifetimes of nodes of the group are taken one after another (cyclically).
+    Trace { values: Vec<f64> },
+}
+
+impl ReclamationModel {
+    fn validate(&self, group_name: &str) {
+        if let ReclamationModel::Trace { values } = self {
+            assert!(!values.is_empty(), "Reclamation trace of the node group {} has no values", group_name);
+        }
+    }
+}
+
+fn default_termination_notice() -> f64 {
+    120.0
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SpotConfig {
+    pub reclamation: ReclamationModel,
+    /// Time between the termination notice (the node is drained) and the reclamation.
+    #[serde(default = "default_termination_notice")]
+    pub termination_notice: f64,
+}
+
+/// Group of identical nodes which are added and removed by the cluster autoscaler
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct NodeGroupConfig {
+    pub name: String,
+    /// Node CPU capacity.
+    pub cpu: f32,
+    /// Node memory capacity in GB.
+    pub memory: f64,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub taints: Vec<Taint>,
+    /// Min count of nodes of the group (they are added at the start of the simulation).
+    #[serde(default)]
+    pub min_size: u64,
+    /// Max count of nodes of the group.
+    pub max_size: u64,
+    /// Price of the node per hour.
+    #[serde(default)]
+    pub price: f64,
+    /// Time from the request of the node to its addition to the cluster
+    /// (if boot_time of the cloud provider isn't set).
+    pub provisioning_time: f64,
+    /// Instance type of nodes for quotas and stockouts of the cloud provider (name of the group if None).
+    #[serde(default)]
+    pub instance_type: Option<String>,
+    /// Nodes of the group are spot instances which can be reclaimed by the cloud (on-demand if None).
+    #[serde(default)]
+    pub spot: Option<SpotConfig>,
+}
+
+impl NodeGroupConfig {
+    /// Panics if the reclamation trace of spot nodes has no values
+    pub fn validate(&self) {
+        if let Some(spot) = &self.spot {
+            spot.reclamation.validate(&self.name);
         }
     }
+
+    pub fn instance_type(&self) -> &str {
+        self.instance_type.as_ref().unwrap_or(&self.name)
+    }
+
+    /// Labels of nodes of the group with the name of the group and the capacity type
+    pub fn node_labels(&self) -> BTreeMap<String, String> {
+        let mut labels = self.labels.clone();
+        labels.insert(LABEL_NODE_GROUP.to_string(), self.name.clone());
+        let capacity_type = if self.spot.is_some() { "spot" } else { "on-demand" };
+        labels.insert(LABEL_CAPACITY_TYPE.to_string(), capacity_type.to_string());
+        labels
+    }
 }
 
 /// Holds configuration of a single node or a set of identical pods.
@@ -57,6 +156,259 @@ impl PodConfig {
     }
 }
 
+fn default_max_unavailable_nodes() -> u64 {
+    1
+}
+
+/// Nodes which are drained and rebooted one after another (for a rolling upgrade)
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// Start time (in simulation time, seconds from start of simulation).
+    pub start_time: f64,
+    /// Names of nodes (node_1, node_2, ... in the order of their creation).
+    pub nodes: Vec<String>,
+    /// Time of the node's reboot after the drain.
+    pub reboot_duration: f64,
+    /// Max count of nodes under maintenance at the same time.
+    #[serde(default = "default_max_unavailable_nodes")]
+    pub max_unavailable_nodes: u64,
+}
+
+/// Distribution of times between failures or of repair times (in seconds)
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum FailureDistribution {
+    Exponential { mean: f64 },
+    Weibull { shape: f64, scale: f64 },
+    /// Times are taken one after another (cyclically) for every failure unit.
+    Trace { values: Vec<f64> },
+}
+
+/// Random failures of nodes
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct FaultInjectorConfig {
+    /// Time from the start (or the repair) of the failure unit to its failure (MTBF is its mean).
+    pub time_to_failure: FailureDistribution,
+    /// Time from the failure to the repair (MTTR is its mean).
+    pub time_to_repair: FailureDistribution,
+    /// Nodes with the same value of the label (e.g. topology.kubernetes.io/zone) fail together,
+    /// otherwise every node fails independently.
+    #[serde(default)]
+    pub correlation_label: Option<String>,
+}
+
+impl FailureDistribution {
+    fn validate(&self, name: &str) {
+        if let FailureDistribution::Trace { values } = self {
+            assert!(!values.is_empty(), "Trace of {} of the fault injector has no values", name);
+        }
+    }
+}
+
+impl FaultInjectorConfig {
+    /// Panics if a trace distribution has no values
+    pub fn validate(&self) {
+        self.time_to_failure.validate("time_to_failure");
+        self.time_to_repair.validate("time_to_repair");
+    }
+}
+
+/// Components of the control plane which can be stopped and restarted
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum ControlPlaneComponent {
+    Scheduler,
+    ClusterAutoscaler,
+    HorizontalAutoscaler,
+    VerticalAutoscaler,
+    MetricsServer,
+}
+
+/// Outage of the control plane component: it doesn't work during the outage
+/// and loses its in-memory state on restart
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ComponentOutage {
+    pub component: ControlPlaneComponent,
+    /// Start time (in simulation time, seconds from start of simulation).
+    pub start_time: f64,
+    pub duration: f64,
+    /// Events received during the outage are queued and handled after the restart, otherwise they're dropped.
+    #[serde(default)]
+    pub queue_events: bool,
+}
+
+fn default_heartbeat_interval() -> f64 {
+    10.0
+}
+
+fn default_monitor_period() -> f64 {
+    5.0
+}
+
+fn default_grace_period() -> f64 {
+    40.0
+}
+
+fn default_pod_eviction_timeout() -> f64 {
+    300.0
+}
+
+/// Detection of failed nodes by heartbeats (like the k8s node lifecycle controller)
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct NodeLifecycleConfig {
+    /// Period of heartbeats sent by nodes to the API server.
+    #[serde(default = "default_heartbeat_interval")]
+    pub heartbeat_interval: f64,
+    /// Period of checks of heartbeats by the node lifecycle controller.
+    #[serde(default = "default_monitor_period")]
+    pub monitor_period: f64,
+    /// Time without heartbeats after which the node becomes NotReady.
+    #[serde(default = "default_grace_period")]
+    pub grace_period: f64,
+    /// Time in the NotReady state after which pods of the node are evicted.
+    #[serde(default = "default_pod_eviction_timeout")]
+    pub pod_eviction_timeout: f64,
+}
+
+impl Default for NodeLifecycleConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: default_heartbeat_interval(),
+            monitor_period: default_monitor_period(),
+            grace_period: default_grace_period(),
+            pod_eviction_timeout: default_pod_eviction_timeout(),
+        }
+    }
+}
+
+/// Distribution of the latency added to messages (in seconds)
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum LatencyDistribution {
+    Constant { value: f64 },
+    Uniform { min: f64, max: f64 },
+    Exponential { mean: f64 },
+}
+
+/// Properties of links between components (names of components are like api_server, scheduler,
+/// node_1, cluster_autoscaler, a pattern with `*` at the end matches names with the prefix)
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct LinkConfig {
+    /// Sender of messages (any component if None).
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Receiver of messages (any component if None).
+    #[serde(default)]
+    pub to: Option<String>,
+    /// Latency added to the message delay of the config.
+    #[serde(default)]
+    pub latency: Option<LatencyDistribution>,
+    /// Max uniformly distributed addition to the latency.
+    #[serde(default)]
+    pub jitter: f64,
+    /// Probability of the loss of the message.
+    #[serde(default)]
+    pub loss_probability: f64,
+}
+
+/// Messages between components of the group and its peers are lost during the partition
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct NetworkPartition {
+    pub start_time: f64,
+    pub duration: f64,
+    /// Names (or patterns) of components in the isolated group.
+    pub components: Vec<String>,
+    /// Components unreachable from the group (all other components if empty).
+    #[serde(default)]
+    pub peers: Vec<String>,
+}
+
+/// Network between components (messages are delivered with the fixed delays of the config if empty)
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// Links are matched in the order of the list, the first matched link is used.
+    #[serde(default)]
+    pub links: Vec<LinkConfig>,
+    #[serde(default)]
+    pub partitions: Vec<NetworkPartition>,
+}
+
+/// Distribution of the boot time of new nodes (in seconds)
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum BootTimeDistribution {
+    Constant { value: f64 },
+    Uniform { min: f64, max: f64 },
+    /// Negative samples are replaced with zero.
+    Normal { mean: f64, std_dev: f64 },
+}
+
+/// Instances of the type can't be provisioned during the window
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct StockoutWindow {
+    pub instance_type: String,
+    pub start_time: f64,
+    pub duration: f64,
+}
+
+/// Expander of the cluster autoscaler which chooses the node group for scale-up
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ExpanderConfig {
+    /// Random group, drawn with the simulation's random generator.
+    Random,
+    MostPods,
+    LeastWaste,
+    Price,
+    /// Priorities by names of node groups.
+    Priority { priorities: BTreeMap<String, i64> },
+}
+
+/// Cloud provider which provisions nodes of the cluster autoscaler
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct CloudProviderConfig {
+    /// Max count of nodes of the instance type in the cluster (including nodes being provisioned).
+    #[serde(default)]
+    pub quotas: BTreeMap<String, u64>,
+    /// Probability that the provisioned node fails to boot (it's detected after the boot time).
+    #[serde(default)]
+    pub provisioning_failure_probability: f64,
+    /// Boot time of new nodes (provisioning_time of the node group if None).
+    #[serde(default)]
+    pub boot_time: Option<BootTimeDistribution>,
+    #[serde(default)]
+    pub stockouts: Vec<StockoutWindow>,
+    /// YAML file with the list of stockout windows (added to stockouts).
+    #[serde(default)]
+    pub stockouts_file: Option<String>,
+}
+
+/// Model of the time spent by the scheduler to find a node for a pod.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SchedulingCostModel {
+    /// Fixed cost of the scheduling cycle plus costs of filtering and scoring of every node (in seconds).
+    Deterministic {
+        #[serde(default)]
+        per_pod_cost: f64,
+        #[serde(default)]
+        per_node_filter_cost: f64,
+        #[serde(default)]
+        per_node_score_cost: f64,
+    },
+    /// Real time of the algorithm's work (results depend on the machine and the build).
+    WallClock,
+}
+
+impl Default for SchedulingCostModel {
+    fn default() -> Self {
+        SchedulingCostModel::Deterministic {
+            per_pod_cost: 0.001,
+            per_node_filter_cost: 0.00001,
+            per_node_score_cost: 0.00001,
+        }
+    }
+}
+
 /// Holds information about the used trace dataset.
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct DatasetConfig {
@@ -78,12 +430,34 @@ struct RawSimulationConfig {
     pub default_node: Option<NodeConfig>,
     pub default_node_allocation_time: Option<f64>,
     pub cloud_nodes_count: Option<u64>,
+    pub node_groups: Option<Vec<NodeGroupConfig>>,
+    pub expander: Option<ExpanderConfig>,
+    pub cloud_provider: Option<CloudProviderConfig>,
+    pub node_group_initial_backoff: Option<f64>,
+    pub node_group_max_backoff: Option<f64>,
+    pub scale_down_utilization_threshold: Option<f64>,
+    pub scale_down_delay_after_add: Option<f64>,
+    pub scale_down_delay_after_failure: Option<f64>,
     pub metrics_server_interval: Option<f64>,
     pub vpa_interval: Option<f64>,
     pub hpa_interval: Option<f64>,
     pub memory_pressure_threshold: Option<f64>,
     pub update_pods_resources_period: Option<f64>,
+    pub cost_accounting_interval: Option<f64>,
+    pub container_restart_initial_backoff: Option<f64>,
+    pub container_restart_max_backoff: Option<f64>,
+    pub container_backoff_reset: Option<f64>,
+    pub image_pull_durations: Option<BTreeMap<String, f64>>,
+    pub default_image_pull_duration: Option<f64>,
+    pub drain_retry_interval: Option<f64>,
+    pub maintenance_windows: Option<Vec<MaintenanceWindow>>,
+    pub control_plane_outages: Option<Vec<ComponentOutage>>,
+    pub fault_injector: Option<FaultInjectorConfig>,
+    pub node_lifecycle: Option<NodeLifecycleConfig>,
+    pub network: Option<NetworkConfig>,
+    pub scheduling_cost_model: Option<SchedulingCostModel>,
     pub nodes: Option<Vec<NodeConfig>>,
+    pub priority_classes: Option<BTreeMap<String, PriorityClass>>,
     pub trace: Option<DatasetConfig>,
 }
 
@@ -112,6 +486,24 @@ pub struct SimulationConfig {
     pub default_node_allocation_time: f64,
     /// Count of default nodes in cloud pool (for cluster autoscaler)
     pub cloud_nodes_count: u64,
+    /// Node groups of the cluster autoscaler (a group of cloud_nodes_count default nodes if empty)
+    pub node_groups: Vec<NodeGroupConfig>,
+    /// Expander of the cluster autoscaler's algorithm (the expander set in code is used if None)
+    pub expander: Option<ExpanderConfig>,
+    /// Quotas, failures, boot times and stockouts of provisioning of nodes of node groups
+    pub cloud_provider: CloudProviderConfig,
+    /// Initial time for which the cluster autoscaler doesn't scale up the node group after the failed
+    /// provisioning (doubled after every consecutive failure)
+    pub node_group_initial_backoff: f64,
+    /// Max time of the backoff of the node group
+    pub node_group_max_backoff: f64,
+    /// Nodes with request utilization (max of shares of requested CPU and memory) below the threshold
+    /// are scaled down by the cluster autoscaler's algorithm (0.5 by default)
+    pub scale_down_utilization_threshold: f64,
+    /// Time after the arrival of new nodes when nodes aren't scaled down (600 seconds by default)
+    pub scale_down_delay_after_add: f64,
+    /// Time after the failed scale-down when nodes aren't scaled down (180 seconds by default)
+    pub scale_down_delay_after_failure: f64,
     /// Time of metrics server's interval
     pub metrics_server_interval: f64,
     /// Time of VPA interval
@@ -120,8 +512,37 @@ pub struct SimulationConfig {
     pub hpa_interval: f64,
     pub memory_pressure_threshold: f64,
     pub update_pods_resources_period: f64,
+    /// Interval of accounting of costs of nodes between pods (costs are also accounted on additions
+    /// and removals of nodes)
+    pub cost_accounting_interval: f64,
+    /// Initial delay of the container restart after OOM kill (doubled after every restart)
+    pub container_restart_initial_backoff: f64,
+    /// Max delay of the container restart after OOM kill (CrashLoopBackOff)
+    pub container_restart_max_backoff: f64,
+    /// Time of the container's work without OOM kills after which the restart delay is initial again
+    pub container_backoff_reset: f64,
+    /// Time of pulling of the image to the node by image names
+    pub image_pull_durations: BTreeMap<String, f64>,
+    /// Time of pulling of images which aren't in image_pull_durations
+    pub default_image_pull_duration: f64,
+    /// Interval of retries of the drain, while evictions are blocked by disruption budgets
+    pub drain_retry_interval: f64,
+    /// Planned drains and reboots of nodes
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+    /// Planned outages and restarts of control plane components
+    pub control_plane_outages: Vec<ComponentOutage>,
+    /// Random failures of nodes (disabled if None)
+    pub fault_injector: Option<FaultInjectorConfig>,
+    /// Heartbeats of nodes and their NotReady state (failed nodes are crashed at once if None)
+    pub node_lifecycle: Option<NodeLifecycleConfig>,
+    /// Latencies, losses and partitions of messages between components
+    pub network: NetworkConfig,
+    /// Time of the scheduler's work on a single pod
+    pub scheduling_cost_model: SchedulingCostModel,
     /// Configurations of nodes.
     pub nodes: Vec<NodeConfig>,
+    /// Priority classes by their names.
+    pub priority_classes: BTreeMap<String, PriorityClass>,
     /// Used trace dataset.
     pub trace: Option<DatasetConfig>,
 }
@@ -142,12 +563,34 @@ impl SimulationConfig {
             default_node: NodeConfig::new(8., 64., 1),
             default_node_allocation_time: 120.0,
             cloud_nodes_count: 100,
+            node_groups: Vec::default(),
+            expander: None,
+            cloud_provider: CloudProviderConfig::default(),
+            node_group_initial_backoff: 300.0,
+            node_group_max_backoff: 1800.0,
+            scale_down_utilization_threshold: 0.5,
+            scale_down_delay_after_add: 600.0,
+            scale_down_delay_after_failure: 180.0,
             metrics_server_interval: 30.0,
             vpa_interval: 30.0,
             hpa_interval: 30.0,
             memory_pressure_threshold: 0.95,
             update_pods_resources_period: 10.0,
+            cost_accounting_interval: 10.0,
+            container_restart_initial_backoff: 10.0,
+            container_restart_max_backoff: 300.0,
+            container_backoff_reset: 600.0,
+            image_pull_durations: BTreeMap::default(),
+            default_image_pull_duration: 10.0,
+            drain_retry_interval: 5.0,
+            maintenance_windows: Vec::default(),
+            control_plane_outages: Vec::default(),
+            fault_injector: None,
+            node_lifecycle: None,
+            network: NetworkConfig::default(),
+            scheduling_cost_model: SchedulingCostModel::default(),
             nodes: Vec::default(),
+            priority_classes: BTreeMap::default(),
             trace: None,
         }
     }
@@ -156,6 +599,12 @@ impl SimulationConfig {
         let raw: RawSimulationConfig = serde_yaml::from_str(
             &std::fs::read_to_string(file_name).unwrap_or_else(|_| panic!("Can't read file {}", file_name)),
         ).unwrap_or_else(|_| panic!("Can't parse YAML from file {}", file_name));
+        if let Some(fault_injector) = &raw.fault_injector {
+            fault_injector.validate();
+        }
+        for group in raw.node_groups.iter().flatten() {
+            group.validate();
+        }
 
         Self {
             message_delay: raw.message_delay.unwrap_or(0.2),
@@ -169,13 +618,59 @@ impl SimulationConfig {
             default_node: raw.default_node.unwrap_or(NodeConfig::new(8., 64., 1)),
             default_node_allocation_time: raw.default_node_allocation_time.unwrap_or(120.0),
             cloud_nodes_count: raw.cloud_nodes_count.unwrap_or(100),
+            node_groups: raw.node_groups.unwrap_or_default(),
+            expander: raw.expander,
+            cloud_provider: raw.cloud_provider.unwrap_or_default(),
+            node_group_initial_backoff: raw.node_group_initial_backoff.unwrap_or(300.0),
+            node_group_max_backoff: raw.node_group_max_backoff.unwrap_or(1800.0),
+            scale_down_utilization_threshold: raw.scale_down_utilization_threshold.unwrap_or(0.5),
+            scale_down_delay_after_add: raw.scale_down_delay_after_add.unwrap_or(600.0),
+            scale_down_delay_after_failure: raw.scale_down_delay_after_failure.unwrap_or(180.0),
             metrics_server_interval: raw.metrics_server_interval.unwrap_or(30.0),
             vpa_interval: raw.vpa_interval.unwrap_or(30.0),
             hpa_interval: raw.hpa_interval.unwrap_or(30.0),
             memory_pressure_threshold: raw.memory_pressure_threshold.unwrap_or(0.95),
             update_pods_resources_period: raw.update_pods_resources_period.unwrap_or(10.0),
+            cost_accounting_interval: raw.cost_accounting_interval.unwrap_or(10.0),
+            container_restart_initial_backoff: raw.container_restart_initial_backoff.unwrap_or(10.0),
+            container_restart_max_backoff: raw.container_restart_max_backoff.unwrap_or(300.0),
+            container_backoff_reset: raw.container_backoff_reset.unwrap_or(600.0),
+            image_pull_durations: raw.image_pull_durations.unwrap_or_default(),
+            default_image_pull_duration: raw.default_image_pull_duration.unwrap_or(10.0),
+            drain_retry_interval: raw.drain_retry_interval.unwrap_or(5.0),
+            maintenance_windows: raw.maintenance_windows.unwrap_or_default(),
+            control_plane_outages: raw.control_plane_outages.unwrap_or_default(),
+            fault_injector: raw.fault_injector,
+            node_lifecycle: raw.node_lifecycle,
+            network: raw.network.unwrap_or_default(),
+            scheduling_cost_model: raw.scheduling_cost_model.unwrap_or_default(),
             nodes: raw.nodes.unwrap_or_default(),
+            priority_classes: raw.priority_classes.unwrap_or_default(),
             trace: raw.trace,
         }
     }
+
+    /// Node groups of the cluster autoscaler: node_groups or the group of default nodes named "cloud"
+    pub fn autoscaler_node_groups(&self) -> Vec<NodeGroupConfig> {
+        if !self.node_groups.is_empty() {
+            return self.node_groups.clone();
+        }
+        vec![NodeGroupConfig {
+            name: "cloud".to_string(),
+            cpu: self.default_node.cpu,
+            memory: self.default_node.memory,
+            labels: self.default_node.labels.clone(),
+            taints: self.default_node.taints.clone(),
+            min_size: 0,
+            max_size: self.cloud_nodes_count,
+            price: self.default_node.price,
+            provisioning_time: self.default_node_allocation_time,
+            instance_type: None,
+            spot: None,
+        }]
+    }
+
+    pub fn image_pull_duration(&self, image: &str) -> f64 {
+        self.image_pull_durations.get(image).cloned().unwrap_or(self.default_image_pull_duration)
+    }
 }
\ No newline at end of file
@@ -1,6 +1,103 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufWriter, Error, Write};
 use serde::Serialize;
+use crate::pod::QoSClass;
+
+/// Preemption of victim pods on the node in favor of the preemptor pod
+#[derive(Clone, Serialize)]
+pub struct Preemption {
+    pub timestamp: f64,
+    pub preemptor_id: u64,
+    pub node_id: u32,
+    pub victim_ids: Vec<u64>,
+}
+
+/// Kill of the pod's container which exceeded its memory limit
+#[derive(Clone, Serialize)]
+pub struct OOMKill {
+    pub timestamp: f64,
+    pub pod_id: u64,
+    pub node_id: u32,
+}
+
+/// Finished rollout of the deployment's revision
+#[derive(Clone, Serialize)]
+pub struct Rollout {
+    pub deployment_id: u64,
+    pub revision: u64,
+    pub start_time: f64,
+    pub duration: f64,
+    /// Min count of available (running) replicas during the rollout.
+    pub min_available_replicas: u64,
+}
+
+/// Completion (or failure) of the job
+#[derive(Clone, Serialize)]
+pub struct JobCompletion {
+    pub job_id: u64,
+    pub cron_job_id: Option<u64>,
+    pub start_time: f64,
+    pub completion_time: f64,
+    pub succeeded: bool,
+}
+
+/// Failure of the node detected by missed heartbeats, which pods were evicted
+#[derive(Clone, Serialize)]
+pub struct NodeOutage {
+    pub node_id: u32,
+    /// Time of the failure (or of the last heartbeat, if the failure time is unknown).
+    pub failure_time: f64,
+    pub not_ready_time: f64,
+    pub eviction_time: f64,
+    pub evicted_pod_count: u64,
+}
+
+/// Results of failures injected by the fault injector
+#[derive(Clone, Default, Serialize)]
+pub struct FaultInjectionStats {
+    /// Count of injected failures of nodes.
+    pub node_failure_count: u64,
+    /// Total time of repaired nodes in the failed state.
+    pub node_downtime: f64,
+    /// Work of rescheduled pods lost by failures (sum of times from the last starts
+    /// of their running containers to failures of their nodes).
+    pub lost_pod_seconds: f64,
+    /// Count of pods returned to the scheduler from failed nodes (with the node lifecycle controller
+    /// only pods of nodes which weren't repaired before the pod eviction timeout).
+    pub rescheduled_pod_count: u64,
+}
+
+/// Cost and disruptions of nodes of node groups of one capacity type (spot or on-demand)
+#[derive(Clone, Default, Serialize)]
+pub struct CapacityStats {
+    /// Total time of nodes in the cluster (in hours).
+    pub node_hours: f64,
+    /// Node hours multiplied by prices of node groups.
+    pub cost: f64,
+    /// Count of nodes reclaimed by the cloud.
+    pub reclaimed_node_count: u64,
+    /// Count of pods on nodes when termination notices were received (they're drained).
+    pub drained_pod_count: u64,
+    /// Count of pods on nodes at their reclamation (they're killed and rescheduled).
+    pub killed_pod_count: u64,
+}
+
+/// Cost of nodes of the cluster (working and failed ones) by their hourly prices
+#[derive(Clone, Default, Serialize)]
+pub struct CostReport {
+    pub node_hours: f64,
+    pub total_cost: f64,
+    /// Cost of nodes split between deployments by requests of their pods
+    /// (average of shares of CPU and memory of the node).
+    pub deployment_costs: BTreeMap<u64, f64>,
+    /// Cost of resources allocated to pods, but not used by them.
+    pub wasted_cost: f64,
+    /// Cost of resources of working nodes not requested by pods.
+    pub idle_cost: f64,
+    /// Cost of failed nodes (they are billed, but can't host pods).
+    pub failed_cost: f64,
+}
 
 #[derive(Serialize)]
 pub struct Metrics {
@@ -19,6 +116,34 @@ pub struct Metrics {
     pub deployments_cpu_utilization: f64,
     pub deployments_memory_utilization: f64,
     pub summary_pods_count: u64,
+    pub deployments_skew: BTreeMap<u64, u64>,
+    pub preemption_count: u64,
+    /// Preemptions since the previous snapshot.
+    pub preemptions: Vec<Preemption>,
+    /// Pods evicted by nodes due to memory pressure for every QoS class.
+    pub evicted_pods_per_qos: BTreeMap<QoSClass, u64>,
+    pub oom_kill_count: u64,
+    pub restart_count: u64,
+    /// OOM kills since the previous snapshot.
+    pub oom_kills: Vec<OOMKill>,
+    /// Cpu wanted by pods, but not granted because of limits and contention on nodes.
+    pub throttled_cpu: f64,
+    /// Jobs finished since the previous snapshot.
+    pub job_completions: Vec<JobCompletion>,
+    /// Time from the start of the first job to the completion of the last finished job.
+    pub jobs_makespan: f64,
+    /// Rollouts of deployments finished since the previous snapshot.
+    pub rollouts: Vec<Rollout>,
+    /// Voluntary evictions blocked by disruption budgets.
+    pub blocked_eviction_count: u64,
+    pub fault_injection: FaultInjectionStats,
+    /// Outages of nodes with evictions of pods since the previous snapshot.
+    pub node_outages: Vec<NodeOutage>,
+    /// Messages between components lost by the network.
+    pub lost_message_count: u64,
+    pub spot: CapacityStats,
+    pub on_demand: CapacityStats,
+    pub cost: CostReport,
 }
 
 impl Metrics {
@@ -28,7 +153,14 @@ impl Metrics {
                cpu_used_load_rate: f64, memory_used_load_rate: f64,
                pod_migration_count: u64, memory_overuse_count: u64, nodes_count: u64,
                deployments_cpu_utilization: f64, deployments_memory_utilization: f64,
-               summary_pods_count: u64) -> Self {
+               summary_pods_count: u64, deployments_skew: BTreeMap<u64, u64>,
+               preemption_count: u64, preemptions: Vec<Preemption>,
+               evicted_pods_per_qos: BTreeMap<QoSClass, u64>, oom_kill_count: u64, restart_count: u64,
+               oom_kills: Vec<OOMKill>, throttled_cpu: f64,
+               job_completions: Vec<JobCompletion>, jobs_makespan: f64, rollouts: Vec<Rollout>,
+               blocked_eviction_count: u64, fault_injection: FaultInjectionStats,
+               node_outages: Vec<NodeOutage>, lost_message_count: u64, spot: CapacityStats,
+               on_demand: CapacityStats, cost: CostReport) -> Self {
         Self {
             timestamp,
             average_cpu_allocated,
@@ -45,6 +177,24 @@ impl Metrics {
             deployments_cpu_utilization,
             deployments_memory_utilization,
             summary_pods_count,
+            deployments_skew,
+            preemption_count,
+            preemptions,
+            evicted_pods_per_qos,
+            oom_kill_count,
+            restart_count,
+            oom_kills,
+            throttled_cpu,
+            job_completions,
+            jobs_makespan,
+            rollouts,
+            blocked_eviction_count,
+            fault_injection,
+            node_outages,
+            lost_message_count,
+            spot,
+            on_demand,
+            cost,
         }
     }
 }
@@ -59,12 +209,12 @@ pub struct EmptyMetricsLogger {}
 
 impl MetricsLogger for EmptyMetricsLogger {
     fn snapshot_period(&self) -> f64 {
-        return -1.0;
+        -1.0
     }
 
-    fn log_metrics(&mut self, metrics: Metrics) {}
+    fn log_metrics(&mut self, _metrics: Metrics) {}
 
-    fn save_log(&mut self, path: &str) -> Result<(), Error> {
+    fn save_log(&mut self, _path: &str) -> Result<(), Error> {
         Ok(())
     }
 }
@@ -100,7 +250,7 @@ impl MetricsLogger for StdoutMetricsLogger {
                  metrics.pod_migration_count, metrics.memory_overuse_count)
     }
 
-    fn save_log(&mut self, path: &str) -> Result<(), Error> {
+    fn save_log(&mut self, _path: &str) -> Result<(), Error> {
         Ok(())
     }
 }
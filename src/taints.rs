@@ -0,0 +1,103 @@
+//! Node taints and pod tolerations
+
+use serde::{Deserialize, Serialize};
+
+/// Well-known NoSchedule taint of cordoned nodes
+pub const TAINT_UNSCHEDULABLE: &str = "node.kubernetes.io/unschedulable";
+/// Well-known NoSchedule taint of nodes which don't send heartbeats (NotReady)
+pub const TAINT_UNREACHABLE: &str = "node.kubernetes.io/unreachable";
+
+/// Effect of the taint on pods which don't tolerate it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TaintEffect {
+    /// New pods are not scheduled to the node.
+    NoSchedule,
+    /// Scheduler tries to avoid the node.
+    PreferNoSchedule,
+    /// New pods are not scheduled to the node, running pods are evicted.
+    NoExecute,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Taint {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+    pub effect: TaintEffect,
+}
+
+impl Taint {
+    pub fn new(key: &str, value: &str, effect: TaintEffect) -> Self {
+        Self {
+            key: key.to_string(),
+            value: value.to_string(),
+            effect,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum TolerationOperator {
+    #[default]
+    Equal,
+    Exists,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Toleration {
+    /// Taint key, empty key with Exists operator tolerates all taints.
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub operator: TolerationOperator,
+    #[serde(default)]
+    pub value: String,
+    /// Tolerated effect, None means all effects.
+    #[serde(default)]
+    pub effect: Option<TaintEffect>,
+    /// How long the pod stays on the node after NoExecute taint is added (forever if None).
+    #[serde(default)]
+    pub toleration_seconds: Option<f64>,
+}
+
+impl Toleration {
+    pub fn new(key: &str, operator: TolerationOperator, value: &str, effect: Option<TaintEffect>,
+               toleration_seconds: Option<f64>) -> Self {
+        Self {
+            key: key.to_string(),
+            operator,
+            value: value.to_string(),
+            effect,
+            toleration_seconds,
+        }
+    }
+
+    pub fn tolerates(&self, taint: &Taint) -> bool {
+        if self.effect.as_ref().is_some_and(|effect| *effect != taint.effect) {
+            return false;
+        }
+        if !self.key.is_empty() && self.key != taint.key {
+            return false;
+        }
+        match self.operator {
+            TolerationOperator::Exists => true,
+            TolerationOperator::Equal => self.value == taint.value,
+        }
+    }
+}
+
+/// Returns None if the taint isn't tolerated, otherwise the time for which the pod tolerates it
+/// (the min time of matched tolerations, infinity if none of them is limited in time).
+pub fn toleration_time(tolerations: &[Toleration], taint: &Taint) -> Option<f64> {
+    tolerations.iter()
+        .filter(|toleration| toleration.tolerates(taint))
+        .map(|toleration| toleration.toleration_seconds.unwrap_or(f64::INFINITY))
+        .reduce(f64::min)
+}
+
+/// Checks that all taints with the effect are tolerated.
+pub fn tolerates_taints(tolerations: &[Toleration], taints: &[Taint], effect: TaintEffect) -> bool {
+    taints.iter()
+        .filter(|taint| taint.effect == effect)
+        .all(|taint| toleration_time(tolerations, taint).is_some())
+}
@@ -2,9 +2,12 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use dslab_core::{cast, Event, EventHandler, SimulationContext};
 use crate::api_server::APIServer;
+use crate::control_plane_outage::OutageState;
 use crate::events::autoscaler::VerticalAutoscalerCycle;
+use crate::events::outage::{ComponentOutageEnd, ComponentOutageStart};
 use crate::events::pod::PodRequestAndLimitsChange;
 use crate::metrics_server::MetricsServer;
+use crate::network::NetworkModel;
 use crate::simulation_config::SimulationConfig;
 use crate::vertical_autoscaler_algorithm::{VerticalAutoscalerAlgorithm, VPARecommendation};
 
@@ -13,6 +16,8 @@ pub struct VerticalAutoscaler {
     api_server: Rc<RefCell<APIServer>>,
     metrics_server: Rc<RefCell<MetricsServer>>,
     vpa_algorithm: Box<dyn VerticalAutoscalerAlgorithm>,
+    network: Rc<RefCell<NetworkModel>>,
+    pub outage: OutageState,
 
     ctx: SimulationContext,
     sim_config: Rc<SimulationConfig>,
@@ -20,13 +25,15 @@ pub struct VerticalAutoscaler {
 
 impl VerticalAutoscaler {
     pub fn new(api_server: Rc<RefCell<APIServer>>, metrics_server: Rc<RefCell<MetricsServer>>,
-               vpa_algorithm: Box<dyn VerticalAutoscalerAlgorithm>,
+               vpa_algorithm: Box<dyn VerticalAutoscalerAlgorithm>, network: Rc<RefCell<NetworkModel>>,
                ctx: SimulationContext, sim_config: Rc<SimulationConfig>) -> Self {
         Self {
             id: ctx.id(),
             api_server,
             metrics_server,
             vpa_algorithm,
+            network,
+            outage: OutageState::default(),
             ctx,
             sim_config
         }
@@ -48,7 +55,7 @@ impl VerticalAutoscaler {
                 continue;
             }
             let node_id = node_id.unwrap();
-            let node = api_server.working_nodes.get(&node_id);
+            let node = api_server.working_nodes.get(node_id);
             if node.is_none() {
                 continue;
             }
@@ -74,6 +81,7 @@ impl VerticalAutoscaler {
     }
 
     fn try_to_apply_recommendations(&mut self, recommendations: Vec<VPARecommendation>) {
+        let mut applied_recommendations = Vec::<(VPARecommendation, u32)>::default();
         for recommendation in recommendations {
             let api_server = self.api_server.borrow();
             let node_id = api_server.pod_to_node_map.get(&recommendation.pod_id);
@@ -81,7 +89,7 @@ impl VerticalAutoscaler {
                 continue;
             }
             let node_id = node_id.unwrap();
-            let node = api_server.working_nodes.get(&node_id);
+            let node = api_server.working_nodes.get(node_id);
             if node.is_none() {
                 continue;
             }
@@ -95,26 +103,55 @@ impl VerticalAutoscaler {
             let pod = pod.unwrap();
 
             if self.vpa_algorithm.try_to_apply_recommendation(pod, node, recommendation) {
-                self.ctx.emit(PodRequestAndLimitsChange {
-                    pod_id: recommendation.pod_id,
-                    new_requested_cpu: recommendation.new_requested_cpu,
-                    new_limit_cpu: recommendation.new_limit_cpu,
-                    new_requested_memory: recommendation.new_requested_memory,
-                    new_limit_memory: recommendation.new_limit_memory
-                }, *node_id, self.sim_config.message_delay * 2.0);
+                applied_recommendations.push((recommendation, *node_id));
             }
         }
+
+        let mut evicted_pod_ids = Vec::<u64>::default();
+        for (recommendation, node_id) in applied_recommendations {
+            // the pod is recreated, so disruption budgets must allow its eviction
+            if !self.api_server.borrow_mut().check_additional_eviction(recommendation.pod_id, &evicted_pod_ids) {
+                continue;
+            }
+            evicted_pod_ids.push(recommendation.pod_id);
+            self.network.borrow_mut().send(&self.ctx, PodRequestAndLimitsChange {
+                pod_id: recommendation.pod_id,
+                new_requested_cpu: recommendation.new_requested_cpu,
+                new_limit_cpu: recommendation.new_limit_cpu,
+                new_requested_memory: recommendation.new_requested_memory,
+                new_limit_memory: recommendation.new_limit_memory
+            }, node_id, self.sim_config.message_delay * 2.0);
+        }
+    }
+
+    /// Restart after the outage: in-memory state of the algorithm is lost
+    fn restart(&mut self) {
+        self.vpa_algorithm.reset();
+        for event in self.outage.finish() {
+            self.on(event);
+        }
     }
 }
 
 impl EventHandler for VerticalAutoscaler {
     fn on(&mut self, event: Event) {
+        let event = self.outage.intercept::<VerticalAutoscalerCycle>(event);
+        if event.is_none() {
+            return;
+        }
+        let event = event.unwrap();
         cast!(match event.data {
             VerticalAutoscalerCycle {} => {
                 let recommendations = self.collect_recommendations();
                 self.try_to_apply_recommendations(recommendations);
                 self.ctx.emit(VerticalAutoscalerCycle{}, self.id, self.sim_config.vpa_interval);
             }
+            ComponentOutageStart { queue_events } => {
+                self.outage.start(queue_events);
+            }
+            ComponentOutageEnd {} => {
+                self.restart();
+            }
         })
     }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::metrics_server::{PodSnapshot, PodStatistic};
+use crate::metrics_server::PodStatistic;
 use crate::node::Node;
 use crate::pod::Pod;
 
@@ -18,4 +18,7 @@ pub trait VerticalAutoscalerAlgorithm {
 
     fn try_to_apply_recommendation(&mut self, pod: &Pod, node: &Rc<RefCell<Node>>,
                                    recommendation: VPARecommendation) -> bool;
+
+    /// Forgets the in-memory state (on restart of the autoscaler)
+    fn reset(&mut self) {}
 }
\ No newline at end of file
@@ -1,17 +1,36 @@
+// scheduling constraints of pods are built field by field
+#![allow(clippy::field_reassign_with_default)]
+
+use std::collections::BTreeMap;
 use dslab_core::Simulation;
+use K8s_simulator::affinity::{LabelSelector, NodeAffinity, NodeSelectorOperator, NodeSelectorRequirement,
+                              NodeSelectorTerm, PodAffinity, PodAffinityTerm, PreferredSchedulingTerm,
+                              TopologySpreadConstraint, UnsatisfiableConstraintAction};
 use K8s_simulator::default_cluster_autoscaler_algorithms::default_simple_algorithm::SimpleClusterAutoscalerAlgorithm;
+use K8s_simulator::default_cluster_autoscaler_algorithms::expanders::PriceExpander;
 use K8s_simulator::default_horizontal_autoscaler_algorithms::default_horizontal_algorithm::ControlledResources::CPUOnly;
 use K8s_simulator::default_horizontal_autoscaler_algorithms::default_horizontal_algorithm::ResourcesHorizontalAutoscalerAlgorithm;
 use K8s_simulator::default_scheduler_algorithms::mrp_algorithm::MRPAlgorithm;
 use K8s_simulator::default_scheduler_algorithms::lrp_algorithm::LRPAlgorithm;
 use K8s_simulator::default_vertical_autoscaler_algorithms::default_auto_algorithm::AutoVerticalAutoscalerAlgorithm;
 use K8s_simulator::default_vertical_autoscaler_algorithms::default_auto_algorithm::ControlledValuesMode::RequestsAndLimits;
+use K8s_simulator::deployment::{DeploymentStrategy, PodTemplate};
+use K8s_simulator::disruption_budget::{DisruptionBudgetTarget, PodDisruptionBudget};
+use K8s_simulator::job::{JobSpec, JobStatus};
 use K8s_simulator::load_model::{ConstantLoadModel, DecreaseLoadModel, IncreaseLoadModel};
 use K8s_simulator::logger::StdoutLogger;
-use K8s_simulator::node::NodeState;
+use K8s_simulator::node::{NodeState, LABEL_HOSTNAME, LABEL_ZONE};
+use K8s_simulator::pod::{PodStatus, QoSClass, SchedulingConstraints};
+use K8s_simulator::priority_class::PreemptionPolicy;
+use K8s_simulator::scheduler_profile::SchedulerProfile;
 use K8s_simulator::simulation::K8sSimulation;
-use K8s_simulator::simulation_config::SimulationConfig;
-use K8s_simulator::simulation_metrics::{EmptyMetricsLogger, StdoutMetricsLogger};
+use K8s_simulator::simulation_config::{ComponentOutage, ControlPlaneComponent, ExpanderConfig,
+                                       FailureDistribution, FaultInjectorConfig, LatencyDistribution, LinkConfig,
+                                       MaintenanceWindow, NetworkPartition, NodeConfig, NodeGroupConfig,
+                                       NodeLifecycleConfig, ReclamationModel, SchedulingCostModel, SimulationConfig,
+                                       SpotConfig, StockoutWindow};
+use K8s_simulator::simulation_metrics::EmptyMetricsLogger;
+use K8s_simulator::taints::{Taint, TAINT_UNREACHABLE, TAINT_UNSCHEDULABLE, TaintEffect, Toleration, TolerationOperator};
 
 fn name_wrapper(file_name: &str) -> String {
     format!("test-configs/{}", file_name)
@@ -21,7 +40,7 @@ fn get_default_simulation_with_mrp() -> K8sSimulation {
     let sim = Simulation::new(42);
     let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
     K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
-                       sim_config, Box::new(MRPAlgorithm::new()), None, None, None)
+                       sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())), None, None, None)
 }
 
 #[test]
@@ -56,7 +75,7 @@ fn test_base_simulation_with_lrp() {
     let sim = Simulation::new(42);
     let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
     let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
-                                         sim_config, Box::new(LRPAlgorithm::new()), None, None, None);
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(LRPAlgorithm::new())), None, None, None);
 
     let node_id_1 = k8s_sim.add_node(20., 20.);
     let node_id_2 = k8s_sim.add_node(20., 20.);
@@ -176,7 +195,7 @@ fn test_cluster_scale_up() {
     let sim = Simulation::new(42);
     let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
     let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
-                                         sim_config, Box::new(MRPAlgorithm::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
                                          Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(
                                              600.0,
                                              10,
@@ -193,25 +212,80 @@ fn test_cluster_scale_up() {
     assert_ne!(k8s_sim.memory_allocated_load_rate(), 0.0);
 }
 
+#[test]
+fn test_cluster_autoscaler_outage() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.control_plane_outages = vec![ComponentOutage {
+        component: ControlPlaneComponent::ClusterAutoscaler,
+        start_time: 305.,
+        duration: 300.,
+        queue_events: false,
+    }];
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(
+                                             600.0,
+                                             10,
+                                             300.0
+                                         ))), None, None);
+    k8s_sim.submit_pod(2.0, 6.0, 2.0, 6.0, 100,
+                       Box::new(ConstantLoadModel::new(2.0)),
+                       Box::new(ConstantLoadModel::new(6.0)),
+                       1.);
+
+    // the node requested before the outage is added when the cloud provisions it
+    k8s_sim.step_for_duration(500.0);
+    assert_eq!(k8s_sim.working_nodes().len(), 1);
+    assert_ne!(k8s_sim.cpu_allocated_load_rate(), 0.0);
+}
+
 #[test]
 fn test_cluster_scale_down() {
     let sim = Simulation::new(42);
     let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
     let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
-                                         sim_config, Box::new(MRPAlgorithm::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
                                          Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(
                                              600.0,
                                              10,
                                              300.0
                                          ))), None, None);
-    let node_id_1 = k8s_sim.add_node(20., 20.);
+    k8s_sim.add_node(20., 20.);
     assert_ne!(k8s_sim.working_nodes().len(), 0);
 
-    k8s_sim.step_for_duration(700.0);
+    // the empty node is removed after it has been unneeded for scale_down_unneeded_time
+    k8s_sim.step_for_duration(500.0);
+    assert_eq!(k8s_sim.working_nodes().len(), 1);
+    k8s_sim.step_for_duration(200.0);
     assert_eq!(k8s_sim.working_nodes().len(), 0);
     assert_eq!(k8s_sim.failed_nodes().len(), 0);
 }
 
+#[test]
+fn test_scale_down_delay_after_add() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.scale_down_delay_after_add = 1000.0;
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(100.0, 10, 0.0))),
+                                         None, None);
+    let pod_id = k8s_sim.submit_pod(2.0, 2.0, 2.0, 2.0, 100,
+                                    Box::new(ConstantLoadModel::new(2.0)),
+                                    Box::new(ConstantLoadModel::new(2.0)),
+                                    1.);
+    k8s_sim.step_for_duration(300.0);
+    assert_eq!(k8s_sim.working_nodes().len(), 1);
+    k8s_sim.remove_pod(pod_id);
+
+    // the empty node isn't removed until scale_down_delay_after_add passes after its arrival
+    k8s_sim.step_for_duration(600.0);
+    assert_eq!(k8s_sim.working_nodes().len(), 1);
+    k8s_sim.step_for_duration(500.0);
+    assert_eq!(k8s_sim.working_nodes().len(), 0);
+}
+
 #[test]
 fn test_pod_load_model() {
     let mut k8s_sim = get_default_simulation_with_mrp();
@@ -219,7 +293,7 @@ fn test_pod_load_model() {
 
     let pod_id = k8s_sim.submit_pod(4.0, 10., 8.0, 20., 100,
                        Box::new(IncreaseLoadModel::new(100.0, 4.0, 10.0)),
-                       Box::new(IncreaseLoadModel::new(100.0, 10.0, 30.0)),
+                       Box::new(IncreaseLoadModel::new(100.0, 10.0, 20.0)),
                                     1.);
     k8s_sim.step_for_duration(20.0);
     assert!(k8s_sim.node(node_id).borrow().cpu_allocated < 8.0);
@@ -247,12 +321,269 @@ fn test_pod_load_model() {
     assert_eq!(k8s_sim.node(node_id).borrow().memory_allocated, 0.0);
 }
 
+#[test]
+fn test_memory_pressure_eviction_order() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id = k8s_sim.add_node(10., 10.);
+
+    let guaranteed_id = k8s_sim.submit_pod(1.0, 3.5, 1.0, 3.5, 10,
+                                           Box::new(ConstantLoadModel::new(1.0)),
+                                           Box::new(ConstantLoadModel::new(3.5)),
+                                           1.);
+    let burstable_id = k8s_sim.submit_pod(1.0, 2.0, 1.0, 6.0, 10,
+                                          Box::new(ConstantLoadModel::new(1.0)),
+                                          Box::new(IncreaseLoadModel::new(50.0, 2.0, 6.0)),
+                                          1.);
+    let best_effort_id = k8s_sim.submit_pod(0.0, 0.0, 1.0, 4.0, 100,
+                                            Box::new(ConstantLoadModel::new(1.0)),
+                                            Box::new(ConstantLoadModel::new(2.0)),
+                                            1.);
+    k8s_sim.step_for_duration(10.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods.len(), 3);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods[&guaranteed_id].qos_class(), QoSClass::Guaranteed);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods[&burstable_id].qos_class(), QoSClass::Burstable);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods[&best_effort_id].qos_class(), QoSClass::BestEffort);
+
+    // the growing burstable pod overflows the node, the best effort pod is evicted despite its priority
+    k8s_sim.step_for_duration(100.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods.len(), 2);
+    assert!(k8s_sim.node(node_id).borrow().pods.contains_key(&guaranteed_id));
+    assert!(k8s_sim.node(node_id).borrow().pods.contains_key(&burstable_id));
+    assert!(!k8s_sim.node(node_id).borrow().pods.contains_key(&best_effort_id));
+    // the evicted pod is placed again while the node isn't under pressure, but only it is evicted
+    let evicted_pods_per_qos = k8s_sim.evicted_pods_per_qos();
+    assert_eq!(evicted_pods_per_qos.keys().collect::<Vec<_>>(), vec![&QoSClass::BestEffort]);
+}
+
+#[test]
+fn test_oom_kill_and_restart() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id = k8s_sim.add_node(20., 20.);
+
+    let pod_id = k8s_sim.submit_pod(1.0, 2.0, 2.0, 4.0, 100,
+                                    Box::new(ConstantLoadModel::new(1.0)),
+                                    Box::new(IncreaseLoadModel::new(20.0, 2.0, 8.0)),
+                                    1.);
+    // the pod starts at ~5 and exceeds its memory limit every time in ~7 seconds after the start,
+    // it's killed at 20, 40 and 70 and restarted at 30 and 60 (backoff is doubled every time)
+    k8s_sim.step_for_duration(95.0);
+    let node = k8s_sim.node(node_id);
+    let pod = node.borrow().pods.get(&pod_id).unwrap().clone();
+    assert_eq!(pod.oom_kill_count, 3);
+    assert_eq!(pod.restart_count, 2);
+    assert_eq!(pod.container_start_time, Some(110.0));
+    assert_eq!(k8s_sim.oom_kill_count(), 3);
+    assert_eq!(k8s_sim.restart_count(), 2);
+
+    // the killed container doesn't use resources, but its requests are kept on the node
+    assert_eq!(node.borrow().memory_used, 0.0);
+    assert_eq!(node.borrow().memory_allocated, 2.0);
+    assert_eq!(node.borrow().cpu_allocated, 1.0);
+}
+
+#[test]
+fn test_cpu_fair_sharing() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id = k8s_sim.add_node(10., 20.);
+
+    let small_id = k8s_sim.submit_pod(2.0, 1.0, 10.0, 2.0, 100,
+                                      Box::new(IncreaseLoadModel::new(20.0, 1.0, 8.0)),
+                                      Box::new(ConstantLoadModel::new(1.0)),
+                                      1.);
+    let large_id = k8s_sim.submit_pod(6.0, 1.0, 10.0, 2.0, 100,
+                                      Box::new(ConstantLoadModel::new(8.0)),
+                                      Box::new(ConstantLoadModel::new(1.0)),
+                                      1.);
+    // both pods want 8 cpu, overcommitted cpu is split in proportion to requests
+    k8s_sim.step_for_duration(50.0);
+    let node = k8s_sim.node(node_id);
+    assert_eq!(node.borrow().pods[&small_id].cpu, 2.5);
+    assert_eq!(node.borrow().pods[&small_id].throttled_cpu, 5.5);
+    assert_eq!(node.borrow().pods[&large_id].cpu, 7.5);
+    assert_eq!(node.borrow().pods[&large_id].throttled_cpu, 0.5);
+    assert_eq!(node.borrow().cpu_allocated, 10.0);
+    assert_eq!(k8s_sim.throttled_cpu(), 6.0);
+}
+
+#[test]
+fn test_cpu_sharing_with_idle_pod() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id = k8s_sim.add_node(10., 20.);
+
+    let idle_id = k8s_sim.submit_pod(4.0, 1.0, 4.0, 2.0, 100,
+                                     Box::new(ConstantLoadModel::new(1.0)),
+                                     Box::new(ConstantLoadModel::new(1.0)),
+                                     1.);
+    let bursting_id = k8s_sim.submit_pod(2.0, 1.0, 10.0, 2.0, 100,
+                                         Box::new(ConstantLoadModel::new(10.0)),
+                                         Box::new(ConstantLoadModel::new(1.0)),
+                                         1.);
+    // cpu requested, but unused by the idle pod is given to the bursting pod
+    k8s_sim.step_for_duration(50.0);
+    let node = k8s_sim.node(node_id);
+    assert_eq!(node.borrow().pods[&idle_id].cpu, 1.0);
+    assert_eq!(node.borrow().pods[&idle_id].throttled_cpu, 0.0);
+    assert_eq!(node.borrow().pods[&bursting_id].cpu, 9.0);
+    assert_eq!(node.borrow().pods[&bursting_id].throttled_cpu, 1.0);
+    assert_eq!(node.borrow().cpu_used, 10.0);
+}
+
+#[test]
+fn test_pod_startup_and_termination() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.image_pull_durations.insert("web".to_string(), 20.0);
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let node_id = k8s_sim.add_node(20., 20.);
+
+    // cold start: the image is pulled for 20 seconds, then the container is created for 3 seconds
+    let pod_template = PodTemplate::new(2.0, 2.0, 2.0, 2.0, 100,
+                                        Box::new(ConstantLoadModel::new(2.0)),
+                                        Box::new(ConstantLoadModel::new(2.0)))
+        .with_image(Some("web".to_string()));
+    let cold_pod_id = k8s_sim.submit_pod_from_template(pod_template, 1.);
+    k8s_sim.step_for_duration(20.0);
+    let node = k8s_sim.node(node_id);
+    assert_eq!(node.borrow().pods[&cold_pod_id].status, PodStatus::ContainerCreating);
+    assert_eq!(node.borrow().cpu_allocated, 2.0);
+    assert_eq!(node.borrow().cpu_used, 0.0);
+    k8s_sim.step_for_duration(20.0);
+    assert_eq!(node.borrow().pods[&cold_pod_id].status, PodStatus::Running);
+    let cold_start_time = node.borrow().pods[&cold_pod_id].start_time;
+    assert!((cold_start_time - 25.0).abs() < 0.01);
+
+    // warm start: the image is already on the node
+    let pod_template = PodTemplate::new(2.0, 2.0, 2.0, 2.0, 100,
+                                        Box::new(ConstantLoadModel::new(2.0)),
+                                        Box::new(ConstantLoadModel::new(2.0)))
+        .with_image(Some("web".to_string()));
+    let warm_pod_id = k8s_sim.submit_pod_from_template(pod_template, 1.);
+    k8s_sim.step_for_duration(20.0);
+    let warm_start_time = node.borrow().pods[&warm_pod_id].start_time;
+    assert!((warm_start_time - 45.0).abs() < 0.01);
+
+    // the removed pod holds its requests while it's terminating
+    k8s_sim.remove_pod(warm_pod_id);
+    k8s_sim.step_for_duration(1.0);
+    assert!(!node.borrow().pods.contains_key(&warm_pod_id));
+    assert_eq!(node.borrow().terminating_pods.len(), 1);
+    assert_eq!(node.borrow().terminating_pods[0].status, PodStatus::Terminating);
+    assert_eq!(node.borrow().cpu_allocated, 4.0);
+    k8s_sim.step_for_duration(5.0);
+    assert!(node.borrow().terminating_pods.is_empty());
+    assert_eq!(node.borrow().cpu_allocated, 2.0);
+}
+
+#[test]
+fn test_jobs() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id = k8s_sim.add_node(20., 20.);
+
+    // two pods work in parallel, the third one is created after the first completion
+    let job_id = k8s_sim.submit_job(2.0, 2.0, 2.0, 2.0, 100,
+                                    Box::new(ConstantLoadModel::new(2.0)),
+                                    Box::new(ConstantLoadModel::new(2.0)),
+                                    50.0, JobSpec::new(3, 2, 6, None), 1.);
+    k8s_sim.step_for_duration(30.0);
+    assert_eq!(k8s_sim.job(job_id).unwrap().active_pod_ids.len(), 2);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods.len(), 2);
+    k8s_sim.step_for_duration(40.0);
+    let job = k8s_sim.job(job_id).unwrap();
+    assert_eq!(job.succeeded_count, 2);
+    assert_eq!(job.active_pod_ids.len(), 1);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods.len(), 1);
+    k8s_sim.step_for_duration(80.0);
+    let job = k8s_sim.job(job_id).unwrap();
+    assert_eq!(job.status, JobStatus::Succeeded);
+    assert_eq!(job.succeeded_count, 3);
+    // each pod takes 50 seconds of work, pod_start_duration and the message delays
+    assert!((job.completion_time.unwrap() - 111.0).abs() < 0.01);
+    assert!((k8s_sim.jobs_makespan() - 110.0).abs() < 0.01);
+    assert!(k8s_sim.node(node_id).borrow().pods.is_empty());
+    assert_eq!(k8s_sim.node(node_id).borrow().cpu_allocated, 0.0);
+
+    // the job fails after its deadline, its pod is removed
+    let job_id = k8s_sim.submit_job(2.0, 2.0, 2.0, 2.0, 100,
+                                    Box::new(ConstantLoadModel::new(2.0)),
+                                    Box::new(ConstantLoadModel::new(2.0)),
+                                    100.0, JobSpec::new(1, 1, 6, Some(30.0)), 0.);
+    k8s_sim.step_for_duration(40.0);
+    assert_eq!(k8s_sim.job(job_id).unwrap().status, JobStatus::Failed);
+    assert!(k8s_sim.node(node_id).borrow().pods.is_empty());
+
+    // the job fails after the OOM kill of its pod (backoff limit is 0)
+    let job_id = k8s_sim.submit_job(2.0, 2.0, 2.0, 2.0, 100,
+                                    Box::new(ConstantLoadModel::new(2.0)),
+                                    Box::new(ConstantLoadModel::new(5.0)),
+                                    100.0, JobSpec::new(1, 1, 0, None), 0.);
+    k8s_sim.step_for_duration(30.0);
+    let job = k8s_sim.job(job_id).unwrap();
+    assert_eq!(job.status, JobStatus::Failed);
+    assert_eq!(job.failed_count, 1);
+
+    // the cron job creates two jobs with the period of 100 seconds
+    let cron_job_id = k8s_sim.submit_cron_job(2.0, 2.0, 2.0, 2.0, 100,
+                                              Box::new(ConstantLoadModel::new(2.0)),
+                                              Box::new(ConstantLoadModel::new(2.0)),
+                                              10.0, JobSpec::default(), 100.0, Some(2), 0.);
+    k8s_sim.step_for_duration(300.0);
+    let job_ids = k8s_sim.cron_job_jobs(cron_job_id);
+    assert_eq!(job_ids.len(), 2);
+    for job_id in job_ids {
+        let job = k8s_sim.job(job_id).unwrap();
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.cron_job_id, Some(cron_job_id));
+    }
+}
+
+#[test]
+fn test_deployment_rollout() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id = k8s_sim.add_node(10., 20.);
+    let deployment_id = k8s_sim.submit_deployment(2.0, 2.0, 2.0, 2.0, 100,
+                                                  Box::new(ConstantLoadModel::new(1.0)),
+                                                  Box::new(ConstantLoadModel::new(1.0)),
+                                                  4, 1.);
+    k8s_sim.step_for_duration(50.0);
+    let node = k8s_sim.node(node_id);
+    assert_eq!(node.borrow().pods.len(), 4);
+
+    // old replicas are removed only after surge replicas are running
+    let pod_template = PodTemplate::new(1.0, 2.0, 1.0, 2.0, 100,
+                                        Box::new(ConstantLoadModel::new(1.0)),
+                                        Box::new(ConstantLoadModel::new(1.0)));
+    k8s_sim.update_deployment(deployment_id, pod_template,
+                              DeploymentStrategy::RollingUpdate { max_surge: 1, max_unavailable: 0 }, 0.);
+    k8s_sim.step_for_duration(200.0);
+    let rollout = k8s_sim.deployment_rollout(deployment_id).unwrap();
+    assert_eq!(rollout.revision, 2);
+    assert!(rollout.finish_time.is_some());
+    assert_eq!(rollout.min_available_replicas, 4);
+    assert_eq!(node.borrow().pods.len(), 4);
+    assert!(node.borrow().pods.values().all(|pod| pod.requested_cpu == 1.0));
+
+    // all old replicas are terminated before new ones are created
+    let pod_template = PodTemplate::new(2.0, 2.0, 2.0, 2.0, 100,
+                                        Box::new(ConstantLoadModel::new(1.0)),
+                                        Box::new(ConstantLoadModel::new(1.0)));
+    k8s_sim.update_deployment(deployment_id, pod_template, DeploymentStrategy::Recreate, 0.);
+    k8s_sim.step_for_duration(200.0);
+    let rollout = k8s_sim.deployment_rollout(deployment_id).unwrap();
+    assert_eq!(rollout.revision, 3);
+    assert!(rollout.finish_time.is_some());
+    assert_eq!(rollout.min_available_replicas, 0);
+    assert_eq!(node.borrow().pods.len(), 4);
+    assert!(node.borrow().pods.values().all(|pod| pod.requested_cpu == 2.0));
+}
+
 #[test]
 fn test_vertical_autoscaler() {
     let sim = Simulation::new(42);
     let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
     let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
-                                         sim_config, Box::new(MRPAlgorithm::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
                                          None,
                                          Some(Box::new(AutoVerticalAutoscalerAlgorithm::new(RequestsAndLimits))),
                                          None);
@@ -283,20 +614,51 @@ fn test_vertical_autoscaler() {
     assert!(k8s_sim.node(node_id).borrow().pods.get(&pod_id).unwrap().requested_memory > 10.0);
 }
 
+#[test]
+fn test_disruption_budget() {
+    let sim = Simulation::new(42);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None,
+                                         Some(Box::new(AutoVerticalAutoscalerAlgorithm::new(RequestsAndLimits))),
+                                         None);
+    let node_id = k8s_sim.add_node(20., 20.);
+    let labels = BTreeMap::from([("app".to_string(), "db".to_string())]);
+    let pod_template = PodTemplate::new(10.0, 10.0, 10.0, 10.0, 100,
+                                        Box::new(ConstantLoadModel::new(1.0)),
+                                        Box::new(ConstantLoadModel::new(1.0)))
+        .with_labels(labels.clone());
+    k8s_sim.submit_pod_from_template(pod_template, 1.);
+    let selector = LabelSelector::from_labels(labels);
+    let budget_id = k8s_sim.add_disruption_budget(
+        PodDisruptionBudget::with_min_available(DisruptionBudgetTarget::Selector { selector }, 1), 0.);
+
+    // recreation of the only pod with new requests would leave no available pods
+    k8s_sim.step_for_duration(40000.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().cpu_allocated, 10.0);
+    // the recreation is retried by every cycle of the autoscaler, but the pod is counted once
+    assert_eq!(k8s_sim.blocked_eviction_count(), 1);
+
+    k8s_sim.remove_disruption_budget(budget_id, 0.);
+    k8s_sim.step_for_duration(40000.0);
+    assert!(k8s_sim.node(node_id).borrow().cpu_allocated < 2.0);
+}
+
 #[test]
 fn test_create_deployment() {
     let sim = Simulation::new(42);
     let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
     let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
-                                         sim_config, Box::new(LRPAlgorithm::new()), None, None, None);
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(LRPAlgorithm::new())), None, None, None);
 
     let node_id_1 = k8s_sim.add_node(5., 20.);
     let node_id_2 = k8s_sim.add_node(5., 20.);
 
-    let deployment_id = k8s_sim.submit_deployment(5., 10., 5., 10., 100,
-                                                  Box::new(ConstantLoadModel::new(5.0)),
-                                                  Box::new(ConstantLoadModel::new(10.0)),
-                                                  2, 1.);
+    k8s_sim.submit_deployment(5., 10., 5., 10., 100,
+                              Box::new(ConstantLoadModel::new(5.0)),
+                              Box::new(ConstantLoadModel::new(10.0)),
+                              2, 1.);
     k8s_sim.step_for_duration(100.0);
     assert_eq!(k8s_sim.node(node_id_1).borrow().cpu_allocated, 5.0);
     assert_eq!(k8s_sim.node(node_id_1).borrow().memory_allocated, 10.0);
@@ -315,7 +677,7 @@ fn test_horizontal_autoscaler() {
             )
         );
     let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
-                                         sim_config, Box::new(LRPAlgorithm::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(LRPAlgorithm::new())),
                                          None, None, Some(horizontal_autoscaler));
     let node_id_1 = k8s_sim.add_node(5., 20.);
     let node_id_2 = k8s_sim.add_node(5., 20.);
@@ -331,4 +693,994 @@ fn test_horizontal_autoscaler() {
     assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_allocated, 5.0);
     assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_used, 2.5);
 
-}
\ No newline at end of file
+}
+#[test]
+fn test_mixed_score_plugins() {
+    let sim = Simulation::new(42);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut profile = SchedulerProfile::new();
+    profile.add_score_plugin(Box::new(MRPAlgorithm::new()), 1.0);
+    profile.add_score_plugin(Box::new(LRPAlgorithm::new()), 2.0);
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, profile, None, None, None);
+
+    let node_id_1 = k8s_sim.add_node(20., 20.);
+    let node_id_2 = k8s_sim.add_node(20., 20.);
+
+    for _ in 0..2 {
+        k8s_sim.submit_pod(4.0, 5., 4.0, 5., 100,
+                           Box::new(ConstantLoadModel::new(4.0)),
+                           Box::new(ConstantLoadModel::new(5.0)),
+                           1.);
+        k8s_sim.step_for_duration(100.0);
+    }
+    assert_eq!(k8s_sim.node(node_id_1).borrow().cpu_allocated, 4.0);
+    assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_allocated, 4.0);
+}
+
+/// Zone of the node chosen for the pod preferring zone-b after the first pod is placed on some node
+fn chosen_zone(profile: SchedulerProfile) -> String {
+    let sim = Simulation::new(42);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, profile, None, None, None);
+    k8s_sim.add_node_with_labels(20., 20., BTreeMap::from([(LABEL_ZONE.to_string(), "zone-a".to_string())]));
+    k8s_sim.add_node_with_labels(20., 20., BTreeMap::from([(LABEL_ZONE.to_string(), "zone-b".to_string())]));
+    k8s_sim.submit_pod(4.0, 5., 4.0, 5., 100,
+                       Box::new(ConstantLoadModel::new(4.0)),
+                       Box::new(ConstantLoadModel::new(5.0)),
+                       1.);
+    k8s_sim.step_for_duration(100.0);
+
+    let mut constraints = SchedulingConstraints::default();
+    constraints.node_affinity = Some(NodeAffinity {
+        required: Vec::default(),
+        preferred: vec![PreferredSchedulingTerm {
+            weight: 10,
+            preference: NodeSelectorTerm::new(vec![
+                NodeSelectorRequirement::new(LABEL_ZONE, NodeSelectorOperator::In, vec!["zone-b"]),
+            ]),
+        }],
+    });
+    let pod_template = PodTemplate::new(4.0, 5.0, 4.0, 5.0, 100,
+                                        Box::new(ConstantLoadModel::new(4.0)),
+                                        Box::new(ConstantLoadModel::new(5.0)))
+        .with_constraints(constraints);
+    let pod_id = k8s_sim.submit_pod_from_template(pod_template, 1.);
+    k8s_sim.step_for_duration(100.0);
+    let node = k8s_sim.working_nodes().values()
+        .find(|node| node.borrow().pods.contains_key(&pod_id)).unwrap().clone();
+    let zone = node.borrow().labels[LABEL_ZONE].clone();
+    zone
+}
+
+#[test]
+fn test_preferences_with_score_plugin() {
+    // MRP prefers the node of the first pod (zone-a), the preferred zone wins with default score plugins
+    assert_eq!(chosen_zone(SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new()))), "zone-b");
+    assert_eq!(chosen_zone(SchedulerProfile::from_single_score_plugin(Box::new(MRPAlgorithm::new()))), "zone-a");
+}
+
+#[test]
+fn test_node_selector_and_affinity() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id_1 = k8s_sim.add_node_with_labels(20., 20., BTreeMap::from([
+        (LABEL_ZONE.to_string(), "zone-a".to_string()),
+    ]));
+    let node_id_2 = k8s_sim.add_node_with_labels(20., 20., BTreeMap::from([
+        (LABEL_ZONE.to_string(), "zone-b".to_string()),
+        ("gpu_count".to_string(), "4".to_string()),
+    ]));
+
+    let mut constraints = SchedulingConstraints::default();
+    constraints.node_selector.insert(LABEL_ZONE.to_string(), "zone-b".to_string());
+    let pod_template = PodTemplate::new(4.0, 5.0, 4.0, 5.0, 100,
+                                        Box::new(ConstantLoadModel::new(4.0)),
+                                        Box::new(ConstantLoadModel::new(5.0)))
+        .with_constraints(constraints);
+    k8s_sim.submit_pod_from_template(pod_template, 1.);
+    k8s_sim.step_for_duration(100.0);
+    assert_eq!(k8s_sim.node(node_id_1).borrow().cpu_allocated, 0.0);
+    assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_allocated, 4.0);
+
+    // MRP prefers the second node, but it has GPUs
+    let mut constraints = SchedulingConstraints::default();
+    constraints.node_affinity = Some(NodeAffinity {
+        required: vec![NodeSelectorTerm::new(vec![
+            NodeSelectorRequirement::new("gpu_count", NodeSelectorOperator::DoesNotExist, vec![]),
+        ])],
+        preferred: Vec::default(),
+    });
+    let pod_template = PodTemplate::new(4.0, 5.0, 4.0, 5.0, 100,
+                                        Box::new(ConstantLoadModel::new(4.0)),
+                                        Box::new(ConstantLoadModel::new(5.0)))
+        .with_constraints(constraints);
+    k8s_sim.submit_pod_from_template(pod_template, 1.);
+
+    // no node has more than 4 GPUs
+    let mut constraints = SchedulingConstraints::default();
+    constraints.node_affinity = Some(NodeAffinity {
+        required: vec![NodeSelectorTerm::new(vec![
+            NodeSelectorRequirement::new("gpu_count", NodeSelectorOperator::Gt, vec!["4"]),
+        ])],
+        preferred: Vec::default(),
+    });
+    let pod_template = PodTemplate::new(1.0, 1.0, 1.0, 1.0, 100,
+                                        Box::new(ConstantLoadModel::new(1.0)),
+                                        Box::new(ConstantLoadModel::new(1.0)))
+        .with_constraints(constraints);
+    k8s_sim.submit_pod_from_template(pod_template, 1.);
+    k8s_sim.step_for_duration(100.0);
+    assert_eq!(k8s_sim.node(node_id_1).borrow().cpu_allocated, 4.0);
+    assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_allocated, 4.0);
+}
+
+#[test]
+fn test_taints_and_tolerations() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id_1 = k8s_sim.add_node(20., 20.);
+    let node_id_2 = k8s_sim.add_node(20., 20.);
+    let node_id_3 = k8s_sim.add_node(20., 20.);
+    k8s_sim.add_node_taint(node_id_3, Taint::new("dedicated", "gpu", TaintEffect::NoSchedule), 0.);
+
+    k8s_sim.submit_pod(4.0, 5.0, 4.0, 5.0, 100,
+                       Box::new(ConstantLoadModel::new(4.0)),
+                       Box::new(ConstantLoadModel::new(5.0)),
+                       1.);
+    let mut constraints = SchedulingConstraints::default();
+    constraints.tolerations.push(Toleration::new("maintenance", TolerationOperator::Exists, "",
+                                                 Some(TaintEffect::NoExecute), Some(50.0)));
+    let pod_template = PodTemplate::new(2.0, 5.0, 2.0, 5.0, 100,
+                                        Box::new(ConstantLoadModel::new(2.0)),
+                                        Box::new(ConstantLoadModel::new(5.0)))
+        .with_constraints(constraints);
+    k8s_sim.submit_pod_from_template(pod_template, 1.);
+    k8s_sim.step_for_duration(100.0);
+    assert_eq!(k8s_sim.node(node_id_1).borrow().cpu_allocated, 6.0);
+    assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_allocated, 0.0);
+
+    // the first pod is evicted at once, the second one tolerates the taint for 50 seconds
+    k8s_sim.add_node_taint(node_id_1, Taint::new("maintenance", "", TaintEffect::NoExecute), 0.);
+    k8s_sim.step_for_duration(20.0);
+    assert_eq!(k8s_sim.node(node_id_1).borrow().cpu_allocated, 2.0);
+    assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_allocated, 4.0);
+
+    k8s_sim.step_for_duration(100.0);
+    assert_eq!(k8s_sim.node(node_id_1).borrow().cpu_allocated, 0.0);
+    assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_allocated, 6.0);
+    assert_eq!(k8s_sim.node(node_id_3).borrow().cpu_allocated, 0.0);
+}
+
+#[test]
+fn test_inter_pod_affinity() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id_1 = k8s_sim.add_node(20., 20.);
+    let node_id_2 = k8s_sim.add_node(20., 20.);
+    let node_id_3 = k8s_sim.add_node(20., 20.);
+
+    // replicas of the deployment are spread across nodes despite the MRP score
+    let web_labels = BTreeMap::from([("app".to_string(), "web".to_string())]);
+    let mut web_constraints = SchedulingConstraints::default();
+    web_constraints.pod_anti_affinity = Some(PodAffinity {
+        required: vec![PodAffinityTerm::new(LabelSelector::from_labels(web_labels.clone()), LABEL_HOSTNAME)],
+        preferred: Vec::default(),
+    });
+    let pod_template = PodTemplate::new(2.0, 2.0, 2.0, 2.0, 100,
+                                        Box::new(ConstantLoadModel::new(2.0)),
+                                        Box::new(ConstantLoadModel::new(2.0)))
+        .with_labels(web_labels.clone())
+        .with_constraints(web_constraints);
+    k8s_sim.submit_deployment_from_template(pod_template, 3, 1.);
+    k8s_sim.step_for_duration(50.0);
+    assert_eq!(k8s_sim.node(node_id_1).borrow().cpu_allocated, 2.0);
+    assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_allocated, 2.0);
+    assert_eq!(k8s_sim.node(node_id_3).borrow().cpu_allocated, 2.0);
+
+    // the cache pod is placed together with some web pod, the fourth web replica can't be placed
+    let mut cache_constraints = SchedulingConstraints::default();
+    cache_constraints.pod_affinity = Some(PodAffinity {
+        required: vec![PodAffinityTerm::new(LabelSelector::from_labels(web_labels.clone()), LABEL_HOSTNAME)],
+        preferred: Vec::default(),
+    });
+    let pod_template = PodTemplate::new(3.0, 3.0, 3.0, 3.0, 100,
+                                        Box::new(ConstantLoadModel::new(3.0)),
+                                        Box::new(ConstantLoadModel::new(3.0)))
+        .with_constraints(cache_constraints);
+    k8s_sim.submit_pod_from_template(pod_template, 1.);
+    let mut web_constraints = SchedulingConstraints::default();
+    web_constraints.pod_anti_affinity = Some(PodAffinity {
+        required: vec![PodAffinityTerm::new(LabelSelector::from_labels(web_labels.clone()), LABEL_HOSTNAME)],
+        preferred: Vec::default(),
+    });
+    let pod_template = PodTemplate::new(2.0, 2.0, 2.0, 2.0, 100,
+                                        Box::new(ConstantLoadModel::new(2.0)),
+                                        Box::new(ConstantLoadModel::new(2.0)))
+        .with_labels(web_labels)
+        .with_constraints(web_constraints);
+    k8s_sim.submit_pod_from_template(pod_template, 1.);
+    k8s_sim.step_for_duration(50.0);
+    let cpu_allocated = k8s_sim.node(node_id_1).borrow().cpu_allocated +
+        k8s_sim.node(node_id_2).borrow().cpu_allocated + k8s_sim.node(node_id_3).borrow().cpu_allocated;
+    assert_eq!(cpu_allocated, 9.0);
+    for node_id in [node_id_1, node_id_2, node_id_3] {
+        let cpu_allocated = k8s_sim.node(node_id).borrow().cpu_allocated;
+        assert!(cpu_allocated == 2.0 || cpu_allocated == 5.0);
+    }
+}
+
+#[test]
+fn test_topology_spread_constraints() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id_1 = k8s_sim.add_node_with_labels(20., 20., BTreeMap::from([
+        (LABEL_ZONE.to_string(), "zone-a".to_string()),
+    ]));
+    let node_id_2 = k8s_sim.add_node_with_labels(20., 20., BTreeMap::from([
+        (LABEL_ZONE.to_string(), "zone-a".to_string()),
+    ]));
+    let node_id_3 = k8s_sim.add_node_with_labels(20., 20., BTreeMap::from([
+        (LABEL_ZONE.to_string(), "zone-b".to_string()),
+    ]));
+
+    // MRP packs replicas of the first deployment into a single node
+    let packed_id = k8s_sim.submit_deployment(2.0, 2.0, 2.0, 2.0, 100,
+                                              Box::new(ConstantLoadModel::new(2.0)),
+                                              Box::new(ConstantLoadModel::new(2.0)),
+                                              4, 1.);
+    k8s_sim.step_for_duration(50.0);
+    assert_eq!(k8s_sim.deployments_skew()[&packed_id], 4);
+
+    // replicas of the second deployment are balanced across zones
+    let web_labels = BTreeMap::from([("app".to_string(), "web".to_string())]);
+    let mut constraints = SchedulingConstraints::default();
+    constraints.topology_spread_constraints.push(TopologySpreadConstraint::new(
+        1, LABEL_ZONE, UnsatisfiableConstraintAction::DoNotSchedule, LabelSelector::from_labels(web_labels.clone())));
+    let pod_template = PodTemplate::new(1.0, 1.0, 1.0, 1.0, 100,
+                                        Box::new(ConstantLoadModel::new(1.0)),
+                                        Box::new(ConstantLoadModel::new(1.0)))
+        .with_labels(web_labels)
+        .with_constraints(constraints);
+    let spread_id = k8s_sim.submit_deployment_from_template(pod_template, 4, 1.);
+    k8s_sim.step_for_duration(50.0);
+    let zone_a_cpu_allocated = k8s_sim.node(node_id_1).borrow().cpu_allocated +
+        k8s_sim.node(node_id_2).borrow().cpu_allocated;
+    let zone_b_cpu_allocated = k8s_sim.node(node_id_3).borrow().cpu_allocated;
+    assert_eq!(zone_a_cpu_allocated + zone_b_cpu_allocated, 12.0);
+    assert_eq!(k8s_sim.deployments_skew()[&spread_id], 0);
+}
+
+#[test]
+fn test_preemption() {
+    let mut k8s_sim = get_default_simulation_with_mrp();
+    let node_id = k8s_sim.add_node(10., 20.);
+    for _ in 0..2 {
+        k8s_sim.submit_pod(4.0, 2.0, 4.0, 2.0, 10,
+                           Box::new(ConstantLoadModel::new(4.0)),
+                           Box::new(ConstantLoadModel::new(2.0)),
+                           1.);
+    }
+    k8s_sim.step_for_duration(50.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().cpu_allocated, 8.0);
+
+    // the pod which can't preempt others waits for free resources
+    let mut constraints = SchedulingConstraints::default();
+    constraints.preemption_policy = PreemptionPolicy::Never;
+    let pod_template = PodTemplate::new(6.0, 2.0, 6.0, 2.0, 100,
+                                        Box::new(ConstantLoadModel::new(6.0)),
+                                        Box::new(ConstantLoadModel::new(2.0)))
+        .with_constraints(constraints);
+    k8s_sim.submit_pod_from_template(pod_template, 1.);
+    k8s_sim.step_for_duration(50.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().cpu_allocated, 8.0);
+    assert_eq!(k8s_sim.preemption_count(), 0);
+
+    // only one of low priority pods is evicted, it can't return to the node
+    k8s_sim.submit_pod(6.0, 2.0, 6.0, 2.0, 100,
+                       Box::new(ConstantLoadModel::new(6.0)),
+                       Box::new(ConstantLoadModel::new(2.0)),
+                       1.);
+    k8s_sim.step_for_duration(100.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().cpu_allocated, 10.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods.len(), 2);
+    assert_eq!(k8s_sim.preemption_count(), 1);
+}
+
+#[test]
+fn test_deterministic_scheduling_cost() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.scheduling_cost_model = SchedulingCostModel::Deterministic {
+        per_pod_cost: 1.0,
+        per_node_filter_cost: 0.25,
+        per_node_score_cost: 0.125,
+    };
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let node_id_1 = k8s_sim.add_node(20., 20.);
+    k8s_sim.add_node(20., 20.);
+
+    let pod_id = k8s_sim.submit_pod(4.0, 5.0, 4.0, 5.0, 100,
+                                    Box::new(ConstantLoadModel::new(4.0)),
+                                    Box::new(ConstantLoadModel::new(5.0)),
+                                    1.);
+    k8s_sim.step_for_duration(10.0);
+    // submit time + 1.0 for the pod + 2 * 0.25 for filtering + 2 * 0.125 for scoring + 2 message delays
+    // + pod start duration
+    assert_eq!(k8s_sim.node(node_id_1).borrow().pods.get(&pod_id).unwrap().start_time, 6.75);
+}
+
+#[test]
+fn test_node_drain_and_maintenance() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.nodes = vec![NodeConfig { cpu: 20., memory: 20., count: 2, labels: BTreeMap::default(), taints: vec![],
+                                         price: 0.0 }];
+    sim_config.maintenance_windows = vec![MaintenanceWindow {
+        start_time: 300.,
+        nodes: vec!["node_1".to_string(), "node_2".to_string()],
+        reboot_duration: 20.,
+        max_unavailable_nodes: 1,
+    }];
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let deployment_id = k8s_sim.submit_deployment(4., 4., 4., 4., 100,
+                                                  Box::new(ConstantLoadModel::new(1.0)),
+                                                  Box::new(ConstantLoadModel::new(1.0)),
+                                                  1, 1.);
+    let budget_id = k8s_sim.add_disruption_budget(
+        PodDisruptionBudget::with_min_available(DisruptionBudgetTarget::Deployment { deployment_id }, 1), 0.);
+    k8s_sim.step_for_duration(50.0);
+
+    let node_ids: Vec<u32> = k8s_sim.working_nodes().keys().cloned().collect();
+    let drained_id = *node_ids.iter().find(|id| !k8s_sim.node(**id).borrow().pods.is_empty()).unwrap();
+    let other_id = *node_ids.iter().find(|id| **id != drained_id).unwrap();
+
+    // the only replica can't be evicted
+    k8s_sim.drain_node(drained_id, 0.);
+    k8s_sim.step_for_duration(50.0);
+    assert_eq!(k8s_sim.node(drained_id).borrow().pods.len(), 1);
+    assert!(k8s_sim.node(drained_id).borrow().taints.iter().any(|taint| taint.key == TAINT_UNSCHEDULABLE));
+    // retries of the drain and the removal of the node don't count the blocked pod again
+    k8s_sim.remove_node(drained_id, 0.);
+    k8s_sim.step_for_duration(10.0);
+    assert!(k8s_sim.working_nodes().contains_key(&drained_id));
+    assert_eq!(k8s_sim.blocked_eviction_count(), 1);
+
+    k8s_sim.remove_disruption_budget(budget_id, 0.);
+    k8s_sim.step_for_duration(50.0);
+    assert!(k8s_sim.node(drained_id).borrow().pods.is_empty());
+    assert_eq!(k8s_sim.node(other_id).borrow().cpu_allocated, 4.0);
+
+    k8s_sim.uncordon_node(drained_id, 0.);
+    k8s_sim.step_for_duration(50.0);
+    assert!(k8s_sim.node(drained_id).borrow().taints.is_empty());
+
+    // nodes are drained and rebooted one after another
+    k8s_sim.step_for_duration(300.0);
+    assert_eq!(k8s_sim.maintenance_rebooted_nodes(0), node_ids);
+    assert_eq!(k8s_sim.working_nodes().len(), 2);
+    let allocated: f32 = node_ids.iter().map(|id| k8s_sim.node(*id).borrow().cpu_allocated).sum();
+    assert_eq!(allocated, 4.0);
+    assert!(node_ids.iter().all(|id| k8s_sim.node(*id).borrow().taints.is_empty()));
+}
+
+#[test]
+fn test_fault_injector() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.fault_injector = Some(FaultInjectorConfig {
+        time_to_failure: FailureDistribution::Trace { values: vec![100.] },
+        time_to_repair: FailureDistribution::Trace { values: vec![10.] },
+        correlation_label: Some(LABEL_ZONE.to_string()),
+    });
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let zone_a = BTreeMap::from([(LABEL_ZONE.to_string(), "a".to_string())]);
+    let zone_b = BTreeMap::from([(LABEL_ZONE.to_string(), "b".to_string())]);
+    k8s_sim.add_node_with_labels(20., 20., zone_a.clone());
+    k8s_sim.add_node_with_labels(20., 20., zone_a);
+    k8s_sim.add_node_with_labels(20., 20., zone_b);
+    k8s_sim.submit_pod(4.0, 5.0, 4.0, 5.0, 100,
+                       Box::new(ConstantLoadModel::new(4.0)),
+                       Box::new(ConstantLoadModel::new(5.0)),
+                       1.);
+
+    // both zones fail at once
+    k8s_sim.step_for_duration(105.0);
+    assert_eq!(k8s_sim.working_nodes().len(), 0);
+    assert_eq!(k8s_sim.fault_injection_stats().node_failure_count, 3);
+
+    // the node added later forms its own failure unit
+    let zone_c = BTreeMap::from([(LABEL_ZONE.to_string(), "c".to_string())]);
+    k8s_sim.add_node_with_labels(20., 20., zone_c);
+    k8s_sim.step_for_duration(145.0);
+    assert_eq!(k8s_sim.working_nodes().len(), 4);
+    let stats = k8s_sim.fault_injection_stats();
+    assert_eq!(stats.node_failure_count, 7);
+    assert_eq!(stats.node_downtime, 70.0);
+    // the pod works 96 seconds before two failures, the third one happens while its container is created
+    assert_eq!(stats.rescheduled_pod_count, 3);
+    assert!((stats.lost_pod_seconds - 192.0).abs() < 0.01);
+}
+
+#[test]
+fn test_node_lifecycle_controller() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.node_lifecycle = Some(NodeLifecycleConfig {
+        heartbeat_interval: 10.,
+        monitor_period: 5.,
+        grace_period: 40.,
+        pod_eviction_timeout: 60.,
+    });
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let node_id_1 = k8s_sim.add_node(20., 20.);
+    let node_id_2 = k8s_sim.add_node(20., 20.);
+    k8s_sim.submit_pod(4.0, 5.0, 4.0, 5.0, 100,
+                       Box::new(ConstantLoadModel::new(4.0)),
+                       Box::new(ConstantLoadModel::new(5.0)),
+                       1.);
+    k8s_sim.step_for_duration(50.0);
+    let failed_id = if k8s_sim.node(node_id_1).borrow().pods.is_empty() { node_id_2 } else { node_id_1 };
+    let other_id = if failed_id == node_id_1 { node_id_2 } else { node_id_1 };
+
+    // the failure isn't known to the control plane yet
+    k8s_sim.crash_node(failed_id, 50.);
+    k8s_sim.step_for_duration(60.0);
+    assert!(k8s_sim.working_nodes().contains_key(&failed_id));
+    assert!(k8s_sim.not_ready_nodes().is_empty());
+
+    // NotReady after the grace period, pods aren't evicted before the timeout
+    k8s_sim.step_for_duration(40.0);
+    assert!(k8s_sim.not_ready_nodes().contains_key(&failed_id));
+    assert!(k8s_sim.node(failed_id).borrow().taints.iter().any(|taint| taint.key == TAINT_UNREACHABLE));
+    assert_eq!(k8s_sim.node(failed_id).borrow().pods.len(), 1);
+
+    // the pod doesn't tolerate the unreachable NoExecute taint longer than the timeout
+    k8s_sim.step_for_duration(80.0);
+    assert!(k8s_sim.node(failed_id).borrow().pods.is_empty());
+    assert_eq!(k8s_sim.node(other_id).borrow().cpu_allocated, 4.0);
+
+    // the node is Ready again after the recovery
+    k8s_sim.recover_node(failed_id, 0.);
+    k8s_sim.step_for_duration(50.0);
+    assert!(k8s_sim.working_nodes().contains_key(&failed_id));
+    assert!(k8s_sim.not_ready_nodes().is_empty());
+    assert!(k8s_sim.node(failed_id).borrow().taints.is_empty());
+}
+
+#[test]
+fn test_unreachable_taint_tolerations() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.node_lifecycle = Some(NodeLifecycleConfig {
+        heartbeat_interval: 10.,
+        monitor_period: 5.,
+        grace_period: 40.,
+        pod_eviction_timeout: 60.,
+    });
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let node_id = k8s_sim.add_node(20., 20.);
+    let mut pod_ids = Vec::new();
+    for toleration_seconds in [None, Some(Some(200.)), Some(None)] {
+        let mut constraints = SchedulingConstraints::default();
+        if let Some(toleration_seconds) = toleration_seconds {
+            constraints.tolerations.push(Toleration::new(TAINT_UNREACHABLE, TolerationOperator::Exists, "",
+                                                         Some(TaintEffect::NoExecute), toleration_seconds));
+        }
+        let pod_template = PodTemplate::new(2.0, 2.0, 2.0, 2.0, 100,
+                                            Box::new(ConstantLoadModel::new(1.0)),
+                                            Box::new(ConstantLoadModel::new(1.0)))
+            .with_constraints(constraints);
+        pod_ids.push(k8s_sim.submit_pod_from_template(pod_template, 1.));
+    }
+    k8s_sim.step_for_duration(10.0);
+    k8s_sim.crash_node(node_id, 0.);
+    let other_id = k8s_sim.add_node(20., 20.);
+
+    // NotReady after the grace period, the pod without tolerations is evicted after the timeout
+    k8s_sim.step_for_duration(140.0);
+    assert!(k8s_sim.not_ready_nodes().contains_key(&node_id));
+    assert!(k8s_sim.node(other_id).borrow().pods.contains_key(&pod_ids[0]));
+    let start_times = pod_ids[1..].iter()
+        .map(|pod_id| k8s_sim.node(node_id).borrow().pods.get(pod_id).unwrap().start_time)
+        .collect::<Vec<f64>>();
+
+    // tolerationSeconds of the toleration replace the timeout (the evicted pod tolerates the node
+    // and can be placed there again), the unlimited toleration keeps the pod
+    k8s_sim.step_for_duration(150.0);
+    let start_time = |pod_id: &u64| [node_id, other_id].iter()
+        .find_map(|node_id| k8s_sim.node(*node_id).borrow().pods.get(pod_id).map(|pod| pod.start_time))
+        .unwrap();
+    assert!(start_time(&pod_ids[1]) > start_times[0]);
+    assert_eq!(start_time(&pod_ids[2]), start_times[1]);
+}
+
+#[test]
+fn test_eviction_through_lossy_link() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.network.links = vec![LinkConfig {
+        from: Some("node_*".to_string()),
+        to: Some("api_server".to_string()),
+        latency: None,
+        jitter: 0.0,
+        loss_probability: 1.0,
+    }];
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let node_id = k8s_sim.add_node(10., 10.);
+    k8s_sim.submit_pod(1.0, 3.5, 1.0, 3.5, 10,
+                       Box::new(ConstantLoadModel::new(1.0)),
+                       Box::new(ConstantLoadModel::new(3.5)),
+                       1.);
+    let burstable_id = k8s_sim.submit_pod(1.0, 2.0, 1.0, 6.0, 10,
+                                          Box::new(ConstantLoadModel::new(1.0)),
+                                          Box::new(IncreaseLoadModel::new(50.0, 2.0, 6.0)),
+                                          1.);
+    let best_effort_id = k8s_sim.submit_pod(0.0, 0.0, 1.0, 4.0, 100,
+                                            Box::new(ConstantLoadModel::new(1.0)),
+                                            Box::new(ConstantLoadModel::new(2.0)),
+                                            1.);
+    k8s_sim.step_for_duration(10.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods.len(), 3);
+    k8s_sim.cordon_node(node_id, 0.);
+    let other_id = k8s_sim.add_node(10., 10.);
+
+    // the evicted pod isn't lost by the link, it's rescheduled on the free node
+    k8s_sim.step_for_duration(100.0);
+    assert!(k8s_sim.node(node_id).borrow().pods.contains_key(&burstable_id));
+    assert!(k8s_sim.node(other_id).borrow().pods.contains_key(&best_effort_id));
+}
+
+#[test]
+fn test_network_partition() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.node_lifecycle = Some(NodeLifecycleConfig::default());
+    sim_config.network.links = vec![LinkConfig {
+        from: Some("node_*".to_string()),
+        to: Some("api_server".to_string()),
+        latency: Some(LatencyDistribution::Constant { value: 2.0 }),
+        jitter: 0.0,
+        loss_probability: 0.0,
+    }];
+    sim_config.network.partitions = vec![NetworkPartition {
+        start_time: 100.,
+        duration: 60.,
+        components: vec!["node_2".to_string()],
+        peers: vec![],
+    }];
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let node_id_1 = k8s_sim.add_node(20., 20.);
+    let node_id_2 = k8s_sim.add_node(20., 20.);
+    let mut constraints = SchedulingConstraints::default();
+    constraints.node_selector.insert(LABEL_HOSTNAME.to_string(), "node_2".to_string());
+    let pod_template = PodTemplate::new(4.0, 5.0, 4.0, 5.0, 100,
+                                        Box::new(ConstantLoadModel::new(4.0)),
+                                        Box::new(ConstantLoadModel::new(5.0)))
+        .with_constraints(constraints);
+    let pod_id = k8s_sim.submit_pod_from_template(pod_template, 1.);
+
+    // heartbeats of the isolated node are lost
+    k8s_sim.step_for_duration(140.0);
+    assert!(k8s_sim.lost_message_count() > 0);
+    assert!(k8s_sim.not_ready_nodes().contains_key(&node_id_2));
+    assert!(!k8s_sim.not_ready_nodes().contains_key(&node_id_1));
+
+    // the node is Ready after the end of the partition, its pods aren't evicted
+    k8s_sim.step_for_duration(60.0);
+    assert!(k8s_sim.not_ready_nodes().is_empty());
+    assert!(k8s_sim.working_nodes().contains_key(&node_id_2));
+    assert_eq!(k8s_sim.node(node_id_2).borrow().pods.get(&pod_id).unwrap().status, PodStatus::Running);
+    assert!(k8s_sim.node(node_id_1).borrow().pods.is_empty());
+}
+
+#[test]
+fn test_lossy_network_link() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.network.links = vec![LinkConfig {
+        from: None,
+        to: None,
+        latency: Some(LatencyDistribution::Uniform { min: 0.5, max: 1.5 }),
+        jitter: 0.5,
+        loss_probability: 0.3,
+    }];
+    sim_config.node_lifecycle = Some(NodeLifecycleConfig::default());
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let node_id = k8s_sim.add_node(20., 20.);
+    for _ in 0..4 {
+        k8s_sim.submit_pod(4.0, 4.0, 4.0, 4.0, 100,
+                           Box::new(ConstantLoadModel::new(4.0)),
+                           Box::new(ConstantLoadModel::new(4.0)),
+                           1.);
+    }
+
+    // heartbeats are lost and delayed, but binding of pods to the node is retried until it's delivered
+    k8s_sim.step_for_duration(100.0);
+    assert!(k8s_sim.lost_message_count() > 0);
+    assert_eq!(k8s_sim.node(node_id).borrow().pods.len(), 4);
+    assert_eq!(k8s_sim.node(node_id).borrow().cpu_allocated, 16.0);
+}
+
+#[test]
+fn test_scheduler_outage() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.control_plane_outages = vec![ComponentOutage {
+        component: ControlPlaneComponent::Scheduler,
+        start_time: 10.,
+        duration: 60.,
+        queue_events: false,
+    }];
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    let node_id = k8s_sim.add_node(20., 20.);
+    k8s_sim.submit_pod(4.0, 5.0, 4.0, 5.0, 100,
+                       Box::new(ConstantLoadModel::new(4.0)),
+                       Box::new(ConstantLoadModel::new(5.0)),
+                       20.);
+
+    // the pod submitted during the outage isn't lost, it's scheduled after the restart
+    k8s_sim.step_for_duration(65.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().cpu_allocated, 0.0);
+    k8s_sim.step_for_duration(15.0);
+    assert_eq!(k8s_sim.node(node_id).borrow().cpu_allocated, 4.0);
+}
+
+#[test]
+fn test_cluster_autoscaler_node_groups() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.node_groups = vec![
+        NodeGroupConfig {
+            name: "small".to_string(),
+            cpu: 4.,
+            memory: 8.,
+            labels: BTreeMap::default(),
+            taints: vec![],
+            min_size: 1,
+            max_size: 5,
+            price: 0.1,
+            provisioning_time: 60.,
+            instance_type: None,
+            spot: None,
+        },
+        NodeGroupConfig {
+            name: "large".to_string(),
+            cpu: 16.,
+            memory: 32.,
+            labels: BTreeMap::default(),
+            taints: vec![],
+            min_size: 0,
+            max_size: 2,
+            price: 0.5,
+            provisioning_time: 100.,
+            instance_type: None,
+            spot: None,
+        },
+    ];
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(
+                                             600.0,
+                                             10,
+                                             0.0
+                                         ))), None, None);
+    assert_eq!(k8s_sim.node_group_sizes()["small"], 1);
+    assert_eq!(k8s_sim.working_nodes().len(), 1);
+
+    // only the large group fits the pod, a single node is provisioned for it
+    k8s_sim.submit_pod(8.0, 16.0, 8.0, 16.0, 100,
+                       Box::new(ConstantLoadModel::new(8.0)),
+                       Box::new(ConstantLoadModel::new(16.0)),
+                       1.);
+    k8s_sim.step_for_duration(50.0);
+    assert_eq!(k8s_sim.node_group_sizes()["large"], 1);
+    assert_eq!(k8s_sim.node_group_sizes()["small"], 1);
+    assert_eq!(k8s_sim.working_nodes().len(), 1);
+
+    k8s_sim.step_for_duration(100.0);
+    assert_eq!(k8s_sim.node_group_sizes()["large"], 1);
+    assert_eq!(k8s_sim.working_nodes().len(), 2);
+    let large_node = k8s_sim.working_nodes().values()
+        .find(|node| node.borrow().cpu_total == 16.).unwrap().clone();
+    assert_eq!(large_node.borrow().cpu_allocated, 8.0);
+}
+
+#[test]
+fn test_utilization_based_scale_down() {
+    let sim = Simulation::new(42);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(
+                                             600.0,
+                                             10,
+                                             300.0
+                                         ))), None, None);
+    let node_id_1 = k8s_sim.add_node(20., 20.);
+    k8s_sim.submit_pod(4.0, 4.0, 4.0, 4.0, 100,
+                       Box::new(ConstantLoadModel::new(4.0)),
+                       Box::new(ConstantLoadModel::new(4.0)),
+                       1.);
+    k8s_sim.step_for_duration(10.0);
+    let node_id_2 = k8s_sim.add_node(20., 20.);
+    assert_eq!(k8s_sim.node(node_id_1).borrow().cpu_allocated, 4.0);
+
+    // the pod of the underutilized node can be moved to another node, which is kept for it
+    k8s_sim.step_for_duration(700.0);
+    assert_eq!(k8s_sim.working_nodes().len(), 1);
+    assert!(k8s_sim.working_nodes().contains_key(&node_id_2));
+    assert_eq!(k8s_sim.node(node_id_2).borrow().cpu_allocated, 4.0);
+}
+
+fn expander_simulation(expander: ExpanderConfig) -> K8sSimulation {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let group = |name: &str, cpu: f32, memory: f64, price: f64| NodeGroupConfig {
+        name: name.to_string(),
+        cpu,
+        memory,
+        labels: BTreeMap::default(),
+        taints: vec![],
+        min_size: 0,
+        max_size: 2,
+        price,
+        provisioning_time: 30.,
+        instance_type: None,
+        spot: None,
+    };
+    sim_config.node_groups = vec![group("large", 16., 32., 0.8), group("small", 4., 8., 0.3),
+                                  group("cheap", 16., 32., 0.1)];
+    sim_config.expander = Some(expander);
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(600.0, 10, 0.0))),
+                                         None, None);
+    k8s_sim.submit_pod(2.0, 4.0, 2.0, 4.0, 100,
+                       Box::new(ConstantLoadModel::new(2.0)),
+                       Box::new(ConstantLoadModel::new(4.0)),
+                       1.);
+    k8s_sim.step_for_duration(60.0);
+    k8s_sim
+}
+
+#[test]
+fn test_cluster_autoscaler_expanders() {
+    // every group fits the pod, the expander chooses one of them
+    let k8s_sim = expander_simulation(ExpanderConfig::Price);
+    let sizes = k8s_sim.node_group_sizes();
+    assert_eq!((sizes["large"], sizes["small"], sizes["cheap"]), (0, 0, 1));
+    assert_eq!(k8s_sim.working_nodes().values().next().unwrap().borrow().cpu_allocated, 2.0);
+
+    let k8s_sim = expander_simulation(ExpanderConfig::LeastWaste);
+    let sizes = k8s_sim.node_group_sizes();
+    assert_eq!((sizes["large"], sizes["small"], sizes["cheap"]), (0, 1, 0));
+
+    let priorities = BTreeMap::from([("large".to_string(), 10), ("cheap".to_string(), 5)]);
+    let k8s_sim = expander_simulation(ExpanderConfig::Priority { priorities });
+    let sizes = k8s_sim.node_group_sizes();
+    assert_eq!((sizes["large"], sizes["small"], sizes["cheap"]), (1, 0, 0));
+
+    // the random expander draws from the seeded simulation RNG
+    let sizes = expander_simulation(ExpanderConfig::Random).node_group_sizes();
+    assert_eq!(sizes, expander_simulation(ExpanderConfig::Random).node_group_sizes());
+    assert_eq!(sizes.values().sum::<u64>(), 1);
+}
+
+#[test]
+fn test_cloud_provider_quotas_and_stockouts() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let group = |name: &str, cpu: f32, memory: f64, provisioning_time: f64| NodeGroupConfig {
+        name: name.to_string(),
+        cpu,
+        memory,
+        labels: BTreeMap::default(),
+        taints: vec![],
+        min_size: 0,
+        max_size: 5,
+        price: 0.0,
+        provisioning_time,
+        instance_type: None,
+        spot: None,
+    };
+    sim_config.node_groups = vec![group("small", 4., 8., 60.), group("large", 16., 32., 100.)];
+    sim_config.cloud_provider.quotas.insert("small".to_string(), 2);
+    sim_config.cloud_provider.stockouts.push(StockoutWindow {
+        instance_type: "large".to_string(),
+        start_time: 0.0,
+        duration: 250.0,
+    });
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(600.0, 10, 0.0))),
+                                         None, None);
+    for _ in 0..3 {
+        k8s_sim.submit_pod(4.0, 4.0, 4.0, 4.0, 100,
+                           Box::new(ConstantLoadModel::new(4.0)),
+                           Box::new(ConstantLoadModel::new(4.0)),
+                           1.);
+    }
+
+    // the third small node exceeds the quota, the large group is out of stock, both groups are backed off
+    k8s_sim.step_for_duration(200.0);
+    let sizes = k8s_sim.node_group_sizes();
+    assert_eq!((sizes["small"], sizes["large"]), (2, 0));
+    assert_eq!(k8s_sim.working_nodes().len(), 2);
+
+    // the large node is provisioned after the stockout and the backoff
+    k8s_sim.step_for_duration(400.0);
+    let sizes = k8s_sim.node_group_sizes();
+    assert_eq!((sizes["small"], sizes["large"]), (2, 1));
+    let cpu_allocated: f32 = k8s_sim.working_nodes().values().map(|node| node.borrow().cpu_allocated).sum();
+    assert_eq!(cpu_allocated, 12.0);
+}
+
+#[test]
+fn test_spot_node_reclamation() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let group = |name: &str, price: f64, max_size: u64, spot: Option<SpotConfig>| NodeGroupConfig {
+        name: name.to_string(),
+        cpu: 4.,
+        memory: 8.,
+        labels: BTreeMap::default(),
+        taints: vec![],
+        min_size: 0,
+        max_size,
+        price,
+        provisioning_time: 30.,
+        instance_type: None,
+        spot,
+    };
+    let spot = SpotConfig {
+        reclamation: ReclamationModel::Trace { values: vec![100.] },
+        termination_notice: 30.,
+    };
+    sim_config.node_groups = vec![group("spot", 0.1, 1, Some(spot)), group("regular", 1.0, 2, None)];
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(600.0, 10, 0.0)
+                                             .with_expander(Box::new(PriceExpander::new())))), None, None);
+    k8s_sim.submit_pod(2.0, 4.0, 2.0, 4.0, 100,
+                       Box::new(ConstantLoadModel::new(2.0)),
+                       Box::new(ConstantLoadModel::new(4.0)),
+                       1.);
+    k8s_sim.step_for_duration(100.0);
+    assert_eq!(k8s_sim.node_group_sizes()["spot"], 1);
+    assert_eq!(k8s_sim.working_nodes().values().next().unwrap().borrow().cpu_allocated, 2.0);
+
+    // the pod is drained from the spot node on the termination notice and moves to the on-demand node
+    k8s_sim.step_for_duration(200.0);
+    let sizes = k8s_sim.node_group_sizes();
+    assert_eq!((sizes["spot"], sizes["regular"]), (0, 1));
+    assert_eq!(k8s_sim.working_nodes().values().next().unwrap().borrow().cpu_allocated, 2.0);
+
+    let (spot, on_demand) = k8s_sim.capacity_stats();
+    assert_eq!((spot.reclaimed_node_count, spot.drained_pod_count, spot.killed_pod_count), (1, 1, 0));
+    assert!((spot.node_hours * 3600.0 - 100.0).abs() < 1e-6);
+    assert!(on_demand.node_hours > 0.0);
+    assert!(spot.cost < on_demand.cost);
+}
+
+#[test]
+#[should_panic(expected = "has no values")]
+fn test_empty_reclamation_trace() {
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.node_groups = vec![NodeGroupConfig {
+        name: "spot".to_string(),
+        cpu: 4.,
+        memory: 8.,
+        labels: BTreeMap::default(),
+        taints: vec![],
+        min_size: 0,
+        max_size: 1,
+        price: 0.1,
+        provisioning_time: 30.,
+        instance_type: None,
+        spot: Some(SpotConfig { reclamation: ReclamationModel::Trace { values: vec![] }, termination_notice: 30. }),
+    }];
+    K8sSimulation::new(Simulation::new(42), Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                       sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                       Some(Box::new(SimpleClusterAutoscalerAlgorithm::new(600.0, 10, 0.0))), None, None);
+}
+
+#[test]
+fn test_cost_report() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut node_config = NodeConfig::new(10., 10., 1);
+    node_config.price = 3.6;
+    sim_config.nodes = vec![node_config];
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    // the replica requests half of the node and uses half of its requests
+    let deployment_id = k8s_sim.submit_deployment(5., 5., 5., 5., 100,
+                                                  Box::new(ConstantLoadModel::new(2.5)),
+                                                  Box::new(ConstantLoadModel::new(2.5)),
+                                                  1, 1.);
+    k8s_sim.step_until_time(3600.0);
+
+    let cost = k8s_sim.cost_report();
+    assert!((cost.node_hours - 1.0).abs() < 1e-6);
+    assert!((cost.total_cost - 3.6).abs() < 1e-6);
+    assert!((cost.deployment_costs[&deployment_id] - 1.8).abs() < 0.05);
+    assert!((cost.wasted_cost - 0.9).abs() < 0.05);
+    assert!((cost.idle_cost - 1.8).abs() < 0.05);
+}
+
+#[test]
+fn test_cost_integration_and_failed_nodes() {
+    let sim = Simulation::new(42);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut node_config = NodeConfig::new(10., 10., 2);
+    node_config.price = 3.6;
+    sim_config.nodes = vec![node_config];
+    sim_config.cost_accounting_interval = 3600.0;
+    let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                         sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                         None, None, None);
+    // the pod requests half of the node for half an hour, shorter than the accounting interval
+    let pod_id = k8s_sim.submit_pod(5., 5., 5., 5., 100,
+                                    Box::new(ConstantLoadModel::new(5.)),
+                                    Box::new(ConstantLoadModel::new(5.)),
+                                    1.);
+    k8s_sim.step_until_time(1800.0);
+    k8s_sim.remove_pod(pod_id);
+    k8s_sim.step_until_time(2700.0);
+    // the failed node is billed for the last quarter of the hour, but its cost isn't idle
+    let node_id = *k8s_sim.working_nodes().keys().last().unwrap();
+    k8s_sim.crash_node(node_id, 0.0);
+    k8s_sim.step_until_time(3600.0);
+
+    let cost = k8s_sim.cost_report();
+    assert!((cost.node_hours - 2.0).abs() < 1e-6);
+    assert!((cost.total_cost - 7.2).abs() < 1e-6);
+    assert!((cost.failed_cost - 0.9).abs() < 1e-6);
+    assert!((cost.idle_cost - 5.4).abs() < 0.05);
+}
+
+#[test]
+fn test_cost_of_recovered_nodes() {
+    // nodes failed without node_lifecycle are moved to the failed ones, with it they stay working
+    for node_lifecycle in [None, Some(NodeLifecycleConfig::default())] {
+        let sim = Simulation::new(42);
+        let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+        let mut node_config = NodeConfig::new(10., 10., 2);
+        node_config.price = 3.6;
+        sim_config.nodes = vec![node_config];
+        sim_config.cost_accounting_interval = 3600.0;
+        sim_config.node_lifecycle = node_lifecycle;
+        let mut k8s_sim = K8sSimulation::new(sim, Box::new(EmptyMetricsLogger {}), Box::new(StdoutLogger::new()),
+                                             sim_config, SchedulerProfile::from_score_plugin(Box::new(MRPAlgorithm::new())),
+                                             None, None, None);
+        // the node is failed for a quarter of the hour between accountings
+        let node_id = *k8s_sim.working_nodes().keys().last().unwrap();
+        k8s_sim.step_until_time(900.0);
+        k8s_sim.crash_node(node_id, 0.0);
+        k8s_sim.step_until_time(1800.0);
+        k8s_sim.recover_node(node_id, 0.0);
+        k8s_sim.step_until_time(3600.0);
+
+        let cost = k8s_sim.cost_report();
+        assert!((cost.total_cost - 7.2).abs() < 1e-6);
+        assert!((cost.failed_cost - 0.9).abs() < 0.01);
+        assert!((cost.idle_cost - 6.3).abs() < 0.01);
+    }
+}